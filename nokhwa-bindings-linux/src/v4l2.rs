@@ -1,28 +1,73 @@
 use nokhwa_core::camera::{Camera, Capture, Setting};
-use nokhwa_core::control::{ControlDescription, ControlFlags, ControlId, ControlValue, ControlValueDescriptor, Controls};
-use nokhwa_core::error::{NokhwaError, NokhwaResult};
+#[cfg(feature = "async")]
+use nokhwa_core::camera::AsyncSetting;
+use nokhwa_core::conversion::{ColorMatrix, QuantRange};
+use nokhwa_core::control::{ControlDescription, ControlFlags, ControlId, ControlValue, ControlValueDescriptor, Controls, Orientation};
+use nokhwa_core::error::{ErrorSource, NokhwaError, NokhwaResult};
+use nokhwa_core::format_request::FormatLimits;
 use nokhwa_core::frame_format::FrameFormat;
-use nokhwa_core::platform::{Backends, PlatformTrait};
+use nokhwa_core::platform::{Backends, PermissionStatus, PlatformTrait};
 use nokhwa_core::ranges::Range;
-use nokhwa_core::stream::{StreamHandle, StreamConfiguration, StreamInnerTrait};
+use nokhwa_core::stream::{event_channel, CaptureOutcome, Event, ReconnectPolicy, StreamHandle, StreamConfiguration};
 use nokhwa_core::types::{CameraFormat, CameraIndex, CameraInformation, FrameRate, Resolution};
+use std::any::Any;
 use std::collections::hash_map::{Keys, Values};
 use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::num::NonZeroI32;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::thread::{sleep, JoinHandle};
-use std::time::Duration;
-use flume::{Sender, Receiver, unbounded, bounded};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use flume::{Receiver, Sender, TryRecvError, TrySendError, bounded, unbounded};
 use v4l::context::enum_devices;
-use v4l::control::{Description, Flags, MenuItem, Type, Value};
-use v4l::frameinterval::FrameIntervalEnum;
-use v4l::video::output::Parameters;
-use v4l::video::Output;
-use v4l::{Capabilities, Device, Format, FourCC, Fraction, FrameInterval};
-use v4l2_sys_mit::{V4L2_CID_AUTO_EXPOSURE_BIAS, V4L2_CID_AUTO_FOCUS_RANGE, V4L2_CID_AUTO_FOCUS_STATUS, V4L2_CID_AUTO_N_PRESET_WHITE_BALANCE, V4L2_CID_AUTO_WHITE_BALANCE, V4L2_CID_CAMERA_ORIENTATION, V4L2_CID_EXPOSURE_ABSOLUTE, V4L2_CID_EXPOSURE_AUTO, V4L2_CID_EXPOSURE_METERING, V4L2_CID_FLASH_LED_MODE, V4L2_CID_FLASH_STROBE, V4L2_CID_FLASH_STROBE_STATUS, V4L2_CID_FLASH_STROBE_STOP, V4L2_CID_FOCUS_ABSOLUTE, V4L2_CID_FOCUS_AUTO, V4L2_CID_FOCUS_RELATIVE, V4L2_CID_IRIS_ABSOLUTE, V4L2_CID_IRIS_RELATIVE, V4L2_CID_ISO_SENSITIVITY, V4L2_CID_ISO_SENSITIVITY_AUTO, V4L2_CID_ZOOM_ABSOLUTE, V4L2_CID_ZOOM_CONTINUOUS, V4L2_CID_ZOOM_RELATIVE};
-use v4l::io::traits::OutputStream;
+use v4l::control::{Control, Description, Flags, MenuItem, Type, Value};
+use v4l::frameinterval::{FrameIntervalEnum, Stepwise as FrameIntervalStepwise};
+use v4l::framesize::{FrameSizeEnum, Stepwise as FrameSizeStepwise};
+use v4l::video::capture::Parameters;
+use v4l::video::Capture as _;
+use v4l::buffer::Metadata as BufferMetadata;
+use v4l::capability::Flags as CapabilityFlags;
+use v4l::device::Handle;
+use v4l::v4l_sys::{v4l2_buffer, v4l2_exportbuffer};
+use v4l::format::{Colorspace, Quantization};
+use v4l::{Capabilities, Device, Format, FourCC, Fraction};
+use v4l2_sys_mit::{V4L2_CID_AUTO_EXPOSURE_BIAS, V4L2_CID_AUTO_FOCUS_RANGE, V4L2_CID_AUTO_FOCUS_STATUS, V4L2_CID_AUTO_N_PRESET_WHITE_BALANCE, V4L2_CID_AUTO_WHITE_BALANCE, V4L2_CID_BACKLIGHT_COMPENSATION, V4L2_CID_BRIGHTNESS, V4L2_CID_CAMERA_ORIENTATION, V4L2_CID_CONTRAST, V4L2_CID_EXPOSURE_ABSOLUTE, V4L2_CID_EXPOSURE_AUTO, V4L2_CID_EXPOSURE_METERING, V4L2_CID_FLASH_LED_MODE, V4L2_CID_FLASH_STROBE, V4L2_CID_FLASH_STROBE_STATUS, V4L2_CID_FLASH_STROBE_STOP, V4L2_CID_FOCUS_ABSOLUTE, V4L2_CID_FOCUS_AUTO, V4L2_CID_FOCUS_RELATIVE, V4L2_CID_GAMMA, V4L2_CID_HUE, V4L2_CID_IRIS_ABSOLUTE, V4L2_CID_IRIS_RELATIVE, V4L2_CID_ISO_SENSITIVITY, V4L2_CID_ISO_SENSITIVITY_AUTO, V4L2_CID_PAN_ABSOLUTE, V4L2_CID_PAN_RELATIVE, V4L2_CID_POWER_LINE_FREQUENCY, V4L2_CID_ROLL_ABSOLUTE, V4L2_CID_SATURATION, V4L2_CID_SHARPNESS, V4L2_CID_TILT_ABSOLUTE, V4L2_CID_TILT_RELATIVE, V4L2_CID_ZOOM_ABSOLUTE, V4L2_CID_ZOOM_CONTINUOUS, V4L2_CID_ZOOM_RELATIVE};
+use v4l::io::traits::{CaptureStream, Stream as _};
 use v4l::prelude::MmapStream;
-use nokhwa_core::frame_buffer::FrameBuffer;
+use nokhwa_core::frame_buffer::{FrameBuffer, Metadata};
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+// The physical device a `/dev/videoN` node belongs to. For USB cameras this is the USB device,
+// which all the interfaces (and so all the nodes) of one camera share.
+fn physical_device_of(index: usize) -> Option<PathBuf> {
+    let device = fs::canonicalize(format!("/sys/class/video4linux/video{index}/device")).ok()?;
+    if device.join("bInterfaceNumber").exists() {
+        device.parent().map(Path::to_path_buf)
+    } else {
+        Some(device)
+    }
+}
+
+fn open_device(index: &CameraIndex) -> NokhwaResult<Device> {
+    match index {
+        CameraIndex::Index(i) => Device::new(*i as usize),
+        CameraIndex::String(path) => Device::with_path(path)
+    }.map_err(|why| {
+        NokhwaError::OpenDeviceError(index.to_string(), why.to_string(), Some(ErrorSource::new(why)))
+    })
+}
 
 fn index_capabilities_to_camera_info(index: u32, capabilities: Capabilities) -> CameraInformation {
     let name = capabilities.card;
@@ -33,81 +78,31 @@ fn index_capabilities_to_camera_info(index: u32, capabilities: Capabilities) ->
 }
 
 
-macro_rules! define_back_and_forth {
-    ( $($frame_format:expr => $fourcc:expr ,)+ ) => {
-        fn frame_format_to_fourcc(frame_format: FrameFormat) -> Result<FourCC, NokhwaError> {
-            match frame_format {
-                $(
-                $frame_format => Ok(FourCC::new($fourcc)),
-                )+
-            FrameFormat::Custom(def) => {
-            // if 4-7 is set (non-null) return an error.
-            if def[4..=7] != [0x00, 0x00, 0x00, 0x00] {
-                return Err(NokhwaError::ConversionError("Invalid: Custom bytes 4-7 are set (linux only uses 0-3)".to_string()))
-            }
-            Ok(FourCC::new(&[def[0], def[1], def[2], def[3]]))
-        }
-        _ => {
-            return Err(NokhwaError::ConversionError("Unsupported FrameFormat".to_string()))
-        }}
-        }
-        
-        fn fourcc_to_frame_format(four_cc: FourCC) -> FrameFormat {
-            match &four_cc.repr {
-                $(
-                $fourcc => $frame_format
-                )+
-                custom => FrameFormat::Custom([ custom[0], custom[1], custom[2], custom[3], 0x00, 0x00, 0x00, 0x00 ])
-            }
+// The FourCC table lives in `FrameFormat`, so both directions are just lookups into it.
+fn frame_format_to_fourcc(frame_format: FrameFormat) -> Result<FourCC, NokhwaError> {
+    match frame_format.fourcc() {
+        Some(code) => Ok(FourCC::new(&code)),
+        // bytes 4-7 of a custom format don't fit a v4l2 FourCC.
+        None if matches!(frame_format, FrameFormat::Custom(_)) => {
+            Err(NokhwaError::ConversionError("Invalid: Custom bytes 4-7 are set (linux only uses 0-3)".to_string()))
         }
+        None => Err(NokhwaError::UnsupportedFormat { format: frame_format }),
     }
 }
 
-
-define_back_and_forth!(
-    FrameFormat::H265 => b"HEVC",
-    FrameFormat::H264 => b"H264",
-    FrameFormat::Avc1 => b"AVC1",
-    FrameFormat::H263 => b"H263",
-    FrameFormat::Av1 => b"AV1F",
-    FrameFormat::Mpeg1 => b"MPG1",
-    FrameFormat::Mpeg2 => b"MPG2",
-    FrameFormat::Mpeg4 => b"MPG4",
-    FrameFormat::MJpeg => b"MJPG",
-    FrameFormat::XVid => b"XVID",
-    FrameFormat::VP8 => b"VP80",
-    FrameFormat::VP9 => b"VP90",
-    FrameFormat::Ayuv444 => b"AYUV",
-    FrameFormat::Yuyv422 => b"YUYV",
-    FrameFormat::Uyvy422 => b"UYVY",
-    FrameFormat::Yvyu422 => b"YVYU",
-    FrameFormat::Yv12 => b"YV12",
-    FrameFormat::Nv12 => b"NV12",
-    FrameFormat::Nv21 => b"NV21",
-    FrameFormat::I420 => b"YU12",
-    FrameFormat::Yvu9 => b"YVU9",
-    FrameFormat::Luma8 => b"GREY",
-    FrameFormat::Luma16 => b"Y16 ",
-    FrameFormat::Depth16 => b"Z16 ",
-    FrameFormat::Rgb332 => b"RGB1",
-    FrameFormat::Rgb888 => b"RGB3",
-    FrameFormat::Bgr888 => b"BGR3",
-    FrameFormat::BgrA8888 => b"RA24",
-    FrameFormat::RgbA8888 => b"AB24",
-    FrameFormat::ARgb8888 => b"BA24",
-    FrameFormat::Bayer8 => b"BA81",
-    FrameFormat::Bayer16 => b"BYR2",
-);
+fn fourcc_to_frame_format(four_cc: FourCC) -> FrameFormat {
+    FrameFormat::from_fourcc(four_cc.repr)
+}
 
 macro_rules! define_control_id_conv {
-    ( $($control_id:expr => $v4l_cid:expr ,)+ ) => {
+    ( $($control_id:path => $v4l_cid:ident ,)+ ) => {
         fn control_id_to_cid(control_id: ControlId) -> Result<u32, NokhwaError> {
             match control_id {
                 $(
-                $control_id => Ok($v4l_cid)
+                $control_id => Ok($v4l_cid),
                 )+
                 ControlId::PlatformSpecific(specific_id) => {
-                    u32::try_from(specific_id).map_err(|why| {
+                    u32::try_from(specific_id).map_err(|_| {
                         NokhwaError::ConversionError("ID must be a u32".to_string())
                     })
                 }
@@ -119,10 +114,10 @@ macro_rules! define_control_id_conv {
         fn control_id_to_cid_ref(control_id: &ControlId) -> Result<u32, NokhwaError> {
             match control_id {
                 $(
-                $control_id => Ok($v4l_cid)
+                $control_id => Ok($v4l_cid),
                 )+
                 ControlId::PlatformSpecific(specific_id) => {
-                    u32::try_from(specific_id).map_err(|why| {
+                    u32::try_from(*specific_id).map_err(|_| {
                         NokhwaError::ConversionError("ID must be a u32".to_string())
                     })
                 }
@@ -134,7 +129,7 @@ macro_rules! define_control_id_conv {
         fn cid_to_control_id(cid: u32) -> ControlId {
             match cid {
                 $(
-                $v4l_cid => $control_id
+                $v4l_cid => $control_id,
                 )+
                 other_id => ControlId::PlatformSpecific(other_id as u64)
             }
@@ -173,8 +168,130 @@ define_control_id_conv!(
     ControlId::LightingStatus => V4L2_CID_FLASH_STROBE_STATUS,
 
     ControlId::Orientation => V4L2_CID_CAMERA_ORIENTATION,
+
+    ControlId::Brightness => V4L2_CID_BRIGHTNESS,
+    ControlId::Contrast => V4L2_CID_CONTRAST,
+    ControlId::Saturation => V4L2_CID_SATURATION,
+    ControlId::Hue => V4L2_CID_HUE,
+    ControlId::Gamma => V4L2_CID_GAMMA,
+    ControlId::Sharpness => V4L2_CID_SHARPNESS,
+    ControlId::BacklightCompensation => V4L2_CID_BACKLIGHT_COMPENSATION,
+
+    ControlId::PanAbsolute => V4L2_CID_PAN_ABSOLUTE,
+    ControlId::PanRelative => V4L2_CID_PAN_RELATIVE,
+    ControlId::TiltAbsolute => V4L2_CID_TILT_ABSOLUTE,
+    ControlId::TiltRelative => V4L2_CID_TILT_RELATIVE,
+    ControlId::RollAbsolute => V4L2_CID_ROLL_ABSOLUTE,
+
+    ControlId::PowerLineFrequency => V4L2_CID_POWER_LINE_FREQUENCY,
 );
 
+// the `V4L2_CID_CAMERA_ORIENTATION` menu: front facing, back facing, or an external camera.
+fn orientation_from_v4l2(value: i64) -> Orientation {
+    match value {
+        0 => Orientation::User,
+        1 => Orientation::Environment,
+        2 => Orientation::Other,
+        other => Orientation::Custom(other),
+    }
+}
+
+// V4L2 reports frame intervals (seconds per frame), which are the inverse of a frame rate.
+fn interval_to_frame_rate(interval: Fraction) -> Option<FrameRate> {
+    NonZeroI32::new(interval.numerator as i32).map(|denominator| {
+        FrameRate::new(interval.denominator as i32, denominator).normalized()
+    })
+}
+
+// How many entries a stepwise frame interval or frame size is expanded into at most. Some drivers report
+// e.g. every interval between 1 and 1000000 in steps of 1.
+const MAX_STEPWISE_ENTRIES: u32 = 256;
+
+// The values `min..=max` in steps of `step`, evenly sampled down to `MAX_STEPWISE_ENTRIES` if there are more.
+// A zero step gives just `min` and `max`.
+fn stepwise_values(min: u32, max: u32, step: u32) -> Vec<u32> {
+    if min > max {
+        return vec![]
+    }
+    if step == 0 {
+        return if min == max { vec![min] } else { vec![min, max] }
+    }
+
+    // in u64, as `0..=u32::MAX` by 1 has one value more than a u32 can count.
+    let count = u64::from((max - min) / step) + 1;
+    if count <= u64::from(MAX_STEPWISE_ENTRIES) {
+        return (0..count).map(|i| min + i as u32 * step).collect()
+    }
+    (0..MAX_STEPWISE_ENTRIES).map(|i| {
+        let index = u64::from(i) * (count - 1) / u64::from(MAX_STEPWISE_ENTRIES - 1);
+        min + index as u32 * step
+    }).collect()
+}
+
+// we have to expand stepwise intervals ourselves
+fn stepwise_frame_rates(stepwise: FrameIntervalStepwise) -> Vec<FrameRate> {
+    // no logic to handle different or zero demoninator
+    if (stepwise.step.denominator != stepwise.max.denominator) || (stepwise.step.denominator != stepwise.min.denominator) {
+        return vec![]
+    }
+
+    let denominator = stepwise.step.denominator;
+    stepwise_values(stepwise.min.numerator, stepwise.max.numerator, stepwise.step.numerator).into_iter().flat_map(|numerator| {
+        interval_to_frame_rate(Fraction::new(numerator, denominator))
+    }).collect()
+}
+
+// Common resolutions tried against stepwise (and continuous, which is stepwise with a step of 1) frame sizes.
+const STANDARD_RESOLUTIONS: &[(u32, u32)] = &[
+    (160, 120), (176, 144), (320, 240), (352, 288), (640, 360), (640, 480), (800, 600), (1024, 768),
+    (1280, 720), (1280, 960), (1280, 1024), (1600, 1200), (1920, 1080), (2560, 1440), (3840, 2160), (4096, 2160),
+];
+
+// Expanding every width and height combination can easily be millions of resolutions, so instead this is the
+// smallest and largest size plus every standard resolution that fits the steps.
+fn stepwise_resolutions(stepwise: FrameSizeStepwise) -> Vec<Resolution> {
+    let fits = |value: u32, min: u32, max: u32, step: u32| {
+        (min..=max).contains(&value) && match step {
+            0 => value == min || value == max,
+            step => (value - min).is_multiple_of(step),
+        }
+    };
+    if stepwise.min_width > stepwise.max_width || stepwise.min_height > stepwise.max_height {
+        return vec![]
+    }
+
+    let mut resolutions = vec![Resolution::new(stepwise.min_width, stepwise.min_height)];
+    resolutions.extend(STANDARD_RESOLUTIONS.iter().filter(|(width, height)| {
+        fits(*width, stepwise.min_width, stepwise.max_width, stepwise.step_width)
+            && fits(*height, stepwise.min_height, stepwise.max_height, stepwise.step_height)
+    }).map(|(width, height)| Resolution::new(*width, *height)));
+    resolutions.push(Resolution::new(stepwise.max_width, stepwise.max_height));
+    resolutions.dedup();
+    resolutions
+}
+
+// checks against the stepwise interval directly, as `stepwise_frame_rates` may only be a sample of it.
+fn stepwise_contains_frame_rate(stepwise: FrameIntervalStepwise, frame_rate: FrameRate) -> bool {
+    if (stepwise.step.denominator != stepwise.max.denominator) || (stepwise.step.denominator != stepwise.min.denominator) {
+        return false
+    }
+    let (Ok(fps_numerator), Ok(fps_denominator)) = (u64::try_from(*frame_rate.numerator()), u64::try_from(*frame_rate.denominator())) else {
+        return false
+    };
+
+    // the interval is `fps_denominator / fps_numerator`, which has to be expressible as `numerator / step.denominator`.
+    let scaled = fps_denominator * u64::from(stepwise.step.denominator);
+    if fps_numerator == 0 || scaled % fps_numerator != 0 {
+        return false
+    }
+    let numerator = scaled / fps_numerator;
+    let (min, max, step) = (u64::from(stepwise.min.numerator), u64::from(stepwise.max.numerator), u64::from(stepwise.step.numerator));
+    match step {
+        0 => numerator == min || numerator == max,
+        step => (min..=max).contains(&numerator) && (numerator - min) % step == 0,
+    }
+}
+
 fn flags(flags: Flags) -> HashSet<ControlFlags> {
     let mut output_flags = HashSet::new();
 
@@ -190,6 +307,9 @@ fn flags(flags: Flags) -> HashSet<ControlFlags> {
     if flags.intersects(Flags::UPDATE) {
         output_flags.insert(ControlFlags::CascadingUpdates);
     }
+    if flags.intersects(Flags::INACTIVE) {
+        output_flags.insert(ControlFlags::Inactive);
+    }
     if flags.intersects(Flags::SLIDER) {
         output_flags.insert(ControlFlags::Slider);
     }
@@ -206,6 +326,39 @@ fn flags(flags: Flags) -> HashSet<ControlFlags> {
     output_flags
 }
 
+fn value_to_control_value(value: Value) -> ControlValue {
+    match value {
+        Value::None => ControlValue::Null,
+        Value::Integer(i) => ControlValue::Integer(i),
+        Value::Boolean(b) => ControlValue::Boolean(b),
+        Value::String(s) => ControlValue::String(s),
+        Value::CompoundU8(bin) | Value::CompoundPtr(bin) => ControlValue::Binary(bin),
+        Value::CompoundU16(u) => ControlValue::Array(
+            u.into_iter().map(|u| ControlValue::Integer(i64::from(u))).collect()
+        ),
+        Value::CompoundU32(u) => ControlValue::Array(
+            u.into_iter().map(|u| ControlValue::Integer(i64::from(u))).collect()
+        ),
+    }
+}
+
+// the inverse of `value_to_control_value`, except for arrays: they don't say which compound type they came from.
+fn control_value_to_value(value: &ControlValue) -> Result<Value, NokhwaError> {
+    Ok(match value {
+        ControlValue::Null => Value::None,
+        ControlValue::Integer(i) | ControlValue::BitMask(i) => Value::Integer(*i),
+        ControlValue::Boolean(b) => Value::Boolean(*b),
+        ControlValue::String(s) => Value::String(s.clone()),
+        ControlValue::Binary(bin) => Value::CompoundU8(bin.clone()),
+        // menus take the index of the item to pick
+        ControlValue::EnumPick(pick) => match pick.as_ref() {
+            ControlValue::Integer(i) => Value::Integer(*i),
+            other => return Err(NokhwaError::ConversionError(format!("Cannot pick menu item {other}"))),
+        },
+        other => return Err(NokhwaError::ConversionError(format!("{other} has no V4L2 equivalent"))),
+    })
+}
+
 fn convert_description_to_ctrl_body(description: Description) -> Option<ControlDescription> {
     let flags = flags(description.flags);
 
@@ -218,19 +371,19 @@ fn convert_description_to_ctrl_body(description: Description) -> Option<ControlD
         }
         Type::U8 => {
             (
-                ControlValueDescriptor::Integer(Range::new(0, u8::MAX_VALUE as i64, Some(description.step as i64))),
+                ControlValueDescriptor::Integer(Range::new(0, i64::from(u8::MAX), Some(description.step as i64))),
                 Some(ControlValue::Integer(description.default))
             )
         }
         Type::U16 => {
             (
-                ControlValueDescriptor::Integer(Range::new(0, u16::MAX_VALUE as i64, Some(description.step as i64))),
+                ControlValueDescriptor::Integer(Range::new(0, i64::from(u16::MAX), Some(description.step as i64))),
                 Some(ControlValue::Integer(description.default))
             )
         }
         Type::U32 => {
             (
-                ControlValueDescriptor::Integer(Range::new(0, u32::MAX_VALUE as i64, Some(description.step as i64))),
+                ControlValueDescriptor::Integer(Range::new(0, i64::from(u32::MAX), Some(description.step as i64))),
                 Some(ControlValue::Integer(description.default))
             )
         }
@@ -252,6 +405,13 @@ fn convert_description_to_ctrl_body(description: Description) -> Option<ControlD
                 Some(ControlValue::BitMask(description.default))
             )
         }
+        Type::Menu if description.id == V4L2_CID_CAMERA_ORIENTATION => {
+            let orientations = description.items?.into_iter().map(|(idx, _)| orientation_from_v4l2(i64::from(idx))).collect();
+            (
+                ControlValueDescriptor::Orientation(orientations),
+                Some(ControlValue::Orientation(orientation_from_v4l2(description.default)))
+            )
+        }
         Type::IntegerMenu | Type::Menu => {
             // our keys
             let descriptor = match description.items {
@@ -259,7 +419,7 @@ fn convert_description_to_ctrl_body(description: Description) -> Option<ControlD
                     ControlValueDescriptor::Menu(items.into_iter().map(|(idx, menu_item)| {
                         (ControlValue::Integer(idx as i64), match menu_item {
                             MenuItem::Name(name) => ControlValue::String(name),
-                            MenuItem::Value(v) => ControlValue::Integer(*v),
+                            MenuItem::Value(v) => ControlValue::Integer(v),
                         })
                     }).collect::<HashMap<ControlValue, ControlValue>>())
                 }
@@ -269,7 +429,14 @@ fn convert_description_to_ctrl_body(description: Description) -> Option<ControlD
             };
             (
                 descriptor,
-                Some(ControlValue::Integer(description.default))
+                Some(ControlValue::EnumPick(Box::new(ControlValue::Integer(description.default))))
+            )
+        }
+        // some drivers report the relative PTZ controls as buttons, but they take a signed step to move by.
+        Type::Button if matches!(description.id, V4L2_CID_PAN_RELATIVE | V4L2_CID_TILT_RELATIVE) => {
+            (
+                ControlValueDescriptor::Integer(Range::new(description.minimum, description.maximum, Some(description.step as i64))),
+                None,
             )
         }
         Type::Button => {
@@ -295,6 +462,68 @@ fn convert_description_to_ctrl_body(description: Description) -> Option<ControlD
 }
 
 
+// The manual controls that only do anything while their automatic mode is off.
+fn add_control_dependencies(descriptions: &mut HashMap<ControlId, ControlDescription>) {
+    // V4L2_EXPOSURE_MANUAL
+    let manual_exposure = ControlValue::EnumPick(Box::new(ControlValue::Integer(1)));
+    let dependencies = [
+        (ControlId::ExposureAbsolute, ControlId::ExposureMode, manual_exposure),
+        (ControlId::FocusAbsolute, ControlId::FocusMode, ControlValue::Boolean(false)),
+        (ControlId::FocusRelative, ControlId::FocusMode, ControlValue::Boolean(false)),
+        (ControlId::WhiteBalanceTemperature, ControlId::WhiteBalanceMode, ControlValue::Boolean(false)),
+    ];
+
+    for (child, parent, required) in dependencies {
+        if !descriptions.contains_key(&parent) {
+            continue;
+        }
+        if let Some(description) = descriptions.get_mut(&child) {
+            description.set_depends_on(Some((parent, required)));
+        }
+    }
+}
+
+// How long `query_with_formats` waits for the devices' formats, in total.
+const QUERY_FORMATS_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Every camera, with the capture node's device that was opened to query it.
+fn query_devices() -> Vec<(CameraInformation, Device)> {
+    let mut nodes = enum_devices().into_iter()
+        .filter_map(|v4l_node| {
+            let index = v4l_node.index();
+            // open camera for capabilities. if we dont get any, dont return the camera
+            let device = Device::new(index).ok()?;
+            let caps = device.query_caps().ok()?;
+            Some((index, v4l_node.path().to_path_buf(), caps, device))
+        }).collect::<Vec<_>>();
+    nodes.sort_by_key(|(index, _, _, _)| *index);
+
+    // UVC cameras usually expose a capture node and a metadata node for the same physical device,
+    // so group them by their device and only list each device once.
+    let mut devices: Vec<(Option<PathBuf>, Vec<(usize, PathBuf, Capabilities, Device)>)> = Vec::new();
+    for (index, path, caps, device) in nodes {
+        let physical = physical_device_of(index);
+        match devices.iter_mut().find(|(device, _)| physical.is_some() && *device == physical) {
+            Some((_, members)) => members.push((index, path, caps, device)),
+            None => devices.push((physical, vec![(index, path, caps, device)])),
+        }
+    }
+
+    devices.into_iter()
+        .filter_map(|(_, members)| {
+            let node_paths = members.iter().map(|(_, path, _, _)| path.display().to_string()).collect::<Vec<_>>().join(", ");
+            // a device without a capture node (e.g. metadata only) isn't a camera we can stream from.
+            let (index, _, caps, device) = members.into_iter().find(|(_, _, caps, _)| {
+                caps.capabilities.intersects(CapabilityFlags::VIDEO_CAPTURE | CapabilityFlags::VIDEO_CAPTURE_MPLANE)
+            })?;
+
+            let mut information = index_capabilities_to_camera_info(index as u32, caps);
+            let misc = format!("{} Nodes: {node_paths}", information.misc());
+            information.set_misc(&misc);
+            Some((information, device))
+        }).collect()
+}
+
 pub struct V4L2Platform {}
 
 impl PlatformTrait for V4L2Platform {
@@ -305,30 +534,45 @@ impl PlatformTrait for V4L2Platform {
         Ok(())
     }
 
-    fn check_permission_given(&mut self) -> bool {
-        true
+    fn permission_status(&mut self) -> PermissionStatus {
+        PermissionStatus::Granted
     }
 
     fn query(&mut self) -> NokhwaResult<Vec<CameraInformation>> {
-        Ok(enum_devices().into_iter()
-            .map(|v4l_node| {
-                let index = v4l_node.index();
-                // open camera for capabilities. if we dont get any, dont return the camera
-                Device::new(index).map(|dev|
-                    dev.query_caps().map(|caps| {
-                        index_capabilities_to_camera_info(index as u32, caps)
-                    }).ok()
-                ).ok().flatten()
-            }).flatten().collect::<Vec<_>>())
+        Ok(query_devices().into_iter().map(|(information, _)| information).collect())
+    }
+
+    // every device is enumerated on its own thread, so one that hangs only costs `QUERY_FORMATS_TIMEOUT`.
+    fn query_with_formats(&mut self) -> NokhwaResult<Vec<(CameraInformation, Vec<CameraFormat>)>> {
+        let pending = query_devices().into_iter().map(|(information, device)| {
+            let (sender, receiver) = bounded(1);
+            std::thread::spawn(move || {
+                let _ = sender.send(enumerate_formats_within(&device, &FormatLimits::none()));
+            });
+            (information, receiver)
+        }).collect::<Vec<_>>();
+
+        let deadline = Instant::now() + QUERY_FORMATS_TIMEOUT;
+        Ok(pending.into_iter().map(|(information, receiver)| {
+            let formats = receiver.recv_deadline(deadline).ok().and_then(Result::ok).unwrap_or_default();
+            (information, formats)
+        }).collect())
+    }
+
+    fn probe(&mut self, index: &CameraIndex) -> NokhwaResult<bool> {
+        // skip the controls entirely, we only care if this node can capture anything.
+        let Ok(device) = open_device(index) else {
+            return Ok(false);
+        };
+        let can_capture = device.query_caps().is_ok_and(|caps| {
+            caps.capabilities.intersects(CapabilityFlags::VIDEO_CAPTURE | CapabilityFlags::VIDEO_CAPTURE_MPLANE)
+        });
+
+        Ok(can_capture && device.enum_formats().is_ok_and(|formats| !formats.is_empty()))
     }
 
     fn open(&mut self, index: CameraIndex) -> NokhwaResult<Self::Camera> {
-        let device = match &index {
-            CameraIndex::Index(i) => Device::new(*i as usize),
-            CameraIndex::String(path) => Device::with_path(path)
-        }.map_err(|why| {
-            NokhwaError::OpenDeviceError(index.to_string(), why.to_string())
-        })?;
+        let device = open_device(&index)?;
 
         let mut v4l2_camera = V4L2Camera {
             device,
@@ -346,98 +590,210 @@ impl PlatformTrait for V4L2Platform {
 
 pub struct V4L2Camera {
     device: Device,
+    // what `Setting::set_format` last set, and so what the next stream will use.
     camera_format: Option<CameraFormat>,
     camera_index: CameraIndex,
     controls: Controls,
-    stream: Option<Arc<StreamHandle>>,
+    stream: Option<V4L2Stream>,
 }
 
-impl Setting for V4L2Camera {
-    fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
-        let mut formats = vec![];
-
-        for frame_format in self.device.enum_formats().map_err(|why| {
-            NokhwaError::GetPropertyError { property: "enum_formats".to_string(), error: why.to_string() }
-        })?.into_iter().map(|desc| {
-            fourcc_to_frame_format(desc.fourcc)
-        }) {
-            formats.extend(
-                self.enumerate_resolution_and_frame_rates(frame_format)?.into_iter().flat_map(|(resolution, frame_rates)| {
-                    frame_rates.into_iter().map(|frame_rate| {
-                        CameraFormat::new(resolution, frame_format, frame_rate)
-                    })
-                })
-            );
+impl V4L2Camera {
+    // Switches over to the device a reconnecting stream reopened, if it did. Only changes go through here, so until
+    // then queries (e.g. enumerating formats) still go to the old device.
+    fn adopt_reopened_device(&mut self) {
+        if let Some(device) = self.stream.as_ref().and_then(|stream| stream.reopened.try_iter().last()) {
+            self.device = device;
         }
-        Ok(formats)
     }
 
-    fn enumerate_resolution_and_frame_rates(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
-        let fourcc = frame_format_to_fourcc(frame_format)?;
-        let resolutions = self.device.enum_framesizes(fourcc).map_err(|why| {
-            NokhwaError::GetPropertyError { property: "enum_framesizes".to_string(), error: why.to_string() }
-        })?.into_iter()
-            .flat_map(|frame_size| {
-                frame_size.size.to_discrete()
-            }).map(|discrete| {             Resolution::new(discrete.width, discrete.height)
-        }).collect::<Vec<Resolution>>();
-
-        let v4l2_frame_intervals = resolutions.iter()
-            .map(|resolution| (*resolution, self.device.enum_frameintervals(fourcc, resolution.width(), resolution.height())))
-            .collect::<Result<Vec<(Resolution, Vec<FrameInterval>)>, std::io::Error>>()
-            .map_err(|why| {
-            NokhwaError::GetPropertyError { property: "enum_frameintervals".to_string(), error: why.to_string() }
+    // Writes back every control in `snapshot` the driver changed behind our back (e.g. on a format change).
+    // This is best effort: a control that can't be restored is left as the driver set it.
+    fn restore_controls(&mut self, snapshot: &Controls) {
+        if self.refresh_controls().is_err() {
+            return;
+        }
+
+        let changed = snapshot.ids().filter(|id| {
+            snapshot.description(id).is_some_and(|description| {
+                !description.flags().contains(&ControlFlags::ReadOnly) && !description.flags().contains(&ControlFlags::Disabled)
+            })
+        }).filter_map(|id| {
+            let value = snapshot.value(id)?;
+            (self.controls.value(id) != Some(value)).then(|| (*id, value.clone()))
+        }).collect::<Vec<_>>();
+
+        for (id, value) in changed {
+            let _ = self.set_control(&id, value);
+        }
+    }
+}
+
+fn enumerate_formats_within(device: &Device, limits: &FormatLimits) -> Result<Vec<CameraFormat>, NokhwaError> {
+    let mut formats = vec![];
+
+    for frame_format in enumerate_frame_formats(device)? {
+        formats.extend(
+            enumerate_resolution_and_frame_rates_within(device, frame_format, limits)?.into_iter().flat_map(|(resolution, frame_rates)| {
+                frame_rates.into_iter().map(move |frame_rate| {
+                    CameraFormat::new(resolution, frame_format, frame_rate)
+                })
+            })
+        );
+    }
+    Ok(formats)
+}
+
+fn enumerate_frame_formats(device: &Device) -> Result<Vec<FrameFormat>, NokhwaError> {
+    Ok(device.enum_formats().map_err(|why| {
+        NokhwaError::GetPropertyError { property: "enum_formats".to_string(), error: why.to_string(), source: Some(ErrorSource::new(why)) }
+    })?.into_iter().map(|desc| fourcc_to_frame_format(desc.fourcc)).collect())
+}
+
+fn enumerate_resolutions(device: &Device, fourcc: FourCC) -> Result<Vec<Resolution>, NokhwaError> {
+    Ok(device.enum_framesizes(fourcc).map_err(|why| {
+        NokhwaError::GetPropertyError { property: "enum_framesizes".to_string(), error: why.to_string(), source: Some(ErrorSource::new(why)) }
+    })?.into_iter()
+        .flat_map(|frame_size| {
+            match frame_size.size {
+                FrameSizeEnum::Discrete(discrete) => vec![Resolution::new(discrete.width, discrete.height)],
+                FrameSizeEnum::Stepwise(stepwise) => stepwise_resolutions(stepwise),
+            }
+        }).collect())
+}
+
+fn enumerate_resolution_and_frame_rates_within(device: &Device, frame_format: FrameFormat, limits: &FormatLimits) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
+    let fourcc = frame_format_to_fourcc(frame_format)?;
+    // filter out resolutions first, so we don't probe intervals for ones we would throw away anyway
+    let resolutions = enumerate_resolutions(device, fourcc)?.into_iter()
+        .filter(|resolution| limits.allows_resolution(*resolution))
+        .collect::<Vec<Resolution>>();
+
+    let mut resolutions_and_frame_rates: HashMap<Resolution, Vec<FrameRate>> = HashMap::new();
+    for resolution in resolutions {
+        let intervals = device.enum_frameintervals(fourcc, resolution.width(), resolution.height()).map_err(|why| {
+            NokhwaError::GetPropertyError { property: "enum_frameintervals".to_string(), error: why.to_string(), source: Some(ErrorSource::new(why)) }
         })?;
 
-        Ok(v4l2_frame_intervals.into_iter().flatten().flat_map(|(resolution, interval)| {
+        let frame_rates = intervals.into_iter().flat_map(|interval| {
             match interval.interval {
-                FrameIntervalEnum::Discrete(discrete) => {
-                    NonZeroI32::new(discrete.denominator as i32).map(|denominator| {
-                        (resolution, vec![FrameRate::new(discrete.numerator as i32, denominator)])
-                    })
-                }
-                FrameIntervalEnum::Stepwise(stepwise) => {
-                    // we have to do this ourselves
+                FrameIntervalEnum::Discrete(discrete) => interval_to_frame_rate(discrete).into_iter().collect(),
+                FrameIntervalEnum::Stepwise(stepwise) => stepwise_frame_rates(stepwise),
+            }
+        }).filter(|frame_rate| limits.allows_frame_rate(*frame_rate)).collect::<Vec<FrameRate>>();
 
-                    // no logic to handle different or zero demoninator
-                    if (stepwise.step.denominator != stepwise.max.denominator) || (stepwise.step.denominator != stepwise.min.denominator) {
-                        return None
-                    }
+        if !frame_rates.is_empty() {
+            resolutions_and_frame_rates.entry(resolution).or_default().extend(frame_rates);
+        }
+    }
 
-                    let min = stepwise.min.numerator as i32;
-                    let max = stepwise.max.numerator as i32;
-                    let step = stepwise.step.numerator as i32;
-                    let denominator = stepwise.step.denominator as i32;
+    Ok(resolutions_and_frame_rates)
+}
 
-                    NonZeroI32::new(denominator).map(|denominator| {
-                        (resolution, (min..max).step_by(step as usize).map(|numerator| {
-                            FrameRate::new(numerator, denominator)
-                        }).collect::<Vec<FrameRate>>())
-                    })
-                }
+// Sets the format and frame rate of `camera_format` on `device`.
+fn apply_format(device: &Device, camera_format: CameraFormat) -> NokhwaResult<()> {
+    let fourcc = frame_format_to_fourcc(*camera_format.format())?;
+    device.set_format(
+        &Format::new(camera_format.width(), camera_format.height(), fourcc)
+    ).map_err(|why| NokhwaError::SetPropertyError {
+        property: "set_format".to_string(),
+        value: format!("format: {camera_format} fourcc: {fourcc}"),
+        error: why.to_string(),
+        source: Some(ErrorSource::new(why)),
+    })?;
+    // v4l2 takes the frame *interval*, the inverse of the frame rate.
+    device.set_params(&Parameters::new(Fraction::new(camera_format.frame_rate().denominator().unsigned_abs(), camera_format.frame_rate().numerator().unsigned_abs()))).map_err(|why| {
+        NokhwaError::SetPropertyError {
+            property: "set_params".to_string(),
+            value: format!("{}", camera_format.frame_rate()),
+            error: why.to_string(),
+            source: Some(ErrorSource::new(why)),
+        }
+    })?;
+    Ok(())
+}
+
+impl Setting for V4L2Camera {
+    fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+        self.enumerate_formats_within(&FormatLimits::none())
+    }
+
+    fn enumerate_formats_within(&self, limits: &FormatLimits) -> Result<Vec<CameraFormat>, NokhwaError> {
+        enumerate_formats_within(&self.device, limits)
+    }
+
+    fn enumerate_resolution_and_frame_rates(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
+        enumerate_resolution_and_frame_rates_within(&self.device, frame_format, &FormatLimits::none())
+    }
+
+    fn enumerate_frame_formats(&self) -> Result<Vec<FrameFormat>, NokhwaError> {
+        let mut frame_formats = enumerate_frame_formats(&self.device)?;
+        frame_formats.sort();
+        frame_formats.dedup();
+        Ok(frame_formats)
+    }
+
+    fn enumerate_resolutions(&self, frame_format: FrameFormat) -> Result<Vec<Resolution>, NokhwaError> {
+        let mut resolutions = enumerate_resolutions(&self.device, frame_format_to_fourcc(frame_format)?)?;
+        resolutions.sort();
+        resolutions.dedup();
+        Ok(resolutions)
+    }
+
+    fn is_format_supported(&self, format: CameraFormat) -> Result<bool, NokhwaError> {
+        let Ok(fourcc) = frame_format_to_fourcc(*format.format()) else {
+            return Ok(false);
+        };
+        // drivers answer with EINVAL for fourccs and sizes they don't support.
+        let Ok(intervals) = self.device.enum_frameintervals(fourcc, format.width(), format.height()) else {
+            return Ok(false);
+        };
+
+        let frame_rate = format.frame_rate().normalized();
+        Ok(intervals.into_iter().any(|interval| {
+            match interval.interval {
+                FrameIntervalEnum::Discrete(discrete) => interval_to_frame_rate(discrete) == Some(frame_rate),
+                FrameIntervalEnum::Stepwise(stepwise) => stepwise_contains_frame_rate(stepwise, frame_rate),
             }
-        }).flatten().collect::<HashMap<Resolution, Vec<FrameRate>>>())
+        }))
     }
 
-    fn set_format(&self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
-        let fourcc = frame_format_to_fourcc(*camera_format.format())?;
-        self.device.set_format(
-            &Format::new(camera_format.width(), camera_format.height(), fourcc)
-        ).map_err(|why| NokhwaError::SetPropertyError {
-            property: "set_format".to_string(),
-            value: format!("format: {camera_format} fourcc: {fourcc}"),
-            error: why.to_string(),
+    fn frame_rate_range(&self, frame_format: FrameFormat, resolution: Resolution) -> Result<Option<Range<FrameRate>>, NokhwaError> {
+        let fourcc = frame_format_to_fourcc(frame_format)?;
+        let intervals = self.device.enum_frameintervals(fourcc, resolution.width(), resolution.height()).map_err(|why| {
+            NokhwaError::GetPropertyError { property: "enum_frameintervals".to_string(), error: why.to_string(), source: Some(ErrorSource::new(why)) }
         })?;
-        self.device.set_params(&Parameters::new(Fraction::new(*camera_format.frame_rate().numerator() as u32, *camera_format.frame_rate().denominator() as u32))).map_err(|why| {
-            NokhwaError::SetPropertyError {
-                property: "set_params".to_string(),
-                value: format!("{}", camera_format.frame_rate()),
-                error: why.to_string(),
+
+        Ok(intervals.into_iter().find_map(|interval| {
+            match interval.interval {
+                FrameIntervalEnum::Discrete(_) => None,
+                FrameIntervalEnum::Stepwise(stepwise) => {
+                    // the longest interval is the slowest frame rate and vice versa
+                    let slowest = interval_to_frame_rate(stepwise.max)?;
+                    let fastest = interval_to_frame_rate(stepwise.min)?;
+                    Some(Range::new(slowest, fastest, None))
+                }
             }
-        })?;
+        }))
+    }
+
+    fn set_format(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        self.adopt_reopened_device();
+        apply_format(&self.device, camera_format)?;
+        self.camera_format = Some(camera_format);
         Ok(())
     }
 
+    fn current_format(&self) -> Option<CameraFormat> {
+        if let Some(format) = self.camera_format {
+            return Some(format)
+        }
+
+        // nothing was set through us, so ask the driver what it is configured for.
+        let format = self.device.format().ok()?;
+        let frame_rate = self.device.params().ok().and_then(|params| interval_to_frame_rate(params.interval))?;
+        Some(CameraFormat::new(Resolution::new(format.width, format.height), fourcc_to_frame_format(format.fourcc), frame_rate))
+    }
+
     fn control_ids(&self) -> Keys<ControlId, ControlDescription> {
         self.controls.ids()
     }
@@ -459,12 +815,41 @@ impl Setting for V4L2Camera {
     }
 
     fn set_control(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+        self.adopt_reopened_device();
+        self.controls.validate_value(property, &value)?;
+        let cid = control_id_to_cid_ref(property)?;
+
+        self.device.set_control(Control { id: cid, value: control_value_to_value(&value)? }).map_err(|why| {
+            NokhwaError::SetPropertyError {
+                property: property.to_string(),
+                value: value.to_string(),
+                error: why.to_string(),
+                source: Some(ErrorSource::new(why)),
+            }
+        })?;
         self.controls.set_control_value(property, value)
     }
 
+    // mode controls (e.g. auto exposure) go first, as the driver rejects the controls they lock while they're in the way.
+    fn set_controls(&mut self, values: &[(ControlId, ControlValue)]) -> Result<(), Vec<(ControlId, NokhwaError)>> {
+        let mut ordered = values.to_vec();
+        ordered.sort_by_key(|(id, _)| !id.is_mode());
+
+        let errors = ordered.into_iter().filter_map(|(id, value)| {
+            self.set_control(&id, value).err().map(|why| (id, why))
+        }).collect::<Vec<(ControlId, NokhwaError)>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn refresh_controls(&mut self) -> Result<(), NokhwaError> {
-        let descriptions = self.device.query_controls().map_err(|why| {
-            NokhwaError::GetPropertyError { property: "query_controls".to_string(), error: why.to_string() }
+        self.adopt_reopened_device();
+        let mut descriptions = self.device.query_controls().map_err(|why| {
+            NokhwaError::GetPropertyError { property: "query_controls".to_string(), error: why.to_string(), source: Some(ErrorSource::new(why)) }
         })?.into_iter().map(|description| {
             let id = cid_to_control_id(description.id);
 
@@ -472,28 +857,28 @@ impl Setting for V4L2Camera {
                 (id, body)
             })
         }).flatten().collect::<HashMap<ControlId, ControlDescription>>();
+        add_control_dependencies(&mut descriptions);
 
         let values = descriptions.keys().into_iter().copied().flat_map(|k| control_id_to_cid(k).map(|cid| (k, cid))).flat_map(|(id, cid)| {
             self.device.control(cid).map(|v| (id, v))
         }).map(|(id, value)| {
-            (id, match value.value {
-                Value::None => ControlValue::Null,
-                Value::Integer(i) => ControlValue::Integer(i),
-                Value::Boolean(b) => ControlValue::Boolean(b),
-                Value::String(s) => ControlValue::String(s),
-                Value::CompoundU8(bin) | Value::CompoundPtr(bin) => ControlValue::Binary(bin),
-                Value::CompoundU16(u) | Value::CompoundU32(u) => ControlValue::Array(
-                    u.into_iter().map(|u| ControlValue::Integer(u as i64)).collect()
-                ),
-            })
+            let value = value_to_control_value(value.value);
+            // menus report the index of the picked item
+            match (descriptions.get(&id).map(ControlDescription::descriptor), value) {
+                (Some(ControlValueDescriptor::Menu(_)), ControlValue::Integer(i)) => (id, ControlValue::EnumPick(Box::new(ControlValue::Integer(i)))),
+                (Some(ControlValueDescriptor::Orientation(_)), ControlValue::Integer(i)) => (id, ControlValue::Orientation(orientation_from_v4l2(i))),
+                (_, value) => (id, value),
+            }
         }).collect::<HashMap<ControlId, ControlValue>>();
 
+        let value = format!("{} descriptions, {} values", descriptions.len(), values.len());
         match Controls::new(descriptions, values) {
             Some(c) => { self.controls = c; }
             None => return Err(NokhwaError::SetPropertyError {
                 property: "control".to_string(),
-                value: format!("{:?} {:?}", descriptions, values),
+                value,
                 error: "Failed to convert to control".to_string(),
+                source: None,
             })
         }
 
@@ -501,104 +886,708 @@ impl Setting for V4L2Camera {
     }
 }
 
-struct V4L2Stream {
-    thread: JoinHandle<()>,
-    control: Sender<()>,
-    receiver: Arc<Receiver<NokhwaResult<FrameBuffer>>>,
+// How many buffers the driver is asked to allocate for a stream.
+const STREAM_BUFFER_COUNT: u32 = 4;
+
+// How long a dequeue may wait for a frame before it counts as a capture error, at the least and at the most.
+const MIN_DEQUEUE_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_DEQUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How many dequeue errors in a row a stream tolerates before it terminates, or reconnects if its `ReconnectPolicy`
+// allows. Each one is sent as an `Event::Error`.
+const MAX_CONSECUTIVE_CAPTURE_ERRORS: u32 = 5;
+
+// Exports every buffer of a (mmap) capture stream as a DMABUF, in buffer index order.
+fn export_dmabufs(device: &Handle) -> std::io::Result<Vec<OwnedFd>> {
+    let mut dmabufs = vec![];
+    for index in 0..STREAM_BUFFER_COUNT {
+        let mut export = v4l2_exportbuffer {
+            type_: v4l::buffer::Type::VideoCapture as u32,
+            index,
+            flags: (libc::O_CLOEXEC | libc::O_RDONLY) as u32,
+            // SAFETY: v4l2_exportbuffer is a plain C struct, all zeroes is a valid value.
+            ..unsafe { std::mem::zeroed() }
+        };
+        // SAFETY: `export` outlives the call and is the argument type VIDIOC_EXPBUF expects.
+        let exported = unsafe {
+            v4l::v4l2::ioctl(device.fd(), v4l::v4l2::vidioc::VIDIOC_EXPBUF, &mut export as *mut _ as *mut std::os::raw::c_void)
+        };
+        match exported {
+            // SAFETY: the driver just handed us this descriptor, nobody else owns it.
+            Ok(()) => dmabufs.push(unsafe { OwnedFd::from_raw_fd(export.fd) }),
+            // the driver may have allocated fewer buffers than asked for.
+            Err(_) if !dmabufs.is_empty() => break,
+            Err(why) => return Err(why),
+        }
+    }
+    Ok(dmabufs)
 }
 
-impl Drop for V4L2Stream {
-    fn drop(&mut self) {
-        let _ = self.control.send(());
+// A few frame intervals, so a stalled device is noticed (and the stream stays stoppable) without slow frame rates
+// tripping it.
+fn dequeue_timeout(frame_rate: FrameRate) -> Duration {
+    // an unknown (zero) frame rate has no interval to go by, so wait as long as we would for the slowest camera.
+    let interval = Duration::try_from_secs_f32(1.0 / frame_rate.as_f32()).unwrap_or(MAX_DEQUEUE_TIMEOUT);
+    interval.saturating_mul(4).clamp(MIN_DEQUEUE_TIMEOUT, MAX_DEQUEUE_TIMEOUT)
+}
+
+// Opens the device at `index` anew and starts over with a fresh capture stream on it, for a stream whose device
+// stopped delivering frames. A camera that fell off the bus comes back behind a new file handle, so the old one is no use.
+//
+// The new `Device` is returned too, for the camera to switch over to.
+fn reopen_stream(index: &CameraIndex, format: &Format, params: Option<&Parameters>, dmabuf: bool, timeout: Duration) -> NokhwaResult<(CaptureBuffers, Device)> {
+    let device = open_device(index)?;
+    device.set_format(format).map_err(|why| NokhwaError::OpenStreamError(format!("Failed to reapply the format: {why}")))?;
+    if let Some(params) = params {
+        device.set_params(params).map_err(|why| NokhwaError::OpenStreamError(format!("Failed to reapply the frame rate: {why}")))?;
     }
+    let buffers = CaptureBuffers::new(&device, dmabuf, timeout)?;
+    Ok((buffers, device))
 }
 
-impl StreamInnerTrait for V4L2Stream {
-    fn configuration(&self) -> &Option<StreamConfiguration> {
-        &None
+// A dequeued buffer a DMABUF frame points at. The driver doesn't get it back before every frame holding it is dropped.
+struct HeldBuffer {
+    // the frame's own descriptor of the buffer, so it stays valid after the stream moved on.
+    fd: OwnedFd,
+    index: usize,
+    // where the stream picks the buffer up again to requeue it.
+    release: Sender<usize>,
+}
+
+impl Drop for HeldBuffer {
+    fn drop(&mut self) {
+        // the stream may be gone already, in which case its buffers were released with it.
+        let _ = self.release.send(self.index);
     }
+}
 
+// A capture stream whose frames carry DMABUFs of the driver's buffers instead of copies of them.
+//
+// `MmapStream::next` requeues the buffer it returned last on its next call, however long a frame still points at it,
+// so the buffers are queued and dequeued by hand here instead: a buffer goes back to the driver once the last frame
+// holding it (its `HeldBuffer`) is dropped.
+struct DmabufCapture {
+    // allocates the buffers (and streams off and frees them when dropped).
+    mmap_stream: MmapStream<'static>,
+    // the exported buffers, in buffer index order.
+    dmabufs: Vec<OwnedFd>,
+    release: Sender<usize>,
+    released: Receiver<usize>,
+    // how many buffers the driver currently has to fill.
+    queued: usize,
+    timeout: Duration,
+}
 
-    fn receiver(&self) -> Arc<Receiver<NokhwaResult<FrameBuffer>>> {
-        self.receiver.clone()
+impl DmabufCapture {
+    // Queues every buffer of `mmap_stream` and starts streaming.
+    fn start(mut mmap_stream: MmapStream<'static>, dmabufs: Vec<OwnedFd>, timeout: Duration) -> std::io::Result<Self> {
+        for index in 0..dmabufs.len() {
+            CaptureStream::queue(&mut mmap_stream, index)?;
+        }
+        mmap_stream.start()?;
+        let (release, released) = unbounded();
+        Ok(DmabufCapture {
+            mmap_stream,
+            queued: dmabufs.len(),
+            dmabufs,
+            release,
+            released,
+            timeout,
+        })
     }
 
-    fn stop(&mut self) -> NokhwaResult<()> {
-        self.control.send(()).map_err(|why| NokhwaError::StreamShutdownError(why.to_string()))?;
-        loop {
-            if self.thread.is_finished() {
-                break;
+    // Dequeues the next filled buffer, after handing the driver back whatever buffers frames let go of.
+    //
+    // Returns `None` if every buffer is still held by a frame after waiting for one the whole timeout.
+    fn next(&mut self) -> std::io::Result<Option<(HeldBuffer, BufferMetadata)>> {
+        if self.queued == 0 {
+            match self.released.recv_timeout(self.timeout) {
+                Ok(index) => self.requeue(index)?,
+                Err(_) => return Ok(None),
             }
-            sleep(Duration::from_millis(1))
         }
+        while let Ok(index) = self.released.try_recv() {
+            self.requeue(index)?;
+        }
+
+        let device = self.mmap_stream.handle();
+        let timeout = i32::try_from(self.timeout.as_millis()).unwrap_or(i32::MAX);
+        if device.poll(libc::POLLIN, timeout)? == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "VIDIOC_DQBUF"));
+        }
+        let mut buffer = v4l2_buffer {
+            type_: v4l::buffer::Type::VideoCapture as u32,
+            memory: v4l::Memory::Mmap as u32,
+            // SAFETY: v4l2_buffer is a plain C struct, all zeroes is a valid value.
+            ..unsafe { std::mem::zeroed() }
+        };
+        // SAFETY: `buffer` outlives the call and is the argument type VIDIOC_DQBUF expects.
+        unsafe {
+            v4l::v4l2::ioctl(device.fd(), v4l::v4l2::vidioc::VIDIOC_DQBUF, &mut buffer as *mut _ as *mut std::os::raw::c_void)?;
+        }
+        self.queued -= 1;
+
+        let index = buffer.index as usize;
+        let fd = match self.dmabufs.get(index).map(OwnedFd::try_clone) {
+            Some(Ok(fd)) => fd,
+            failed => {
+                // no frame will hold this buffer, so it can go straight back.
+                self.requeue(index)?;
+                return Err(match failed {
+                    Some(Err(why)) => why,
+                    _ => std::io::Error::other(format!("Dequeued buffer {index}, which has no DMABUF")),
+                });
+            }
+        };
+        let meta = BufferMetadata {
+            // some drivers don't fill in `bytesused`, in which case the whole buffer is the frame.
+            bytesused: if buffer.bytesused == 0 { buffer.length } else { buffer.bytesused },
+            flags: buffer.flags.into(),
+            field: buffer.field,
+            timestamp: buffer.timestamp.into(),
+            sequence: buffer.sequence,
+        };
+        Ok(Some((HeldBuffer { fd, index, release: self.release.clone() }, meta)))
+    }
+
+    fn requeue(&mut self, index: usize) -> std::io::Result<()> {
+        CaptureStream::queue(&mut self.mmap_stream, index)?;
+        self.queued += 1;
         Ok(())
     }
 }
 
+// The buffers a capture stream dequeues its frames from.
+enum CaptureBuffers {
+    // frames are copies of the driver's buffers.
+    Mmap(MmapStream<'static>),
+    Dmabuf(DmabufCapture),
+}
+
+impl CaptureBuffers {
+    // Allocates the stream's buffers on `device`, exporting them as DMABUFs if `dmabuf` is set. A dequeue that waits
+    // longer than `timeout` fails.
+    fn new(device: &Device, dmabuf: bool, timeout: Duration) -> NokhwaResult<Self> {
+        // the arena only borrows the device's handle, which the stream keeps alive itself.
+        let mut mmap_stream: MmapStream<'static> = MmapStream::with_buffers(device, v4l::buffer::Type::VideoCapture, STREAM_BUFFER_COUNT).map_err(|why| {
+            NokhwaError::OpenStreamError(why.to_string())
+        })?;
+        if !dmabuf {
+            mmap_stream.set_timeout(timeout);
+            return Ok(CaptureBuffers::Mmap(mmap_stream));
+        }
+        let dmabufs = export_dmabufs(&mmap_stream.handle()).map_err(|why| {
+            NokhwaError::OpenStreamError(format!("Failed to export DMABUFs: {why}"))
+        })?;
+        DmabufCapture::start(mmap_stream, dmabufs, timeout).map(CaptureBuffers::Dmabuf).map_err(|why| {
+            NokhwaError::OpenStreamError(format!("Failed to start streaming: {why}"))
+        })
+    }
+
+    // Dequeues the next frame. `None` means there is no buffer to dequeue into, as frames still hold all of them.
+    fn next_frame(&mut self, resolution: Resolution, frame_format: FrameFormat, color: (Option<ColorMatrix>, Option<QuantRange>)) -> std::io::Result<Option<FrameBuffer>> {
+        match self {
+            CaptureBuffers::Mmap(mmap_stream) => {
+                let (data, meta) = mmap_stream.next()?;
+                let (data, metadata) = dequeued_frame(data, meta, color);
+                Ok(Some(FrameBuffer::new(resolution, data.to_vec(), frame_format, None).with_metadata(metadata)))
+            }
+            CaptureBuffers::Dmabuf(capture) => {
+                let Some((held, meta)) = capture.next()? else {
+                    return Ok(None);
+                };
+                let metadata = dequeued_metadata(&meta, color)
+                    .with_dmabuf_fd(held.fd.as_raw_fd())
+                    .with_dmabuf_length(meta.bytesused as usize);
+                Ok(Some(FrameBuffer::new(resolution, vec![], frame_format, None).with_metadata(metadata).with_backing(Arc::new(held))))
+            }
+        }
+    }
+}
+
+// The YCbCr matrix and range a format says its frames use, following the kernel's defaults for the colorspace.
+fn format_color(format: &Format) -> (Option<ColorMatrix>, Option<QuantRange>) {
+    let matrix = match format.colorspace {
+        Colorspace::SMPTE170M | Colorspace::NTSC | Colorspace::EBUTech3212 | Colorspace::JPEG | Colorspace::SRGB => Some(ColorMatrix::Bt601),
+        Colorspace::Rec709 | Colorspace::DCIP3 => Some(ColorMatrix::Bt709),
+        Colorspace::Rec2020 => Some(ColorMatrix::Bt2020),
+        _ => None,
+    };
+    let range = match format.quantization {
+        Quantization::FullRange => Some(QuantRange::Full),
+        Quantization::LimitedRange => Some(QuantRange::Limited),
+        // only JPEG defaults to full range YCbCr.
+        Quantization::Default if matches!(format.colorspace, Colorspace::JPEG) => Some(QuantRange::Full),
+        Quantization::Default if matrix.is_some() => Some(QuantRange::Limited),
+        Quantization::Default => None,
+    };
+    (matrix, range)
+}
+
+// The metadata of a dequeued buffer.
+fn dequeued_metadata(meta: &BufferMetadata, color: (Option<ColorMatrix>, Option<QuantRange>)) -> Metadata {
+    let mut metadata = Metadata::new().with_sequence(u64::from(meta.sequence));
+    if let (Ok(sec), Ok(usec)) = (u64::try_from(meta.timestamp.sec), u64::try_from(meta.timestamp.usec)) {
+        metadata.set_timestamp(Duration::from_secs(sec) + Duration::from_micros(usec));
+    }
+    if let Some(matrix) = color.0 {
+        metadata.set_color_matrix(matrix);
+    }
+    if let Some(range) = color.1 {
+        metadata.set_quant_range(range);
+    }
+    metadata
+}
+
+// The frame data and metadata of a dequeued buffer.
+fn dequeued_frame<'a>(data: &'a [u8], meta: &BufferMetadata, color: (Option<ColorMatrix>, Option<QuantRange>)) -> (&'a [u8], Metadata) {
+    // some drivers don't fill in `bytesused`, in which case the whole buffer is the frame.
+    let used = match meta.bytesused as usize {
+        0 => data.len(),
+        used => used.min(data.len()),
+    };
+    (&data[..used], dequeued_metadata(meta, color))
+}
+
+struct V4L2Stream {
+    thread: Option<JoinHandle<()>>,
+    control: Sender<()>,
+    handle: Arc<StreamHandle>,
+    // the devices the capture thread reopened after losing the previous one, newest last.
+    reopened: Receiver<Device>,
+}
+
+impl Drop for V4L2Stream {
+    fn drop(&mut self) {
+        let _ = self.control.try_send(());
+    }
+}
+
 impl Capture for V4L2Camera {
-    fn open_stream(&mut self) -> Result<Arc<StreamHandle>, NokhwaError> {
+    fn open_stream_with(&mut self, configuration: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError> {
         if self.stream.is_some() {
             return Err(NokhwaError::OpenStreamError("Stream Already Open".to_string()))
         }
 
-
-        let format = match self.camera_format {
+        // without a format set through us, stream whatever the driver is configured for.
+        let format = match self.current_format() {
             Some(fmt) => fmt,
             None => return Err(NokhwaError::OpenStreamError("No Format".to_string()))
         };
-
         let (control, ctrl_recv) = bounded::<()>(1);
-        let (sender, receiver) = unbounded();
-        let receiver = Arc::new(receiver);
+        let (reopened_send, reopened) = unbounded::<Device>();
+        let (sender, receiver) = event_channel(&configuration.bound);
+        let stats = sender.stats_monitor();
 
+        let controls = (!configuration.skip_control_restore).then(|| self.controls.clone());
         self.set_format(format)?;
+        if let Some(controls) = controls {
+            self.restore_controls(&controls);
+        }
 
-        let mut mmap_stream = MmapStream::new(&self.device, v4l::buffer::Type::VideoCapture).map_err(|why| {
-            return NokhwaError::OpenStreamError(why.to_string())
-        })?;
+        let timeout = dequeue_timeout(*format.frame_rate());
+        let buffers = CaptureBuffers::new(&self.device, configuration.dmabuf, timeout)?;
 
-        let thread = std::thread::spawn(move || {
-            loop {
-                if ctrl_recv.is_disconnected() || sender.is_disconnected() {
-                    return;
-                }
-                if let Ok(_) = ctrl_recv.try_recv() {
-                    return;
-                }
+        let resolution = *format.resolution();
+        let frame_format = *format.format();
+        let driver_format = self.device.format().ok();
+        let color = driver_format.as_ref().map(format_color).unwrap_or_default();
+        // what a reconnect needs to bring the stream back the way it was. Without it, there is no reconnecting.
+        let reopen = driver_format.map(|driver_format| (self.camera_index.clone(), driver_format, self.device.params().ok(), configuration.dmabuf));
+        let reconnect = if reopen.is_some() { configuration.reconnect.clone() } else { ReconnectPolicy::Never };
 
-                match mmap_stream.next() {
-                    Ok((data, _meta)) => { // TODO: Add metadata 
-                        if let Err(_why) = sender.send(Ok(FrameBuffer::new(data))) {
-                            return ();
+        let thread = std::thread::spawn(move || {
+            // Frames are dropped when the consumer falls behind, every other event waits for room in the channel.
+            // That wait ends early on a stop request, and once stopped nothing may block on a full channel anymore,
+            // or `close_stream` could never join this thread.
+            let deliver = |event: Event| sender.send_or_stop(event, &ctrl_recv);
+
+            // A panic in here would otherwise only be visible through the `JoinHandle`, leaving the
+            // consumer with a bare `Closed`. Tell them what happened before going away.
+            //
+            // returns whether the stream was stopped (or its consumer went away), rather than giving up on the device.
+            let capture = catch_unwind(AssertUnwindSafe(|| {
+                reconnect.drive(buffers, &ctrl_recv, deliver, |mut buffers| {
+                    let mut delivered = false;
+                    let mut consecutive_errors = 0;
+                    while consecutive_errors < MAX_CONSECUTIVE_CAPTURE_ERRORS {
+                        match ctrl_recv.try_recv() {
+                            Ok(()) | Err(TryRecvError::Disconnected) => return CaptureOutcome::Stopped,
+                            Err(TryRecvError::Empty) => {}
                         }
-                    }
-                    Err(why) => {
-                        if let Err(_why) = sender.send(Err(NokhwaError::ReadFrameError(why.to_string()))) {
-                            return ();
+
+                        // a timed out dequeue is a capture error like any other.
+                        match buffers.next_frame(resolution, frame_format, color) {
+                            Ok(Some(frame)) => {
+                                consecutive_errors = 0;
+                                delivered = true;
+                                match sender.try_send(Event::NewFrame(frame)) {
+                                    // a full `StreamBounds::Bounded` channel means the consumer fell behind, drop
+                                    // this frame instead of stalling the driver.
+                                    Ok(()) | Err(TrySendError::Full(_)) => {}
+                                    // nobody is listening anymore.
+                                    Err(TrySendError::Disconnected(_)) => return CaptureOutcome::Stopped,
+                                }
+                            }
+                            // the consumer is holding on to every DMABUF frame, the device is fine.
+                            Ok(None) => {}
+                            Err(why) => {
+                                if !deliver(Event::Error(NokhwaError::ReadFrameError(why.to_string()))) {
+                                    return CaptureOutcome::Stopped;
+                                }
+                                consecutive_errors += 1;
+                            }
                         }
                     }
-                }
+                    // the device stopped delivering frames. Dropping `buffers` here releases them, which the driver
+                    // needs before it hands out new ones, even to another file handle.
+                    CaptureOutcome::Lost { delivered }
+                }, || {
+                    let (index, driver_format, params, dmabuf) = reopen.as_ref().ok_or_else(|| {
+                        NokhwaError::OpenStreamError("the stream's format could not be read back".to_string())
+                    })?;
+                    let (buffers, device) = reopen_stream(index, driver_format, params.as_ref(), *dmabuf, timeout)?;
+                    // the camera is still holding on to the old device, hand it the new one.
+                    let _ = reopened_send.send(device);
+                    Ok(buffers)
+                })
+            }));
+
+            let stopped = match capture {
+                Ok(stopped) => stopped,
+                Err(payload) => !deliver(Event::Other(format!("V4L2 capture thread panicked: {}", panic_message(payload.as_ref())))),
+            };
+            if stopped {
+                let _ = sender.try_send(Event::Terminating);
+                let _ = sender.try_send(Event::Closed);
+            } else if deliver(Event::Terminating) {
+                deliver(Event::Closed);
             }
-            return ();
         });
-        
-        let stream = Arc::new(StreamHandle::new(Box::new(V4L2Stream {
-            thread,
+
+        let handle = Arc::new(StreamHandle::new(receiver, control.clone(), configuration, format).with_stats_monitor(stats));
+
+        self.stream = Some(V4L2Stream {
+            thread: Some(thread),
             control,
-            receiver,
-        })));
-        
-        self.stream = Some(stream.clone());
-        Ok(stream)
+            handle: handle.clone(),
+            reopened,
+        });
+        Ok(handle)
     }
 
     fn close_stream(&mut self) -> Result<(), NokhwaError> {
-        if let Some(stream) = self.stream.clone() {
-            stream.stop_stream()?;
-            
+        let Some(mut stream) = self.stream.take() else {
+            return Ok(());
+        };
+
+        // a full control channel means a stop was already requested, and a disconnected one means the
+        // thread is already gone - either way the thread is on its way out.
+        let _ = stream.control.try_send(());
+        let Some(thread) = stream.thread.take() else {
+            return Ok(());
+        };
+        // the capture thread owns (and drops) the mmap stream, so once it's joined the buffers are released.
+        let joined = thread.join().map_err(|payload| {
+            NokhwaError::StreamShutdownError(format!("V4L2 capture thread panicked: {}", panic_message(payload.as_ref())))
+        });
+        if let Some(device) = stream.reopened.try_iter().last() {
+            self.device = device;
         }
-        Ok(())
+        joined
+    }
+
+    fn stream_handle(&self) -> Option<Arc<StreamHandle>> {
+        self.stream.as_ref().map(|stream| stream.handle.clone())
+    }
+
+    // a single buffer is dequeued on the calling thread, no capture thread or channel involved.
+    fn capture_single_frame(&mut self) -> Result<FrameBuffer, NokhwaError> {
+        if self.stream.is_some() {
+            return Err(NokhwaError::OpenStreamError("Stream Already Open".to_string()))
+        }
+
+        let format = match self.current_format() {
+            Some(fmt) => fmt,
+            None => return Err(NokhwaError::OpenStreamError("No Format".to_string()))
+        };
+        let controls = self.controls.clone();
+        self.set_format(format)?;
+        self.restore_controls(&controls);
+        let color = self.device.format().map(|format| format_color(&format)).unwrap_or_default();
+
+        // dropping the stream turns streaming back off and releases the buffer.
+        let mut mmap_stream = MmapStream::with_buffers(&self.device, v4l::buffer::Type::VideoCapture, 1).map_err(|why| {
+            NokhwaError::OpenStreamError(why.to_string())
+        })?;
+        // a device that stopped delivering frames shouldn't hang the caller.
+        mmap_stream.set_timeout(dequeue_timeout(*format.frame_rate()));
+        let (data, meta) = mmap_stream.next().map_err(|why| NokhwaError::ReadFrameError(why.to_string()))?;
+        let (data, metadata) = dequeued_frame(data, meta, color);
+        Ok(FrameBuffer::new(*format.resolution(), data.to_vec(), *format.format(), Some(metadata)))
     }
 }
 
 impl Camera for V4L2Camera {}
+
+#[cfg(feature = "async")]
+impl AsyncSetting for V4L2Camera {
+    async fn enumerate_formats_async(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+        let index = self.camera_index.clone();
+        run_blocking(move || enumerate_formats_within(&open_device(&index)?, &FormatLimits::none())).await
+    }
+
+    async fn enumerate_resolution_and_frame_rates_async(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
+        let index = self.camera_index.clone();
+        run_blocking(move || enumerate_resolution_and_frame_rates_within(&open_device(&index)?, frame_format, &FormatLimits::none())).await
+    }
+
+    // the format belongs to the device rather than the file handle, so setting it through a handle of its own works
+    // like `Setting::set_format`.
+    async fn set_format_async(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        self.adopt_reopened_device();
+        let index = self.camera_index.clone();
+        run_blocking(move || apply_format(&open_device(&index)?, camera_format)).await?;
+        self.camera_format = Some(camera_format);
+        Ok(())
+    }
+
+    async fn properties_async(&self) -> &Controls {
+        &self.controls
+    }
+
+    async fn set_property_async(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+        self.set_control(property, value)
+    }
+}
+
+// Runs blocking ioctls on their own thread so they don't stall the async runtime.
+// The work opens its own handle to the device, so if the future is dropped the thread just finishes and closes that
+// handle. A format set that way still applies, the camera just doesn't know it was set.
+#[cfg(feature = "async")]
+async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> NokhwaResult<T> + Send + 'static) -> NokhwaResult<T> {
+    let (sender, receiver) = bounded(1);
+    std::thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+
+    receiver.recv_async().await.map_err(|_| NokhwaError::GeneralError("V4L2 worker thread exited without a result".to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dequeue_timeout_covers_a_few_frames_within_bounds() {
+        assert_eq!(dequeue_timeout(FrameRate::frame_rate(30)), MIN_DEQUEUE_TIMEOUT);
+        assert_eq!(dequeue_timeout(FrameRate::frame_rate(1)), Duration::from_secs(4));
+        assert_eq!(dequeue_timeout(FrameRate::new(1, NonZeroI32::new(60).unwrap())), MAX_DEQUEUE_TIMEOUT);
+        // a zero frame rate is an infinitely long interval.
+        assert_eq!(dequeue_timeout(FrameRate::frame_rate(0)), MAX_DEQUEUE_TIMEOUT);
+    }
+
+    #[test]
+    fn stepwise_values_are_sampled_down() {
+        assert_eq!(stepwise_values(10, 30, 10), vec![10, 20, 30]);
+        assert_eq!(stepwise_values(10, 35, 10), vec![10, 20, 30]);
+        assert_eq!(stepwise_values(10, 30, 0), vec![10, 30]);
+        assert_eq!(stepwise_values(30, 10, 1), Vec::<u32>::new());
+
+        let full = stepwise_values(0, u32::MAX, 1);
+        assert_eq!(full.len(), MAX_STEPWISE_ENTRIES as usize);
+        assert_eq!((full[0], full[full.len() - 1]), (0, u32::MAX));
+    }
+
+    #[test]
+    fn held_buffers_are_released_once_every_frame_holding_them_drops() {
+        let (release, released) = unbounded();
+        let held = HeldBuffer { fd: OwnedFd::from(fs::File::open("/dev/null").unwrap()), index: 2, release };
+        let metadata = Metadata::new().with_dmabuf_fd(held.fd.as_raw_fd());
+        let frame = FrameBuffer::new(Resolution::new(2, 2), vec![], FrameFormat::Yuyv422, Some(metadata)).with_backing(Arc::new(held));
+        let copy = frame.clone();
+
+        drop(frame);
+        assert_eq!(released.try_recv(), Err(TryRecvError::Empty));
+        // the descriptor is the frame's, so it is still open.
+        let fd = copy.metadata().and_then(Metadata::dmabuf_fd).unwrap();
+        // SAFETY: F_GETFD only reads the descriptor's flags.
+        assert_ne!(unsafe { libc::fcntl(fd, libc::F_GETFD) }, -1);
+        drop(copy);
+        assert_eq!(released.try_recv(), Ok(2));
+    }
+
+    #[test]
+    fn v4l2_values_round_trip_through_control_values() {
+        let pairs = || [
+            (Value::None, ControlValue::Null),
+            (Value::Integer(-3), ControlValue::Integer(-3)),
+            (Value::Boolean(true), ControlValue::Boolean(true)),
+            (Value::String("auto".to_string()), ControlValue::String("auto".to_string())),
+        ];
+        for (value, control_value) in pairs() {
+            assert_eq!(value_to_control_value(value), control_value);
+        }
+        for (value, control_value) in pairs() {
+            assert_eq!(control_value_to_value(&control_value).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn compound_values_become_integer_arrays() {
+        assert_eq!(
+            value_to_control_value(Value::CompoundU16(vec![1, u16::MAX])),
+            ControlValue::Array(vec![ControlValue::Integer(1), ControlValue::Integer(i64::from(u16::MAX))])
+        );
+        assert_eq!(
+            value_to_control_value(Value::CompoundU32(vec![u32::MAX])),
+            ControlValue::Array(vec![ControlValue::Integer(i64::from(u32::MAX))])
+        );
+        // which compound type an array came from is lost, so there is no way back.
+        assert!(control_value_to_value(&ControlValue::Array(vec![ControlValue::Integer(1)])).is_err());
+    }
+
+    #[test]
+    fn control_values_convert_to_what_set_control_sends() {
+        // menus are set by the index of the item.
+        assert_eq!(control_value_to_value(&ControlValue::EnumPick(Box::new(ControlValue::Integer(2)))).unwrap(), Value::Integer(2));
+        assert_eq!(control_value_to_value(&ControlValue::BitMask(0b101)).unwrap(), Value::Integer(0b101));
+        assert_eq!(control_value_to_value(&ControlValue::Binary(vec![1, 2])).unwrap(), Value::CompoundU8(vec![1, 2]));
+
+        assert!(control_value_to_value(&ControlValue::EnumPick(Box::new(ControlValue::String("auto".to_string())))).is_err());
+        assert!(control_value_to_value(&ControlValue::Float(1.5.into())).is_err());
+        assert!(control_value_to_value(&ControlValue::Area { width: 1, height: 1 }).is_err());
+    }
+
+    fn assert_cid_round_trips(controls: &[(ControlId, u32)]) {
+        for (control_id, cid) in controls {
+            assert_eq!(control_id_to_cid(*control_id).unwrap(), *cid);
+            assert_eq!(control_id_to_cid_ref(control_id).unwrap(), *cid);
+            assert_eq!(cid_to_control_id(*cid), *control_id);
+        }
+    }
+
+    #[test]
+    fn image_controls_round_trip_through_their_cids() {
+        assert_cid_round_trips(&[
+            (ControlId::Brightness, V4L2_CID_BRIGHTNESS),
+            (ControlId::Contrast, V4L2_CID_CONTRAST),
+            (ControlId::Saturation, V4L2_CID_SATURATION),
+            (ControlId::Hue, V4L2_CID_HUE),
+            (ControlId::Gamma, V4L2_CID_GAMMA),
+            (ControlId::Sharpness, V4L2_CID_SHARPNESS),
+            (ControlId::BacklightCompensation, V4L2_CID_BACKLIGHT_COMPENSATION),
+        ]);
+        // anything else is passed through as is.
+        assert_eq!(cid_to_control_id(0x00ff_0042), ControlId::PlatformSpecific(0x00ff_0042));
+        assert_eq!(control_id_to_cid(ControlId::PlatformSpecific(0x00ff_0042)).unwrap(), 0x00ff_0042);
+        assert!(control_id_to_cid(ControlId::PlatformSpecific(u64::MAX)).is_err());
+    }
+
+    fn description(id: u32, typ: Type, items: Option<Vec<(u32, MenuItem)>>) -> Description {
+        Description {
+            id,
+            typ,
+            name: String::new(),
+            minimum: -10,
+            maximum: 10,
+            step: 2,
+            default: 0,
+            flags: Flags::empty(),
+            items,
+        }
+    }
+
+    #[test]
+    fn ptz_controls_round_trip_through_their_cids() {
+        assert_cid_round_trips(&[
+            (ControlId::PanAbsolute, V4L2_CID_PAN_ABSOLUTE),
+            (ControlId::PanRelative, V4L2_CID_PAN_RELATIVE),
+            (ControlId::TiltAbsolute, V4L2_CID_TILT_ABSOLUTE),
+            (ControlId::TiltRelative, V4L2_CID_TILT_RELATIVE),
+            (ControlId::RollAbsolute, V4L2_CID_ROLL_ABSOLUTE),
+            (ControlId::ZoomAbsolute, V4L2_CID_ZOOM_ABSOLUTE),
+            (ControlId::ZoomRelative, V4L2_CID_ZOOM_RELATIVE),
+            (ControlId::ZoomContinuous, V4L2_CID_ZOOM_CONTINUOUS),
+        ]);
+    }
+
+    #[test]
+    fn relative_pan_and_tilt_buttons_take_a_step() {
+        for cid in [V4L2_CID_PAN_RELATIVE, V4L2_CID_TILT_RELATIVE] {
+            let control = convert_description_to_ctrl_body(description(cid, Type::Button, None)).unwrap();
+            assert_eq!(*control.descriptor(), ControlValueDescriptor::Integer(Range::new(-10, 10, Some(2))));
+            assert_eq!(*control.default_value(), None);
+        }
+        let button = convert_description_to_ctrl_body(description(V4L2_CID_FLASH_STROBE, Type::Button, None)).unwrap();
+        assert_eq!(*button.descriptor(), ControlValueDescriptor::Null);
+    }
+
+    #[test]
+    fn menus_map_item_indices_to_their_names_or_values() {
+        let items = vec![(0, MenuItem::Name("50 Hz".to_string())), (1, MenuItem::Name("60 Hz".to_string()))];
+        let menu = convert_description_to_ctrl_body(description(V4L2_CID_POWER_LINE_FREQUENCY, Type::Menu, Some(items))).unwrap();
+        let expected = HashMap::from([
+            (ControlValue::Integer(0), ControlValue::String("50 Hz".to_string())),
+            (ControlValue::Integer(1), ControlValue::String("60 Hz".to_string())),
+        ]);
+        assert_eq!(*menu.descriptor(), ControlValueDescriptor::Menu(expected));
+        assert_eq!(*menu.default_value(), Some(ControlValue::EnumPick(Box::new(ControlValue::Integer(0)))));
+
+        let items = vec![(0, MenuItem::Value(100)), (1, MenuItem::Value(200))];
+        let menu = convert_description_to_ctrl_body(description(V4L2_CID_ISO_SENSITIVITY, Type::IntegerMenu, Some(items))).unwrap();
+        let expected = HashMap::from([
+            (ControlValue::Integer(0), ControlValue::Integer(100)),
+            (ControlValue::Integer(1), ControlValue::Integer(200)),
+        ]);
+        assert_eq!(*menu.descriptor(), ControlValueDescriptor::Menu(expected));
+
+        // a menu without items can't be described.
+        assert!(convert_description_to_ctrl_body(description(V4L2_CID_POWER_LINE_FREQUENCY, Type::Menu, None)).is_none());
+    }
+
+    #[test]
+    fn orientations_follow_the_v4l2_menu() {
+        assert_eq!(orientation_from_v4l2(0), Orientation::User);
+        assert_eq!(orientation_from_v4l2(1), Orientation::Environment);
+        assert_eq!(orientation_from_v4l2(2), Orientation::Other);
+        assert_eq!(orientation_from_v4l2(7), Orientation::Custom(7));
+
+        let items = vec![(0, MenuItem::Name("Front".to_string())), (2, MenuItem::Name("External".to_string()))];
+        let mut orientation = description(V4L2_CID_CAMERA_ORIENTATION, Type::Menu, Some(items));
+        orientation.default = 2;
+        let orientation = convert_description_to_ctrl_body(orientation).unwrap();
+        assert_eq!(*orientation.descriptor(), ControlValueDescriptor::Orientation(vec![Orientation::User, Orientation::Other]));
+        assert_eq!(*orientation.default_value(), Some(ControlValue::Orientation(Orientation::Other)));
+    }
+
+    fn frame_size_stepwise(min: (u32, u32), max: (u32, u32), step: (u32, u32)) -> FrameSizeStepwise {
+        FrameSizeStepwise {
+            min_width: min.0,
+            max_width: max.0,
+            step_width: step.0,
+            min_height: min.1,
+            max_height: max.1,
+            step_height: step.1,
+        }
+    }
+
+    #[test]
+    fn stepwise_resolutions_are_the_bounds_and_fitting_standard_sizes() {
+        let resolutions = stepwise_resolutions(frame_size_stepwise((160, 120), (1280, 960), (16, 8)));
+        let expected = [
+            (160, 120), (176, 144), (320, 240), (352, 288), (640, 360), (640, 480), (800, 600), (1024, 768), (1280, 720),
+            (1280, 960),
+        ];
+        assert_eq!(resolutions, expected.map(|(width, height)| Resolution::new(width, height)).to_vec());
+
+        // no standard size is on this grid.
+        let resolutions = stepwise_resolutions(frame_size_stepwise((100, 100), (2100, 2100), (1000, 1000)));
+        assert_eq!(resolutions, vec![Resolution::new(100, 100), Resolution::new(2100, 2100)]);
+        // a zero step only allows the bounds themselves.
+        let resolutions = stepwise_resolutions(frame_size_stepwise((320, 240), (640, 480), (0, 0)));
+        assert_eq!(resolutions, vec![Resolution::new(320, 240), Resolution::new(640, 480)]);
+
+        assert!(stepwise_resolutions(frame_size_stepwise((640, 480), (320, 240), (1, 1))).is_empty());
+    }
+}
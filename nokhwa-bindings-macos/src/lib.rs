@@ -644,6 +644,7 @@ mod internal {
                     Err(NokhwaError::GetPropertyError {
                         property: "AVMediaType".to_string(),
                         error: format!("Invalid AVMediaType {name}"),
+                        source: None,
                     })
                 }
             }
@@ -860,6 +861,7 @@ mod internal {
                         None => Err(NokhwaError::OpenDeviceError(
                             idx.to_string(),
                             "Not Found".to_string(),
+                            None,
                         )),
                     }
                 }
@@ -876,6 +878,7 @@ mod internal {
                 return Err(NokhwaError::OpenDeviceError(
                     id.to_string(),
                     "Device is null".to_string(),
+                    None,
                 ));
             }
             let camera_info = get_raw_device_info(
@@ -949,6 +952,7 @@ mod internal {
                     property: "lockForConfiguration".to_string(),
                     value: "Locked".to_string(),
                     error: "Cannot lock for configuration".to_string(),
+                    source: None,
                 });
             }
             // Space these out for debug purposes
@@ -957,6 +961,7 @@ mod internal {
                     property: "lockForConfiguration".to_string(),
                     value: "Locked".to_string(),
                     error: "Lock Rejected".to_string(),
+                    source: None,
                 });
             }
             Ok(())
@@ -1008,6 +1013,7 @@ mod internal {
                     property: "CameraFormat".to_string(),
                     value: descriptor.to_string(),
                     error: "Not Found/Rejected/Unsupported".to_string(),
+                    source: None,
                 });
             }
 
@@ -1506,6 +1512,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if isoctrl.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1515,6 +1522,7 @@ mod internal {
                             error:
                                 "Exposure is in improper state to set ISO (Please set to `custom`!)"
                                     .to_string(),
+                            source: None,
                         });
                     }
 
@@ -1523,6 +1531,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1531,6 +1540,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Expected float".to_string(),
+                        source: None,
                     })? as f32;
 
                     if !isoctrl.description().verify_setter(&value) {
@@ -1538,6 +1548,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1552,6 +1563,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if duration_ctrl
@@ -1563,6 +1575,7 @@ mod internal {
                             value: value.to_string(),
                             error: "Exposure is in improper state to set Duration (Please set to `custom`!)"
                                 .to_string(),
+                            source: None,
                         });
                     }
 
@@ -1574,6 +1587,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
                     let current_duration: CMTime =
@@ -1585,6 +1599,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected i64".to_string(),
+                            source: None,
                         })?,
                         timescale: current_duration.timescale,
                         flags: current_duration.flags,
@@ -1596,6 +1611,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1610,6 +1626,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if wb_enum_value
@@ -1620,6 +1637,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Read Only".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1631,6 +1649,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
                     let setter =
@@ -1638,6 +1657,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected Enum".to_string(),
+                            source: None,
                         })? as i32);
 
                     if !wb_enum_value.description().verify_setter(&value) {
@@ -1645,6 +1665,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1657,6 +1678,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1664,6 +1686,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Read Only".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1672,6 +1695,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1680,6 +1704,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected Enum".to_string(),
+                            source: None,
                         })? as i32);
 
                     if !ctrlvalue.description().verify_setter(&value) {
@@ -1687,6 +1712,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1699,6 +1725,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1706,6 +1733,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Read Only".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1714,6 +1742,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1722,6 +1751,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected Boolean".to_string(),
+                            source: None,
                         },
                     )? as i32);
 
@@ -1730,6 +1760,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1742,6 +1773,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1749,6 +1781,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Read Only".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1757,6 +1790,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1764,6 +1798,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Expected float".to_string(),
+                        source: None,
                     })? as c_float;
 
                     if !ctrlvalue.description().verify_setter(&value) {
@@ -1771,6 +1806,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1785,6 +1821,7 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1792,6 +1829,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Read Only".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1800,6 +1838,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1808,6 +1847,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected Enum".to_string(),
+                            source: None,
                         })? as i32);
 
                     if !ctrlvalue.description().verify_setter(&value) {
@@ -1815,6 +1855,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1826,12 +1867,14 @@ mod internal {
                     property: id.to_string(),
                     value: value.to_string(),
                     error: "Read Only".to_string(),
+                    source: None,
                 }),
                 KnownCameraControl::Focus => {
                     let ctrlvalue = controls.get(&id).ok_or(NokhwaError::SetPropertyError {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Control does not exist".to_string(),
+                        source: None,
                     })?;
 
                     if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1839,6 +1882,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Read Only".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1847,6 +1891,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Disabled".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1855,6 +1900,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected Enum".to_string(),
+                            source: None,
                         })? as i32);
 
                     if !ctrlvalue.description().verify_setter(&value) {
@@ -1862,6 +1908,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Failed to verify value".to_string(),
+                            source: None,
                         });
                     }
 
@@ -1875,6 +1922,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1882,6 +1930,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1890,6 +1939,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1899,6 +1949,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Expected Point".to_string(),
+                                source: None,
                             })
                             .map(|(x, y)| CGPoint {
                                 x: *x as f32,
@@ -1910,6 +1961,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1922,6 +1974,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1929,6 +1982,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1937,6 +1991,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1944,6 +1999,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected float".to_string(),
+                            source: None,
                         })? as c_float;
 
                         if !ctrlvalue.description().verify_setter(&value) {
@@ -1951,6 +2007,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1965,6 +2022,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -1972,6 +2030,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1980,6 +2039,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -1989,6 +2049,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Expected Point".to_string(),
+                                source: None,
                             })
                             .map(|(x, y)| CGPoint {
                                 x: *x as f32,
@@ -2000,6 +2061,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2013,6 +2075,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -2020,6 +2083,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2028,6 +2092,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2036,6 +2101,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Expected Boolean".to_string(),
+                                source: None,
                             })? {
                                 YES
                             } else {
@@ -2047,6 +2113,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2064,6 +2131,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -2071,6 +2139,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2079,6 +2148,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2086,6 +2156,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Expected Float".to_string(),
+                            source: None,
                         })? as f32;
 
                         if !ctrlvalue.description().verify_setter(&value) {
@@ -2093,6 +2164,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2107,6 +2179,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -2114,6 +2187,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2122,6 +2196,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2130,6 +2205,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Expected Enum".to_string(),
+                                source: None,
                             },
                         )? as i32);
 
@@ -2138,6 +2214,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2150,6 +2227,7 @@ mod internal {
                             property: id.to_string(),
                             value: value.to_string(),
                             error: "Control does not exist".to_string(),
+                            source: None,
                         })?;
 
                         if ctrlvalue.flag().contains(&KnownCameraControlFlag::ReadOnly) {
@@ -2157,6 +2235,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Read Only".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2165,6 +2244,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Disabled".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2173,6 +2253,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Expected Boolean".to_string(),
+                                source: None,
                             })? {
                                 YES
                             } else {
@@ -2184,6 +2265,7 @@ mod internal {
                                 property: id.to_string(),
                                 value: value.to_string(),
                                 error: "Failed to verify value".to_string(),
+                                source: None,
                             });
                         }
 
@@ -2197,12 +2279,14 @@ mod internal {
                         property: id.to_string(),
                         value: value.to_string(),
                         error: "Unknown Control".to_string(),
+                        source: None,
                     }),
                 },
                 _ => Err(NokhwaError::SetPropertyError {
                     property: id.to_string(),
                     value: value.to_string(),
                     error: "Unknown Control".to_string(),
+                    source: None,
                 }),
             }
         }
@@ -2231,6 +2315,7 @@ mod internal {
                 Err(NokhwaError::GetPropertyError {
                     property: "activeFormat".to_string(),
                     error: "None??".to_string(),
+                    source: None,
                 })
             }
         }
@@ -2314,6 +2399,7 @@ mod internal {
                 property: "AVCaptureDeviceInput".to_string(),
                 value: "add new input".to_string(),
                 error: "Rejected".to_string(),
+                source: None,
             })
         }
 
@@ -2335,6 +2421,7 @@ mod internal {
                 property: "AVCaptureVideoDataOutput".to_string(),
                 value: "add new output".to_string(),
                 error: "Rejected".to_string(),
+                source: None,
             })
         }
 
@@ -223,6 +223,7 @@ pub mod wmf {
             return Err(NokhwaError::GetPropertyError {
                 property: "IMFAttributes".to_string(),
                 error: why.to_string(),
+                source: None,
             });
         }
 
@@ -238,6 +239,7 @@ pub mod wmf {
                         property: "GUID MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE".to_string(),
                         value: "MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID".to_string(),
                         error: why.to_string(),
+                        source: None,
                     });
                 }
                 attr
@@ -248,6 +250,7 @@ pub mod wmf {
                     value: "MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID".to_string(),
                     error: "Call to IMFAttributes::SetGUID failed - IMFAttributes is None"
                         .to_string(),
+                    source: None,
                 });
             }
         };
@@ -301,6 +304,7 @@ pub mod wmf {
             return Err(NokhwaError::GetPropertyError {
                 property: "MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME".to_string(),
                 error: why.to_string(),
+                source: None,
             });
         }
 
@@ -314,6 +318,7 @@ pub mod wmf {
             return Err(NokhwaError::GetPropertyError {
                 property: "MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK".to_string(),
                 error: why.to_string(),
+                source: None,
             });
         }
 
@@ -321,12 +326,14 @@ pub mod wmf {
             return Err(NokhwaError::GetPropertyError {
                 property: "MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME".to_string(),
                 error: "Call to IMFActivate::GetAllocatedString failed - PWSTR is null".to_string(),
+                source: None,
             });
         }
         if pwstr_symlink.is_null() {
             return Err(NokhwaError::GetPropertyError {
                 property: "MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_SYMBOLIC_LINK".to_string(),
                 error: "Call to IMFActivate::GetAllocatedString failed - PWSTR is null".to_string(),
+                source: None,
             });
         }
 
@@ -432,6 +439,7 @@ pub mod wmf {
                                         return Err(NokhwaError::OpenDeviceError(
                                             index.to_string(),
                                             why.to_string(),
+                                            None,
                                         ))
                                     }
                                 }
@@ -440,6 +448,7 @@ pub mod wmf {
                                 return Err(NokhwaError::OpenDeviceError(
                                     index.to_string(),
                                     "No device".to_string(),
+                                    None,
                                 ))
                             }
                         };
@@ -472,6 +481,7 @@ pub mod wmf {
                                 property: "MF_READWRITE_DISABLE_CONVERTERS".to_string(),
                                 value: u32::from(true).to_string(),
                                 error: why.to_string(),
+                                source: None,
                             });
                         }
 
@@ -513,7 +523,7 @@ pub mod wmf {
 
                     match id_eq {
                         Some(index) => Self::new(CameraIndex::Index(index)),
-                        None => Err(NokhwaError::OpenDeviceError(s, "Not Found".to_string())),
+                        None => Err(NokhwaError::OpenDeviceError(s, "Not Found".to_string(), None)),
                     }
                 }
             }
@@ -572,6 +582,7 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: "MF_MT_SUBTYPE".to_string(),
                             error: why.to_string(),
+                            source: None,
                         })
                     }
                 };
@@ -586,6 +597,7 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: "MF_MT_FRAME_SIZE".to_string(),
                             error: why.to_string(),
+                            source: None,
                         })
                     }
                 };
@@ -661,6 +673,7 @@ pub mod wmf {
                         property: "MF_SOURCE_READER_MEDIASOURCE".to_string(),
                         value: "IAMCameraControl".to_string(),
                         error: why.to_string(),
+                        source: None,
                     });
                 }
                 receiver.assume_init()
@@ -678,6 +691,7 @@ pub mod wmf {
                         property: "MF_SOURCE_READER_MEDIASOURCE".to_string(),
                         value: "IAMVideoProcAmp".to_string(),
                         error: why.to_string(),
+                        source: None,
                     });
                 }
                 receiver.assume_init()
@@ -694,6 +708,7 @@ pub mod wmf {
                 property: "CameraControl".to_string(),
                 value: control.to_string(),
                 error: "Does not exist".to_string(),
+                source: None,
             })?;
 
             let ctrl_value_set = match control_id {
@@ -709,12 +724,14 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Range", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                     if let Err(why) = video_proc_amp.Get(id, &mut value, &mut flag) {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Value", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
 
@@ -737,12 +754,14 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Range", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                     if let Err(why) = video_proc_amp.Get(id, &mut value, &mut flag) {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Value", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                     ControlValueDescription::IntegerRange {
@@ -765,12 +784,14 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Range", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                     if let Err(why) = camera_control.Get(id, &mut value, &mut flag) {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Value", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
 
@@ -792,12 +813,14 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Range", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                     if let Err(why) = camera_control.Get(id, &mut value, &mut flag) {
                         return Err(NokhwaError::GetPropertyError {
                             property: format!("{:?}: {} - Value", control_id, control),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                     ControlValueDescription::IntegerRange {
@@ -847,6 +870,7 @@ pub mod wmf {
                         property: "MF_SOURCE_READER_MEDIASOURCE".to_string(),
                         value: "IAMCameraControl".to_string(),
                         error: why.to_string(),
+                        source: None,
                     });
                 }
                 receiver.assume_init()
@@ -864,6 +888,7 @@ pub mod wmf {
                         property: "MF_SOURCE_READER_MEDIASOURCE".to_string(),
                         value: "IAMVideoProcAmp".to_string(),
                         error: why.to_string(),
+                        source: None,
                     });
                 }
                 receiver.assume_init()
@@ -873,6 +898,7 @@ pub mod wmf {
                 property: "CameraControl".to_string(),
                 value: control.to_string(),
                 error: "Does not exist".to_string(),
+                source: None,
             })?;
 
             let ctrl_value = match value {
@@ -908,6 +934,7 @@ pub mod wmf {
                             property: control.to_string(),
                             value: ctrl_value.to_string(),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                 },
@@ -917,6 +944,7 @@ pub mod wmf {
                             property: control.to_string(),
                             value: ctrl_value.to_string(),
                             error: why.to_string(),
+                            source: None,
                         });
                     }
                 },
@@ -946,6 +974,7 @@ pub mod wmf {
                             return Err(NokhwaError::GetPropertyError {
                                 property: "MF_MT_FRAME_SIZE".to_string(),
                                 error: why.to_string(),
+                                source: None,
                             })
                         }
                     };
@@ -956,6 +985,7 @@ pub mod wmf {
                             return Err(NokhwaError::GetPropertyError {
                                 property: "MF_MT_FRAME_RATE".to_string(),
                                 error: why.to_string(),
+                                source: None,
                             })
                         }
                     };
@@ -967,6 +997,7 @@ pub mod wmf {
                                 return Err(NokhwaError::GetPropertyError {
                                     property: "MF_MT_SUBTYPE".to_string(),
                                     error: "Unknown".to_string(),
+                                    source: None,
                                 })
                             }
                         },
@@ -974,6 +1005,7 @@ pub mod wmf {
                             return Err(NokhwaError::GetPropertyError {
                                 property: "MF_MT_SUBTYPE".to_string(),
                                 error: why.to_string(),
+                                source: None,
                             })
                         }
                     };
@@ -986,6 +1018,7 @@ pub mod wmf {
                 Err(why) => Err(NokhwaError::GetPropertyError {
                     property: "MF_SOURCE_READER_FIRST_VIDEO_STREAM".to_string(),
                     error: why.to_string(),
+                    source: None,
                 }),
             }
         }
@@ -1013,6 +1046,7 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: "MF_MT_SUBTYPE".to_string(),
                             error: why.to_string(),
+                            source: None,
                         })
                     }
                 };
@@ -1036,6 +1070,7 @@ pub mod wmf {
                         return Err(NokhwaError::GetPropertyError {
                             property: "MF_MT_FRAME_SIZE".to_string(),
                             error: why.to_string(),
+                            source: None,
                         })
                     }
                 };
@@ -1099,6 +1134,7 @@ pub mod wmf {
                                     property: "MEDIA_FOUNDATION_FIRST_VIDEO_STREAM".to_string(),
                                     value: format!("{media_type:?}"),
                                     error: why.to_string(),
+                                    source: None,
                                 });
                             }
                         }
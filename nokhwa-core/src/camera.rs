@@ -1,21 +1,101 @@
-use crate::control::{ControlDescription, ControlId, ControlValue, Controls};
+use crate::control::{ControlDescription, ControlId, ControlValue, FlashStatus, FocusStatus};
+#[cfg(feature = "async")]
+use crate::control::Controls;
+use crate::decoder::Codec;
 use crate::error::NokhwaError;
+use crate::frame_buffer::FrameBuffer;
+use crate::format_request::{FormatLimits, FormatRequest};
 use crate::frame_format::FrameFormat;
-use crate::stream::StreamHandle;
+use crate::ranges::Range;
+use crate::stream::{StreamConfiguration, StreamHandle};
 use crate::types::{CameraFormat, FrameRate, Resolution};
+use std::borrow::Cow;
 use std::collections::hash_map::{Keys, Values};
 use std::collections::HashMap;
 use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::sync::{Mutex, PoisonError};
 
 pub trait Setting {
     fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError>;
 
+    /// [`Setting::enumerate_formats`], but skipping any format outside of `limits`.
+    ///
+    /// Backends should override this to apply the limits *before* expensive probing. The default
+    /// implementation just filters the output of [`Setting::enumerate_formats`].
+    /// # Errors
+    /// If the formats can't be read from the camera, this will error.
+    fn enumerate_formats_within(&self, limits: &FormatLimits) -> Result<Vec<CameraFormat>, NokhwaError> {
+        Ok(self.enumerate_formats()?.into_iter().filter(|format| limits.allows(format)).collect())
+    }
+
     fn enumerate_resolution_and_frame_rates(
         &self,
         frame_format: FrameFormat,
     ) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError>;
 
-    fn set_format(&self, camera_format: CameraFormat) -> Result<(), NokhwaError>;
+    /// Every [`FrameFormat`] the camera supports, sorted and without duplicates.
+    ///
+    /// Backends should override this with something cheaper than enumerating every format. The default
+    /// implementation collects them from [`Setting::enumerate_formats`].
+    /// # Errors
+    /// If the formats can't be read from the camera, this will error.
+    fn enumerate_frame_formats(&self) -> Result<Vec<FrameFormat>, NokhwaError> {
+        let mut frame_formats = self.enumerate_formats()?.iter().map(|format| *format.format()).collect::<Vec<FrameFormat>>();
+        frame_formats.sort();
+        frame_formats.dedup();
+        Ok(frame_formats)
+    }
+
+    /// Every [`Resolution`] the camera supports at `frame_format`, sorted and without duplicates.
+    ///
+    /// Backends should override this with something cheaper than enumerating every format. The default
+    /// implementation collects them from [`Setting::enumerate_formats`].
+    /// # Errors
+    /// If the formats can't be read from the camera, this will error.
+    fn enumerate_resolutions(&self, frame_format: FrameFormat) -> Result<Vec<Resolution>, NokhwaError> {
+        let mut resolutions = self.enumerate_formats()?.iter()
+            .filter(|format| *format.format() == frame_format)
+            .map(|format| *format.resolution())
+            .collect::<Vec<Resolution>>();
+        resolutions.sort();
+        resolutions.dedup();
+        Ok(resolutions)
+    }
+
+    /// Whether the camera supports `format`.
+    ///
+    /// Backends should override this with a check of just that format. The default implementation
+    /// scans [`Setting::enumerate_formats`].
+    /// # Errors
+    /// If the formats can't be read from the camera, this will error.
+    fn is_format_supported(&self, format: CameraFormat) -> Result<bool, NokhwaError> {
+        Ok(self.enumerate_formats()?.contains(&format))
+    }
+
+    /// The continuous frame rate range supported at `frame_format` and `resolution`.
+    ///
+    /// Returns `None` if the camera only reports discrete frame rates for this combination, in
+    /// which case [`Setting::enumerate_resolution_and_frame_rates`] is authoritative.
+    /// # Errors
+    /// If the frame rates can't be read from the camera, this will error.
+    fn frame_rate_range(
+        &self,
+        _frame_format: FrameFormat,
+        _resolution: Resolution,
+    ) -> Result<Option<Range<FrameRate>>, NokhwaError> {
+        Ok(None)
+    }
+
+    /// Sets the format the next stream opens with, see [`Setting::current_format`].
+    /// # Errors
+    /// If the camera doesn't support `camera_format`, or rejects it, this will error.
+    fn set_format(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError>;
+
+    /// The format the camera is currently configured for, if it is known.
+    ///
+    /// This is also available if [`Setting::set_format`] was never called, as far as the backend can ask the camera.
+    fn current_format(&self) -> Option<CameraFormat>;
 
     fn control_ids(&self) -> Keys<ControlId, ControlDescription>;
 
@@ -30,10 +110,47 @@ pub trait Setting {
     fn set_control(&mut self, property: &ControlId, value: ControlValue)
         -> Result<(), NokhwaError>;
 
+    /// Sets several controls at once.
+    ///
+    /// The default implementation applies `values` strictly in the given order, so put controls other controls
+    /// depend on (see [`ControlId::is_mode`]) first. Backends may reorder them to respect such dependencies.
+    /// A control that fails to set doesn't stop the rest from being set.
+    /// # Errors
+    /// Every control that failed to set, together with why.
+    fn set_controls(&mut self, values: &[(ControlId, ControlValue)]) -> Result<(), Vec<(ControlId, NokhwaError)>> {
+        let errors = values.iter().filter_map(|(id, value)| {
+            self.set_control(id, value.clone()).err().map(|why| (*id, why))
+        }).collect::<Vec<(ControlId, NokhwaError)>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn refresh_controls(&mut self) -> Result<(), NokhwaError>;
+
+    /// The state of the automatic focus, if the camera reports it.
+    ///
+    /// This reads the cached [`ControlId::FocusStatus`] value, call [`Setting::refresh_controls`] first
+    /// for an up-to-date status.
+    fn focus_status(&self) -> Option<FocusStatus> {
+        self.control_value(&ControlId::FocusStatus).and_then(FocusStatus::from_control_value)
+    }
+
+    /// The state of the flash, if the camera reports it.
+    ///
+    /// This reads the cached [`ControlId::LightingStatus`] value, call [`Setting::refresh_controls`] first
+    /// for an up-to-date status.
+    fn flash_status(&self) -> Option<FlashStatus> {
+        self.control_value(&ControlId::LightingStatus).and_then(FlashStatus::from_control_value)
+    }
 }
 
+// these futures are awaited by whoever holds the camera, there is no need for a `Send` bound.
 #[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
 pub trait AsyncSetting {
     async fn enumerate_formats_async(&self) -> Result<Vec<CameraFormat>, NokhwaError>;
 
@@ -42,7 +159,7 @@ pub trait AsyncSetting {
         frame_format: FrameFormat,
     ) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError>;
 
-    async fn set_format_async(&self, camera_format: CameraFormat) -> Result<(), NokhwaError>;
+    async fn set_format_async(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError>;
 
     async fn properties_async(&self) -> &Controls;
 
@@ -54,21 +171,720 @@ pub trait AsyncSetting {
 }
 
 pub trait Capture {
-    // Implementations MUST guarantee that there can only ever be one stream open at once.
-    fn open_stream(&mut self) -> Result<Arc<StreamHandle>, NokhwaError>;
+    /// [`Capture::open_stream_with`] using the default [`StreamConfiguration`].
+    ///
+    /// # Errors
+    /// See [`Capture::open_stream_with`].
+    fn open_stream(&mut self) -> Result<Arc<StreamHandle>, NokhwaError> {
+        self.open_stream_with(StreamConfiguration::default())
+    }
+
+    /// Opens a stream, configured by `configuration`.
+    ///
+    /// Implementations MUST guarantee that there can only ever be one stream open at once.
+    ///
+    /// # Errors
+    /// If a stream is already open, no format is set, or the backend fails to start streaming.
+    fn open_stream_with(&mut self, configuration: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError>;
 
     // Implementations MUST be multi-close tolerant.
     fn close_stream(&mut self) -> Result<(), NokhwaError>;
+
+    /// The currently open stream, if any.
+    fn stream_handle(&self) -> Option<Arc<StreamHandle>>;
+
+    /// Reads a single frame without keeping a stream around, e.g. for taking a picture.
+    ///
+    /// The default implementation opens a stream, reads one frame and closes the stream again. Backends may
+    /// override this with something cheaper.
+    /// # Errors
+    /// If a stream is already open, or the stream fails to open, read or close, this will error.
+    fn capture_single_frame(&mut self) -> Result<FrameBuffer, NokhwaError> {
+        if self.stream_handle().is_some() {
+            return Err(NokhwaError::OpenStreamError("Stream Already Open".to_string()))
+        }
+
+        let stream = self.open_stream()?;
+        let frame = stream.next_frame();
+        drop(stream);
+        let closed = self.close_stream();
+
+        let frame = frame?;
+        closed?;
+        Ok(frame)
+    }
+
+    /// Opens a stream whose frames are always delivered as `output`, whatever format the camera
+    /// negotiated.
+    ///
+    /// Frames are converted on a worker thread; see [`StreamHandle::converted`]. Only read from the returned handle,
+    /// not [`Capture::stream_handle`], or the two split the stream's frames between them.
+    /// # Errors
+    /// If the stream fails to open, or there is no conversion from the camera's format to `output`,
+    /// this will error. In the latter case the opened stream is closed again.
+    fn open_stream_as(&mut self, output: FrameFormat) -> Result<Arc<StreamHandle>, NokhwaError> {
+        let stream = self.open_stream()?;
+        match stream.converted(output) {
+            Ok(converted) => Ok(Arc::new(converted)),
+            Err(why) => {
+                drop(stream);
+                let _ = self.close_stream();
+                Err(why)
+            }
+        }
+    }
 }
 
+// see `AsyncSetting`.
 #[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
 pub trait AsyncStream {
-    async fn open_stream_async(&mut self) -> Result<StreamHandle, NokhwaError>;
+    async fn open_stream_async(&mut self) -> Result<Arc<StreamHandle>, NokhwaError>;
 
     async fn close_stream_async(&mut self) -> Result<(), NokhwaError>;
 }
 
-pub trait Camera: Setting + Capture {}
+pub trait Camera: Setting + Capture {
+    /// Switches the open stream over to the format picked by `request`, without reopening the camera.
+    ///
+    /// The current stream is closed (its handle receives [`crate::stream::Event::Terminating`] and
+    /// [`crate::stream::Event::Closed`] as usual), the format is set, and a new stream is opened with the
+    /// old stream's [`StreamConfiguration`] (or the default one if no stream was open). Controls are kept.
+    /// # Errors
+    /// If no format matches `request`, the current stream is left running and this will error.
+    /// It also errors if closing, setting the format, or opening the new stream fails.
+    fn restart_stream(&mut self, request: FormatRequest) -> Result<Arc<StreamHandle>, NokhwaError> {
+        let format = request.best_match(&self.enumerate_formats()?).ok_or_else(|| {
+            NokhwaError::OpenStreamError("No format matches the request".to_string())
+        })?;
+        let configuration = self.stream_handle().map(|stream| stream.configuration().clone()).unwrap_or_default();
+
+        self.close_stream()?;
+        self.set_format(format)?;
+        self.open_stream_with(configuration)
+    }
+
+    /// Opens a stream and hands its frames to `decoder`, see [`DecodingStream`].
+    /// # Errors
+    /// If the stream fails to open, the stream's format isn't one of the decoder's [`Codec::ALLOWED_FORMATS`], or the
+    /// decoder fails to initialize, this will error. In the latter two cases the opened stream is closed again.
+    fn open_decoded_stream<D: Codec>(&mut self, decoder: D) -> Result<DecodingStream<D>, NokhwaError> where Self: Sized {
+        let stream = self.open_stream()?;
+        match DecodingStream::new(stream, decoder) {
+            Ok(decoding) => Ok(decoding),
+            Err(why) => {
+                let _ = self.close_stream();
+                Err(why)
+            }
+        }
+    }
+
+    /// Takes a picture: grabs a single frame and decodes it into RGB888 data using
+    /// [`crate::frame_buffer::FrameBuffer::decode_to_rgb`].
+    ///
+    /// If no stream is open, one is opened for this frame and closed afterward. A stream that was
+    /// already open is left running.
+    /// # Errors
+    /// If the stream fails to open, read, or close, or the frame cannot be decoded, this will error.
+    fn snapshot_rgb(&mut self) -> Result<(Resolution, Vec<u8>), NokhwaError> {
+        let (stream, opened_here) = match self.stream_handle() {
+            Some(stream) => (stream, false),
+            None => (self.open_stream()?, true),
+        };
+
+        let frame = stream.next_frame();
+        drop(stream);
+        let closed = if opened_here {
+            self.close_stream()
+        } else {
+            Ok(())
+        };
+
+        let frame = frame?;
+        closed?;
+        Ok((frame.resolution(), frame.decode_to_rgb()?))
+    }
+}
+
+/// A stream whose frames are decoded as they are read, see [`Camera::open_decoded_stream`].
+#[derive(Debug)]
+pub struct DecodingStream<D: Codec> {
+    stream: Arc<StreamHandle>,
+    decoder: D,
+    format: CameraFormat,
+}
+
+impl<D: Codec> DecodingStream<D> {
+    /// Initializes `decoder` with the current format of `stream`.
+    /// # Errors
+    /// If the stream's format isn't one of the decoder's [`Codec::ALLOWED_FORMATS`], or the decoder fails to
+    /// initialize, this will error.
+    pub fn new(stream: Arc<StreamHandle>, mut decoder: D) -> Result<Self, NokhwaError> {
+        let format = stream.format();
+        Self::check_format(&format)?;
+        decoder.initialize(format)?;
+        Ok(Self { stream, decoder, format })
+    }
+
+    fn check_format(format: &CameraFormat) -> Result<(), NokhwaError> {
+        if !D::ALLOWED_FORMATS.contains(format.format()) {
+            return Err(NokhwaError::UnsupportedFormat { format: *format.format() });
+        }
+        Ok(())
+    }
+
+    /// Reads the next frame and decodes it, returning both the frame as it came from the camera and the decoded data.
+    ///
+    /// If the stream's format changed since the last frame, the decoder is renegotiated with [`Codec::on_format_change`]
+    /// first, see [`crate::decoder::Decoder::decode_next`].
+    /// # Errors
+    /// If reading the frame fails, the new format isn't supported by the decoder, or decoding fails, this will error.
+    pub fn next_decoded(&mut self) -> Result<(FrameBuffer, Cow<'_, [u8]>), NokhwaError> {
+        let frame = self.stream.next_frame()?;
+
+        let format = self.stream.format();
+        if format != self.format {
+            Self::check_format(&format)?;
+            self.decoder.on_format_change(format)?;
+            self.format = format;
+        }
+
+        let decoded = self.decoder.decode_frame(&frame)?;
+        Ok((frame, decoded))
+    }
+
+    pub fn stream(&self) -> &Arc<StreamHandle> {
+        &self.stream
+    }
+
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Gives back the stream and the decoder, which is left initialized.
+    pub fn into_inner(self) -> (Arc<StreamHandle>, D) {
+        (self.stream, self.decoder)
+    }
+}
+
+/// An object-safe facade over [`Camera`] that doesn't leak [`HashMap`] iterator types, so cameras from different
+/// backends can be stored side by side, e.g. in a `Vec<Box<dyn DynCamera>>`.
+///
+/// This is implemented for every [`Camera`], including `dyn Camera` and the `Box<dyn Camera>` returned by
+/// [`crate::platform::PlatformTrait::open_dynamic`].
+pub trait DynCamera {
+    /// See [`Setting::enumerate_formats`].
+    /// # Errors
+    /// If the formats can't be read from the camera, this will error.
+    fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError>;
+
+    /// See [`Setting::set_format`].
+    /// # Errors
+    /// If the camera doesn't support `camera_format`, or rejects it, this will error.
+    fn set_format(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError>;
+
+    fn control_ids(&self) -> Vec<ControlId>;
+
+    fn control_descriptions(&self) -> Box<dyn Iterator<Item = &ControlDescription> + '_>;
+
+    fn control_values(&self) -> Box<dyn Iterator<Item = &ControlValue> + '_>;
+
+    fn control_value(&self, id: &ControlId) -> Option<&ControlValue>;
+
+    fn control_description(&self, id: &ControlId) -> Option<&ControlDescription>;
+
+    /// See [`Setting::set_control`].
+    /// # Errors
+    /// If the control doesn't exist, doesn't allow `value`, or the camera rejects it, this will error.
+    fn set_control(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError>;
+
+    /// See [`Setting::refresh_controls`].
+    /// # Errors
+    /// If the controls can't be read from the camera, this will error.
+    fn refresh_controls(&mut self) -> Result<(), NokhwaError>;
+
+    /// See [`Capture::open_stream`].
+    /// # Errors
+    /// See [`Capture::open_stream_with`].
+    fn open_stream(&mut self) -> Result<Arc<StreamHandle>, NokhwaError>;
+
+    /// See [`Capture::open_stream_with`].
+    /// # Errors
+    /// See [`Capture::open_stream_with`].
+    fn open_stream_with(&mut self, configuration: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError>;
+
+    /// See [`Capture::close_stream`].
+    /// # Errors
+    /// If the stream fails to shut down, this will error.
+    fn close_stream(&mut self) -> Result<(), NokhwaError>;
+
+    fn stream_handle(&self) -> Option<Arc<StreamHandle>>;
+}
+
+impl<T: Camera + ?Sized> DynCamera for T {
+    fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+        Setting::enumerate_formats(self)
+    }
+
+    fn set_format(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        Setting::set_format(self, camera_format)
+    }
+
+    fn control_ids(&self) -> Vec<ControlId> {
+        Setting::control_ids(self).copied().collect()
+    }
+
+    fn control_descriptions(&self) -> Box<dyn Iterator<Item = &ControlDescription> + '_> {
+        Box::new(Setting::control_descriptions(self))
+    }
+
+    fn control_values(&self) -> Box<dyn Iterator<Item = &ControlValue> + '_> {
+        Box::new(Setting::control_values(self))
+    }
+
+    fn control_value(&self, id: &ControlId) -> Option<&ControlValue> {
+        Setting::control_value(self, id)
+    }
+
+    fn control_description(&self, id: &ControlId) -> Option<&ControlDescription> {
+        Setting::control_description(self, id)
+    }
+
+    fn set_control(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+        Setting::set_control(self, property, value)
+    }
+
+    fn refresh_controls(&mut self) -> Result<(), NokhwaError> {
+        Setting::refresh_controls(self)
+    }
+
+    fn open_stream(&mut self) -> Result<Arc<StreamHandle>, NokhwaError> {
+        Capture::open_stream(self)
+    }
+
+    fn open_stream_with(&mut self, configuration: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError> {
+        Capture::open_stream_with(self, configuration)
+    }
+
+    fn close_stream(&mut self) -> Result<(), NokhwaError> {
+        Capture::close_stream(self)
+    }
+
+    fn stream_handle(&self) -> Option<Arc<StreamHandle>> {
+        Capture::stream_handle(self)
+    }
+}
+
+// `Box<dyn Camera>` can't be unsized into `dyn DynCamera` directly, so forward to the boxed `dyn Camera`.
+impl DynCamera for Box<dyn Camera> {
+    fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+        DynCamera::enumerate_formats(&**self)
+    }
+
+    fn set_format(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        DynCamera::set_format(&mut **self, camera_format)
+    }
+
+    fn control_ids(&self) -> Vec<ControlId> {
+        DynCamera::control_ids(&**self)
+    }
+
+    fn control_descriptions(&self) -> Box<dyn Iterator<Item = &ControlDescription> + '_> {
+        DynCamera::control_descriptions(&**self)
+    }
+
+    fn control_values(&self) -> Box<dyn Iterator<Item = &ControlValue> + '_> {
+        DynCamera::control_values(&**self)
+    }
+
+    fn control_value(&self, id: &ControlId) -> Option<&ControlValue> {
+        DynCamera::control_value(&**self, id)
+    }
+
+    fn control_description(&self, id: &ControlId) -> Option<&ControlDescription> {
+        DynCamera::control_description(&**self, id)
+    }
+
+    fn set_control(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+        DynCamera::set_control(&mut **self, property, value)
+    }
+
+    fn refresh_controls(&mut self) -> Result<(), NokhwaError> {
+        DynCamera::refresh_controls(&mut **self)
+    }
+
+    fn open_stream(&mut self) -> Result<Arc<StreamHandle>, NokhwaError> {
+        DynCamera::open_stream(&mut **self)
+    }
+
+    fn open_stream_with(&mut self, configuration: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError> {
+        DynCamera::open_stream_with(&mut **self, configuration)
+    }
+
+    fn close_stream(&mut self) -> Result<(), NokhwaError> {
+        DynCamera::close_stream(&mut **self)
+    }
+
+    fn stream_handle(&self) -> Option<Arc<StreamHandle>> {
+        DynCamera::stream_handle(&**self)
+    }
+}
 
 #[cfg(feature = "async")]
 pub trait AsyncCamera: Camera + AsyncSetting + AsyncStream {}
+
+/// Adapts a blocking [`Camera`] to [`AsyncSetting`] and [`AsyncStream`], so backends that only implement the
+/// blocking traits can still be used from async code.
+///
+/// Every call runs on its own thread while the future waits for it, which doesn't depend on any particular async
+/// runtime. Calls are serialized: the camera is locked for as long as one runs.
+///
+/// [`AsyncSetting::properties_async`] returns a snapshot of the controls, taken on creation and refreshed after every
+/// call that can change them.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct SyncAsAsync<C: Camera + Send + 'static> {
+    camera: Arc<Mutex<C>>,
+    controls: Controls,
+}
+
+#[cfg(feature = "async")]
+impl<C: Camera + Send + 'static> SyncAsAsync<C> {
+    pub fn new(camera: C) -> Self {
+        let controls = Self::snapshot(&camera);
+        Self {
+            camera: Arc::new(Mutex::new(camera)),
+            controls,
+        }
+    }
+
+    /// Gives back the camera.
+    /// # Errors
+    /// If a call whose future was dropped is still running on its thread, this gives back `self` instead.
+    pub fn into_inner(self) -> Result<C, Self> {
+        match Arc::try_unwrap(self.camera) {
+            Ok(camera) => Ok(camera.into_inner().unwrap_or_else(PoisonError::into_inner)),
+            Err(camera) => Err(Self { camera, controls: self.controls }),
+        }
+    }
+
+    fn snapshot(camera: &C) -> Controls {
+        let descriptions = camera.control_ids().filter_map(|id| Some((*id, camera.control_description(id)?.clone()))).collect();
+        let values = camera.control_ids().filter_map(|id| Some((*id, camera.control_value(id)?.clone()))).collect();
+        Controls::unchecked_new(descriptions, values)
+    }
+
+    // runs `work` on the camera on its own thread.
+    async fn blocking<T: Send + 'static>(&self, work: impl FnOnce(&mut C) -> Result<T, NokhwaError> + Send + 'static) -> Result<T, NokhwaError> {
+        let camera = self.camera.clone();
+        let (sender, receiver) = flume::bounded(1);
+        std::thread::spawn(move || {
+            let result = work(&mut camera.lock().unwrap_or_else(PoisonError::into_inner));
+            // let go of the camera first, so `into_inner` works as soon as the call is done.
+            drop(camera);
+            let _ = sender.send(result);
+        });
+
+        receiver.recv_async().await.map_err(|_| NokhwaError::GeneralError("Camera thread exited without a result".to_string()))?
+    }
+
+    // runs `work`, then refreshes the controls snapshot, whether `work` succeeded or not.
+    async fn blocking_then_snapshot<T: Send + 'static>(&mut self, work: impl FnOnce(&mut C) -> Result<T, NokhwaError> + Send + 'static) -> Result<T, NokhwaError> {
+        let (result, controls) = self.blocking(move |camera| {
+            let result = work(camera);
+            Ok((result, Self::snapshot(camera)))
+        }).await?;
+        self.controls = controls;
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: Camera + Send + 'static> AsyncSetting for SyncAsAsync<C> {
+    async fn enumerate_formats_async(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+        self.blocking(|camera| camera.enumerate_formats()).await
+    }
+
+    async fn enumerate_resolution_and_frame_rates_async(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
+        self.blocking(move |camera| camera.enumerate_resolution_and_frame_rates(frame_format)).await
+    }
+
+    // some drivers reset controls on a format change, so this refreshes the snapshot too.
+    async fn set_format_async(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        self.blocking_then_snapshot(move |camera| camera.set_format(camera_format)).await
+    }
+
+    async fn properties_async(&self) -> &Controls {
+        &self.controls
+    }
+
+    async fn set_property_async(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+        let property = *property;
+        self.blocking_then_snapshot(move |camera| camera.set_control(&property, value)).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: Camera + Send + 'static> AsyncStream for SyncAsAsync<C> {
+    async fn open_stream_async(&mut self) -> Result<Arc<StreamHandle>, NokhwaError> {
+        self.blocking(Capture::open_stream).await
+    }
+
+    async fn close_stream_async(&mut self) -> Result<(), NokhwaError> {
+        self.blocking(Capture::close_stream).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{ControlFlags, ControlValueDescriptor, Controls};
+    use crate::decoder::yuyv::YuyvToRgbDecoder;
+    use crate::stream::Event;
+    use flume::{bounded, unbounded, Receiver, Sender};
+    use std::collections::HashSet;
+
+    // an in-memory camera whose streams deliver `frames`, then stay open.
+    struct MockCamera {
+        formats: Vec<CameraFormat>,
+        format: Option<CameraFormat>,
+        controls: Controls,
+        frames: Vec<FrameBuffer>,
+        // the open stream, together with what feeds it and how it is asked to stop.
+        stream: Option<(Arc<StreamHandle>, Sender<Event>, Receiver<()>)>,
+        closes: usize,
+    }
+
+    impl MockCamera {
+        fn new(formats: Vec<CameraFormat>) -> Self {
+            MockCamera {
+                format: formats.first().copied(),
+                formats,
+                controls: Controls::empty(),
+                frames: vec![],
+                stream: None,
+                closes: 0,
+            }
+        }
+
+        fn with_frames(mut self, frames: Vec<FrameBuffer>) -> Self {
+            self.frames = frames;
+            self
+        }
+    }
+
+    impl Setting for MockCamera {
+        fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+            Ok(self.formats.clone())
+        }
+
+        fn enumerate_resolution_and_frame_rates(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
+            let mut frame_rates = HashMap::<Resolution, Vec<FrameRate>>::new();
+            for format in self.formats.iter().filter(|format| *format.format() == frame_format) {
+                frame_rates.entry(*format.resolution()).or_default().push(*format.frame_rate());
+            }
+            Ok(frame_rates)
+        }
+
+        fn set_format(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+            if !self.formats.contains(&camera_format) {
+                return Err(NokhwaError::SetPropertyError {
+                    property: "format".to_string(),
+                    value: camera_format.to_string(),
+                    error: "Not supported by the mock camera".to_string(),
+                    source: None,
+                });
+            }
+            self.format = Some(camera_format);
+            Ok(())
+        }
+
+        fn current_format(&self) -> Option<CameraFormat> {
+            self.format
+        }
+
+        fn control_ids(&self) -> Keys<'_, ControlId, ControlDescription> {
+            self.controls.ids()
+        }
+
+        fn control_descriptions(&self) -> Values<'_, ControlId, ControlDescription> {
+            self.controls.descriptions()
+        }
+
+        fn control_values(&self) -> Values<'_, ControlId, ControlValue> {
+            self.controls.values()
+        }
+
+        fn control_value(&self, id: &ControlId) -> Option<&ControlValue> {
+            self.controls.value(id)
+        }
+
+        fn control_description(&self, id: &ControlId) -> Option<&ControlDescription> {
+            self.controls.description(id)
+        }
+
+        fn set_control(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+            self.controls.set_control_value(property, value)
+        }
+
+        fn refresh_controls(&mut self) -> Result<(), NokhwaError> {
+            Ok(())
+        }
+    }
+
+    impl Capture for MockCamera {
+        fn open_stream_with(&mut self, configuration: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError> {
+            if self.stream.is_some() {
+                return Err(NokhwaError::OpenStreamError("Stream Already Open".to_string()));
+            }
+            let format = self.format.ok_or_else(|| NokhwaError::OpenStreamError("No Format".to_string()))?;
+
+            let (sender, receiver) = unbounded();
+            for frame in &self.frames {
+                sender.send(Event::NewFrame(frame.clone())).unwrap();
+            }
+            let (control, stop) = bounded(1);
+            let handle = Arc::new(StreamHandle::new(receiver, control, configuration, format));
+            self.stream = Some((handle.clone(), sender, stop));
+            Ok(handle)
+        }
+
+        fn close_stream(&mut self) -> Result<(), NokhwaError> {
+            if self.stream.take().is_some() {
+                self.closes += 1;
+            }
+            Ok(())
+        }
+
+        fn stream_handle(&self) -> Option<Arc<StreamHandle>> {
+            self.stream.as_ref().map(|(handle, _, _)| handle.clone())
+        }
+    }
+
+    impl Camera for MockCamera {}
+
+    fn format(width: u32, height: u32, frame_format: FrameFormat) -> CameraFormat {
+        CameraFormat::new(Resolution::new(width, height), frame_format, FrameRate::frame_rate(30))
+    }
+
+    fn luma_frame(value: u8) -> FrameBuffer {
+        FrameBuffer::new(Resolution::new(2, 2), vec![value; 4], FrameFormat::Luma8, None)
+    }
+
+    #[test]
+    fn capture_single_frame_opens_reads_and_closes_a_stream() {
+        let mut camera = MockCamera::new(vec![format(2, 2, FrameFormat::Luma8)]).with_frames(vec![luma_frame(7), luma_frame(8)]);
+
+        assert_eq!(camera.capture_single_frame().unwrap(), luma_frame(7));
+        assert!(Capture::stream_handle(&camera).is_none());
+        assert_eq!(camera.closes, 1);
+
+        // an open stream is left alone.
+        let stream = Capture::open_stream(&mut camera).unwrap();
+        assert!(matches!(camera.capture_single_frame(), Err(NokhwaError::OpenStreamError(_))));
+        assert_eq!(stream.next_frame().unwrap(), luma_frame(7));
+        assert!(Capture::stream_handle(&camera).is_some());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn sync_as_async_sets_the_format_on_the_wrapped_camera() {
+        let formats = vec![format(640, 480, FrameFormat::Yuyv422), format(1280, 720, FrameFormat::MJpeg)];
+        let mut camera = SyncAsAsync::new(MockCamera::new(formats));
+
+        futures::executor::block_on(async {
+            camera.set_format_async(format(1280, 720, FrameFormat::MJpeg)).await.unwrap();
+            assert!(camera.set_format_async(format(320, 240, FrameFormat::Yuyv422)).await.is_err());
+            assert_eq!(camera.enumerate_formats_async().await.unwrap().len(), 2);
+        });
+        assert_eq!(camera.into_inner().ok().unwrap().current_format(), Some(format(1280, 720, FrameFormat::MJpeg)));
+    }
+
+    #[test]
+    fn different_cameras_fit_in_one_dyn_camera_vec() {
+        let boxed: Box<dyn Camera> = Box::new(MockCamera::new(vec![format(1920, 1080, FrameFormat::MJpeg)]));
+        let mut cameras: Vec<Box<dyn DynCamera>> = vec![
+            Box::new(MockCamera::new(vec![format(640, 480, FrameFormat::Yuyv422), format(320, 240, FrameFormat::Yuyv422)])),
+            Box::new(boxed),
+        ];
+
+        let formats = cameras.iter().map(|camera| camera.enumerate_formats().unwrap().len()).collect::<Vec<_>>();
+        assert_eq!(formats, vec![2, 1]);
+        for camera in &mut cameras {
+            let stream = camera.open_stream().unwrap();
+            assert!(camera.stream_handle().is_some_and(|handle| Arc::ptr_eq(&handle, &stream)));
+            camera.close_stream().unwrap();
+            assert!(camera.stream_handle().is_none());
+        }
+    }
+
+    #[test]
+    fn streams_open_with_the_format_set_last() {
+        let formats = vec![format(640, 480, FrameFormat::Yuyv422), format(1280, 720, FrameFormat::MJpeg)];
+        let mut camera = MockCamera::new(formats);
+
+        Setting::set_format(&mut camera, format(1280, 720, FrameFormat::MJpeg)).unwrap();
+        let stream = Capture::open_stream(&mut camera).unwrap();
+        assert_eq!(stream.format(), format(1280, 720, FrameFormat::MJpeg));
+        Capture::close_stream(&mut camera).unwrap();
+
+        // a rejected format leaves the last one in place.
+        assert!(Setting::set_format(&mut camera, format(320, 240, FrameFormat::Yuyv422)).is_err());
+        assert_eq!(Capture::open_stream(&mut camera).unwrap().format(), format(1280, 720, FrameFormat::MJpeg));
+    }
+
+    #[test]
+    fn is_format_supported_defaults_to_scanning_the_formats() {
+        let camera = MockCamera::new(vec![format(640, 480, FrameFormat::Yuyv422), format(1280, 720, FrameFormat::MJpeg)]);
+
+        assert!(camera.is_format_supported(format(1280, 720, FrameFormat::MJpeg)).unwrap());
+        assert!(!camera.is_format_supported(format(1280, 720, FrameFormat::Yuyv422)).unwrap());
+        let slower = CameraFormat::new(Resolution::new(640, 480), FrameFormat::Yuyv422, FrameRate::frame_rate(15));
+        assert!(!camera.is_format_supported(slower).unwrap());
+    }
+
+    #[test]
+    fn focus_and_flash_status_read_the_cached_controls() {
+        let mut camera = MockCamera::new(vec![format(640, 480, FrameFormat::Yuyv422)]);
+        assert_eq!(camera.focus_status(), None);
+        assert_eq!(camera.flash_status(), None);
+
+        let status = |descriptor| ControlDescription::new(HashSet::from([ControlFlags::ReadOnly]), descriptor, None).unwrap();
+        camera.controls = Controls::unchecked_new(
+            HashMap::from([
+                (ControlId::FocusStatus, status(ControlValueDescriptor::BitMask)),
+                (ControlId::LightingStatus, status(ControlValueDescriptor::Boolean)),
+            ]),
+            HashMap::from([
+                (ControlId::FocusStatus, ControlValue::BitMask(0b10)),
+                (ControlId::LightingStatus, ControlValue::Boolean(true)),
+            ]),
+        );
+        assert_eq!(camera.focus_status(), Some(FocusStatus::Reached));
+        assert_eq!(camera.flash_status(), Some(FlashStatus::Strobing));
+    }
+
+    #[test]
+    fn decoding_stream_hands_out_decoded_frames() {
+        let white = FrameBuffer::new(Resolution::new(2, 1), vec![235, 128, 235, 128], FrameFormat::Yuyv422, None);
+        let black = FrameBuffer::new(Resolution::new(2, 1), vec![16, 128, 16, 128], FrameFormat::Yuyv422, None);
+        let mut camera = MockCamera::new(vec![format(2, 1, FrameFormat::Yuyv422)]).with_frames(vec![white.clone(), black]);
+
+        let mut stream = camera.open_decoded_stream(YuyvToRgbDecoder::new()).unwrap();
+        let (frame, decoded) = stream.next_decoded().unwrap();
+        assert_eq!(frame, white);
+        assert_eq!(decoded.as_ref(), &[255; 6]);
+        assert_eq!(stream.next_decoded().unwrap().1.as_ref(), &[0; 6]);
+        assert_eq!(stream.decoder().output_format(), FrameFormat::Rgb888);
+    }
+
+    #[test]
+    fn decoding_stream_refuses_formats_the_decoder_cannot_take() {
+        let mut camera = MockCamera::new(vec![format(2, 1, FrameFormat::MJpeg)]);
+
+        assert!(matches!(camera.open_decoded_stream(YuyvToRgbDecoder::new()), Err(NokhwaError::UnsupportedFormat { .. })));
+        // the stream opened for it is closed again.
+        assert_eq!(camera.closes, 1);
+        assert!(Capture::stream_handle(&camera).is_none());
+    }
+}
@@ -5,10 +5,30 @@ use std::collections::hash_map::{Keys, Values};
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
 
 pub type PlatformSpecificControlId = u64;
 
+// Maps keyed by something other than a string (control IDs, menu choices) don't fit formats like JSON,
+// so they are (de)serialized as a sequence of `(key, value)` pairs instead.
+#[cfg(feature = "serialize")]
+mod map_as_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<K: Serialize, V: Serialize, S: Serializer>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<K, V>, D::Error> {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
 #[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ControlId {
     FocusMode,
     FocusAutoType,
@@ -43,9 +63,140 @@ pub enum ControlId {
 
     Orientation,
 
+    Brightness,
+    Contrast,
+    Saturation,
+    Hue,
+    Gamma,
+    Sharpness,
+    BacklightCompensation,
+
+    PanAbsolute,
+    PanRelative,
+    TiltAbsolute,
+    TiltRelative,
+    RollAbsolute,
+
+    PowerLineFrequency,
+
     PlatformSpecific(PlatformSpecificControlId),
 }
 
+impl ControlId {
+    /// Every abstract [`ControlId`] this crate knows about, in declaration order.
+    ///
+    /// This does not include [`ControlId::PlatformSpecific`].
+    pub const ALL: &'static [ControlId] = &[
+        ControlId::FocusMode,
+        ControlId::FocusAutoType,
+        ControlId::FocusAutoRange,
+        ControlId::FocusAbsolute,
+        ControlId::FocusRelative,
+        ControlId::FocusStatus,
+        ControlId::ExposureMode,
+        ControlId::ExposureBias,
+        ControlId::ExposureMetering,
+        ControlId::ExposureAbsolute,
+        ControlId::ExposureRelative,
+        ControlId::IsoMode,
+        ControlId::IsoSensitivity,
+        ControlId::ApertureAbsolute,
+        ControlId::ApertureRelative,
+        ControlId::WhiteBalanceMode,
+        ControlId::WhiteBalanceTemperature,
+        ControlId::ZoomContinuous,
+        ControlId::ZoomRelative,
+        ControlId::ZoomAbsolute,
+        ControlId::LightingMode,
+        ControlId::LightingStart,
+        ControlId::LightingStop,
+        ControlId::LightingStatus,
+        ControlId::Orientation,
+        ControlId::Brightness,
+        ControlId::Contrast,
+        ControlId::Saturation,
+        ControlId::Hue,
+        ControlId::Gamma,
+        ControlId::Sharpness,
+        ControlId::BacklightCompensation,
+        ControlId::PanAbsolute,
+        ControlId::PanRelative,
+        ControlId::TiltAbsolute,
+        ControlId::TiltRelative,
+        ControlId::RollAbsolute,
+        ControlId::PowerLineFrequency,
+    ];
+
+    /// Every abstract [`ControlId`] this crate knows about. See [`ControlId::ALL`].
+    #[must_use]
+    pub fn all() -> &'static [ControlId] {
+        Self::ALL
+    }
+
+    /// Whether this control picks a mode (e.g. manual or automatic) that decides whether other controls can be set.
+    ///
+    /// For example, [`ControlId::ExposureAbsolute`] can usually only be set while [`ControlId::ExposureMode`] is manual.
+    #[must_use]
+    pub fn is_mode(&self) -> bool {
+        matches!(
+            self,
+            ControlId::FocusMode
+                | ControlId::FocusAutoType
+                | ControlId::FocusAutoRange
+                | ControlId::ExposureMode
+                | ControlId::IsoMode
+                | ControlId::WhiteBalanceMode
+                | ControlId::LightingMode
+        )
+    }
+
+    /// A short, human readable explanation of what this control does.
+    #[must_use]
+    pub fn description_text(&self) -> &'static str {
+        match self {
+            ControlId::FocusMode => "Focus mode, such as manual or automatic focusing",
+            ControlId::FocusAutoType => "Kind of automatic focusing to use when focusing automatically",
+            ControlId::FocusAutoRange => "Distance range the automatic focus is restricted to",
+            ControlId::FocusAbsolute => "Absolute focus position in device units",
+            ControlId::FocusRelative => "Focus adjustment relative to the current position, in device units",
+            ControlId::FocusStatus => "Current state of the automatic focus (read only)",
+            ControlId::ExposureMode => "Exposure mode, such as manual or automatic exposure",
+            ControlId::ExposureBias => "Exposure compensation applied by automatic exposure",
+            ControlId::ExposureMetering => "How the scene is metered for automatic exposure",
+            ControlId::ExposureAbsolute => "Absolute exposure time in device units",
+            ControlId::ExposureRelative => "Exposure time adjustment relative to the current value",
+            ControlId::IsoMode => "ISO mode, such as manual or automatic sensitivity",
+            ControlId::IsoSensitivity => "ISO sensitivity (gain) of the sensor",
+            ControlId::ApertureAbsolute => "Absolute aperture (iris) opening in device units",
+            ControlId::ApertureRelative => "Aperture (iris) adjustment relative to the current opening",
+            ControlId::WhiteBalanceMode => "White balance mode, such as a preset or automatic white balance",
+            ControlId::WhiteBalanceTemperature => "White balance colour temperature in Kelvin",
+            ControlId::ZoomContinuous => "Zoom speed and direction for continuous zooming",
+            ControlId::ZoomRelative => "Zoom adjustment relative to the current zoom level",
+            ControlId::ZoomAbsolute => "Absolute zoom level in device units",
+            ControlId::LightingMode => "Flash or torch mode of the camera's lighting",
+            ControlId::LightingStart => "Triggers the camera's lighting (flash strobe)",
+            ControlId::LightingStop => "Stops the camera's lighting (flash strobe)",
+            ControlId::LightingStatus => "Current state of the camera's lighting (read only)",
+            ControlId::Orientation => "Physical orientation (mounting position) of the camera (read only)",
+            ControlId::Brightness => "Image brightness (black level) in device units",
+            ControlId::Contrast => "Image contrast in device units",
+            ControlId::Saturation => "Colour saturation in device units",
+            ControlId::Hue => "Hue (colour balance) adjustment in device units",
+            ControlId::Gamma => "Gamma correction in device units",
+            ControlId::Sharpness => "Sharpening strength in device units",
+            ControlId::BacklightCompensation => "Backlight compensation strength in device units",
+            ControlId::PanAbsolute => "Absolute pan (horizontal rotation) position in device units",
+            ControlId::PanRelative => "Pan movement relative to the current position, in device units",
+            ControlId::TiltAbsolute => "Absolute tilt (vertical rotation) position in device units",
+            ControlId::TiltRelative => "Tilt movement relative to the current position, in device units",
+            ControlId::RollAbsolute => "Absolute roll (rotation around the lens axis) position in device units",
+            ControlId::PowerLineFrequency => "Mains frequency to filter out lighting flicker for, such as 50Hz, 60Hz or automatic",
+            ControlId::PlatformSpecific(_) => "Platform specific control",
+        }
+    }
+}
+
 impl Display for ControlId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Control ID: {self:?}")
@@ -53,8 +204,11 @@ impl Display for ControlId {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Controls {
+    #[cfg_attr(feature = "serialize", serde(with = "map_as_pairs"))]
     descriptions: HashMap<ControlId, ControlDescription>,
+    #[cfg_attr(feature = "serialize", serde(with = "map_as_pairs"))]
     values: HashMap<ControlId, ControlValue>,
 }
 
@@ -112,41 +266,124 @@ impl Controls {
         self.descriptions.keys()
     }
 
-    pub fn set_control_value(
-        &mut self,
-        control_id: &ControlId,
-        value: ControlValue,
-    ) -> NokhwaResult<()> {
+    /// Whether the control currently has any effect, so UIs can gray out the ones that don't.
+    ///
+    /// A [`ControlFlags::Disabled`] control is never active. Otherwise, if the control
+    /// [depends on](ControlDescription::depends_on) another one, it is active while that one has the required value
+    /// (or no value at all). This follows the cached values, so it is up to date right after a
+    /// [`Controls::set_control_value`]. Without a dependency, the [`ControlFlags::Inactive`] flag decides.
+    ///
+    /// Unknown controls are inactive.
+    #[must_use]
+    pub fn is_active(&self, id: &ControlId) -> bool {
+        let Some(description) = self.descriptions.get(id) else {
+            return false;
+        };
+        if description.flags.contains(&ControlFlags::Disabled) {
+            return false;
+        }
+
+        match &description.depends_on {
+            Some((parent, required)) => self.values.get(parent).is_none_or(|value| value == required),
+            None => !description.flags.contains(&ControlFlags::Inactive),
+        }
+    }
+
+    /// The label of a [`ControlValueDescriptor::Menu`] control's current choice, e.g. for a dropdown.
+    ///
+    /// Returns `None` if the control isn't a menu, has no current value, its value isn't one of the menu's choices, or
+    /// the choice isn't named (integer menus map their choices to a [`ControlValue::Integer`] instead).
+    #[must_use]
+    pub fn current_menu_label(&self, id: &ControlId) -> Option<&str> {
+        let ControlValueDescriptor::Menu(choices) = self.descriptions.get(id)?.descriptor() else {
+            return None;
+        };
+        let ControlValue::EnumPick(choice) = self.values.get(id)? else {
+            return None;
+        };
+        choices.get(choice)?.as_str()
+    }
+
+    /// The controls whose value differs between `self` (old) and `other` (new), as `(id, old value, new value)`,
+    /// e.g. to see what a [`crate::camera::Setting::refresh_controls`] changed.
+    ///
+    /// A control that only has a value on one side is included with `None` on the other. The result is sorted by
+    /// [`ControlId`], so it doesn't depend on either map's iteration order.
+    #[must_use]
+    pub fn diff(&self, other: &Controls) -> Vec<(ControlId, Option<ControlValue>, Option<ControlValue>)> {
+        let mut changed = self.values.keys().chain(other.values.keys().filter(|id| !self.values.contains_key(id))).filter_map(|id| {
+            let old = self.values.get(id);
+            let new = other.values.get(id);
+            (old != new).then(|| (*id, old.cloned(), new.cloned()))
+        }).collect::<Vec<_>>();
+        changed.sort_by_key(|(id, _, _)| *id);
+        changed
+    }
+
+    /// Checks that `control_id` exists and `value` is allowed by its descriptor, without setting it.
+    /// # Errors
+    /// If the control doesn't exist or doesn't allow `value`, this will error.
+    pub fn validate_value(&self, control_id: &ControlId, value: &ControlValue) -> NokhwaResult<()> {
         // see if it exists
-        if let None = self.descriptions.get(control_id) {
+        let Some(description) = self.descriptions.get(control_id) else {
             return Err(NokhwaError::SetPropertyError {
                 property: control_id.to_string(),
                 value: value.to_string(),
                 error: "ID Not Found".to_string(),
+                source: None,
             });
-        }
+        };
 
-        match self.values.get_mut(control_id) {
-            Some(old) => {
-                *old = value;
-                Ok(())
-            }
-            // this should not happen,
-            None => Err(NokhwaError::SetPropertyError {
+        if !description.validate(value) {
+            return Err(NokhwaError::SetPropertyError {
                 property: control_id.to_string(),
                 value: value.to_string(),
-                error: "If you got this, its probably a bug or your camera is _horribly_ bugged :>"
-                    .to_string(),
-            }),
+                error: format!("Value is not allowed by {:?}", description.descriptor()),
+                source: None,
+            });
         }
+        Ok(())
+    }
+
+    /// Sets the cached value of `control_id` to `value`, after [validating](Controls::validate_value) it.
+    /// # Errors
+    /// If the control doesn't exist or doesn't allow `value`, this will error and the cached value is left as it was.
+    pub fn set_control_value(
+        &mut self,
+        control_id: &ControlId,
+        value: ControlValue,
+    ) -> NokhwaResult<()> {
+        self.validate_value(control_id, &value)?;
+
+        // a described control may not have reported a value yet (e.g. write-only controls).
+        self.values.insert(*control_id, value);
+        Ok(())
+    }
+
+    /// Sets every control back to its [`ControlDescription::default_value`].
+    ///
+    /// Controls without a default, and [`ControlFlags::ReadOnly`] controls, are left alone. A control that
+    /// fails to reset doesn't stop the others from being reset, its error is returned instead.
+    pub fn reset_to_defaults(&mut self) -> Vec<(ControlId, NokhwaError)> {
+        let defaults = self.descriptions.iter().filter(|(_, description)| {
+            !description.flags().contains(&ControlFlags::ReadOnly)
+        }).filter_map(|(id, description)| {
+            description.default_value().clone().map(|default| (*id, default))
+        }).collect::<Vec<(ControlId, ControlValue)>>();
+
+        defaults.into_iter().filter_map(|(id, default)| {
+            self.set_control_value(&id, default).err().map(|why| (id, why))
+        }).collect()
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct ControlDescription {
     flags: HashSet<ControlFlags>,
     descriptor: ControlValueDescriptor,
     default_value: Option<ControlValue>,
+    depends_on: Option<(ControlId, ControlValue)>,
 }
 
 impl ControlDescription {
@@ -165,6 +402,7 @@ impl ControlDescription {
             flags: control_flags,
             descriptor: control_value_descriptor,
             default_value,
+            depends_on: None,
         })
     }
 
@@ -177,6 +415,7 @@ impl ControlDescription {
             flags: control_flags,
             descriptor: control_value_descriptor,
             default_value,
+            depends_on: None,
         }
     }
 
@@ -192,6 +431,17 @@ impl ControlDescription {
         &self.default_value
     }
 
+    /// The control and the value it must have for this control to be active, e.g. [`ControlId::ExposureAbsolute`]
+    /// only does anything while [`ControlId::ExposureMode`] is manual. See [`Controls::is_active`].
+    #[must_use]
+    pub fn depends_on(&self) -> &Option<(ControlId, ControlValue)> {
+        &self.depends_on
+    }
+
+    pub fn set_depends_on(&mut self, depends_on: Option<(ControlId, ControlValue)>) {
+        self.depends_on = depends_on;
+    }
+
     pub fn add_flag(&mut self, flag: ControlFlags) {
         self.flags.insert(flag);
     }
@@ -206,6 +456,7 @@ impl ControlDescription {
 }
 
 #[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ControlFlags {
     Disabled,
     Busy,
@@ -220,19 +471,20 @@ pub enum ControlFlags {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ControlValueDescriptor {
     Null,
     Integer(Range<i64>),
     BitMask,
-    Float(Range<f64>),
+    Float(Range<OrderedFloat<f64>>),
     String,
     Boolean,
     // Array of any values of singular type
-    Array(ControlValueDescriptor),
+    Array(Box<ControlValueDescriptor>),
     // Menu(Enum) of valid choices
     // The keys are valid choices,
     // the values represent what the choice is (usually a string or int).
-    Menu(HashMap<ControlValue, ControlValue>),
+    Menu(#[cfg_attr(feature = "serialize", serde(with = "map_as_pairs"))] HashMap<ControlValue, ControlValue>),
     // lmao u deal with it
     // max/min length set by range. step is ALWAYS zero.
     Binary(Range<u64>),
@@ -252,7 +504,7 @@ impl ControlValueDescriptor {
         match self {
             ControlValueDescriptor::Null => {
                 if let &ControlValue::Null = value {
-                    return false;
+                    return true;
                 }
             }
             ControlValueDescriptor::Integer(int_range) => {
@@ -280,14 +532,14 @@ impl ControlValueDescriptor {
                     return true;
                 }
             }
-            ControlValueDescriptor::Array(arr) => {
-                if let &ControlValue::Array(_) = value {
-                    return arr.is_valid_value(value);
+            ControlValueDescriptor::Array(element) => {
+                if let ControlValue::Array(values) = value {
+                    return values.iter().all(|value| element.validate(value));
                 }
             }
             ControlValueDescriptor::Binary(size_limits) => {
                 if let ControlValue::Binary(bin) = value {
-                    return size_limits.validate(bin.len() as u64);
+                    return size_limits.validate(&(bin.len() as u64));
                 }
             }
             ControlValueDescriptor::Menu(choices) => {
@@ -311,9 +563,59 @@ impl ControlValueDescriptor {
         }
         false
     }
+
+    /// Turns `value` into the nearest value this descriptor allows.
+    ///
+    /// [`ControlValueDescriptor::Integer`] and [`ControlValueDescriptor::Float`] clamp `value` into their range and
+    /// round it to the nearest step. Every other descriptor returns `value` as-is if it is allowed.
+    /// Returns `None` if there is no such value (e.g. the wrong kind of value, or an empty range).
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn clamp(&self, value: ControlValue) -> Option<ControlValue> {
+        let clamped = match (self, value) {
+            (ControlValueDescriptor::Integer(range), ControlValue::Integer(i)) => {
+                // exclusive integer bounds have a neighbour that is inclusive
+                let minimum = range.minimum().map(|min| i128::from(min) + i128::from(!range.lower_inclusive()));
+                let maximum = range.maximum().map(|max| i128::from(max) - i128::from(!range.upper_inclusive()));
+                let mut clamped = i128::from(i).clamp(minimum.unwrap_or(i128::MIN), maximum.unwrap_or(i128::MAX));
+
+                if let Some(step) = range.step().map(i128::from).filter(|step| *step > 0) {
+                    // the step grid is anchored like `Range::validate` anchors it
+                    let anchor = range.minimum().map_or(0, i128::from);
+                    let offset = clamped - anchor;
+                    clamped = anchor + (offset + step / 2).div_euclid(step) * step;
+                    if maximum.is_some_and(|max| clamped > max) {
+                        clamped -= step;
+                    }
+                }
+                ControlValue::Integer(i64::try_from(clamped).ok()?)
+            }
+            (ControlValueDescriptor::Float(range), ControlValue::Float(f)) => {
+                let minimum = range.minimum().map_or(f64::NEG_INFINITY, |min| min.0);
+                let maximum = range.maximum().map_or(f64::INFINITY, |max| max.0);
+                if f.is_nan() || minimum > maximum {
+                    return None;
+                }
+                let mut clamped = f.0.clamp(minimum, maximum);
+
+                if let Some(step) = range.step().map(|step| step.0).filter(|step| *step > 0.0) {
+                    let anchor = range.minimum().map_or(0.0, |min| min.0);
+                    clamped = anchor + ((clamped - anchor) / step).round() * step;
+                    if clamped > maximum {
+                        clamped -= step;
+                    }
+                }
+                ControlValue::Float(OrderedFloat(clamped))
+            }
+            (_, value) => value,
+        };
+
+        self.validate(&clamped).then_some(clamped)
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ControlValue {
     Null,
     Integer(i64),
@@ -344,6 +646,95 @@ impl ControlValue {
         }
     }
 
+    /// The value of an [`ControlValue::Integer`] or [`ControlValue::BitMask`].
+    #[must_use]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ControlValue::Integer(i) | ControlValue::BitMask(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The value of a [`ControlValue::Float`], or an [`ControlValue::Integer`] widened to a float.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ControlValue::Float(f) => Some(f.0),
+            ControlValue::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ControlValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ControlValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            ControlValue::Binary(bin) => Some(bin),
+            _ => None,
+        }
+    }
+
+    fn mismatch(&self, expected: &str) -> NokhwaError {
+        NokhwaError::ConversionError(format!("Expected {expected}, got {self:?}"))
+    }
+
+    /// [`ControlValue::as_i64`], but errors on a mismatch.
+    /// # Errors
+    /// If this is not an [`ControlValue::Integer`] or [`ControlValue::BitMask`], this will error.
+    pub fn try_into_i64(self) -> Result<i64, NokhwaError> {
+        self.as_i64().ok_or_else(|| self.mismatch("an integer"))
+    }
+
+    /// [`ControlValue::as_f64`], but errors on a mismatch.
+    /// # Errors
+    /// If this is not a [`ControlValue::Float`] or [`ControlValue::Integer`], this will error.
+    pub fn try_into_f64(self) -> Result<f64, NokhwaError> {
+        self.as_f64().ok_or_else(|| self.mismatch("a float"))
+    }
+
+    /// [`ControlValue::as_bool`], but errors on a mismatch.
+    /// # Errors
+    /// If this is not a [`ControlValue::Boolean`], this will error.
+    pub fn try_into_bool(self) -> Result<bool, NokhwaError> {
+        self.as_bool().ok_or_else(|| self.mismatch("a boolean"))
+    }
+
+    /// Takes the [`String`] out of a [`ControlValue::String`].
+    /// # Errors
+    /// If this is not a [`ControlValue::String`], this will error.
+    pub fn try_into_string(self) -> Result<String, NokhwaError> {
+        match self {
+            ControlValue::String(s) => Ok(s),
+            other => Err(other.mismatch("a string")),
+        }
+    }
+
+    /// Takes the bytes out of a [`ControlValue::Binary`].
+    /// # Errors
+    /// If this is not a [`ControlValue::Binary`], this will error.
+    pub fn try_into_bytes(self) -> Result<Vec<u8>, NokhwaError> {
+        match self {
+            ControlValue::Binary(bin) => Ok(bin),
+            other => Err(other.mismatch("binary data")),
+        }
+    }
+
     // pub fn primitive_same_type(&self, other: &ControlValuePrimitive) -> bool {
     //     match other {
     //         ControlValuePrimitive::Null => {
@@ -432,6 +823,7 @@ impl Display for ControlValue {
 
 #[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
 #[non_exhaustive]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Orientation {
     User,
     Environment,
@@ -451,3 +843,308 @@ impl Display for Orientation {
         write!(f, "Orientation {self:?}")
     }
 }
+
+/// The state of a camera's automatic focus, as read from [`ControlId::FocusStatus`].
+#[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum FocusStatus {
+    /// Automatic focus is not active.
+    Idle,
+    /// Automatic focus is currently searching for focus.
+    Focusing,
+    /// Automatic focus has found focus.
+    Reached,
+    /// Automatic focus failed to find focus.
+    Failed,
+}
+
+impl FocusStatus {
+    const BUSY: i64 = 1 << 0;
+    const REACHED: i64 = 1 << 1;
+    const FAILED: i64 = 1 << 2;
+
+    /// Interprets a [`ControlId::FocusStatus`] value (a bitmask of busy/reached/failed bits).
+    ///
+    /// A failure wins over the other bits, and an ongoing search over having reached focus.
+    #[must_use]
+    pub fn from_control_value(value: &ControlValue) -> Option<Self> {
+        let bits = match value {
+            ControlValue::Integer(bits) | ControlValue::BitMask(bits) => *bits,
+            _ => return None,
+        };
+
+        Some(if bits & Self::FAILED != 0 {
+            FocusStatus::Failed
+        } else if bits & Self::BUSY != 0 {
+            FocusStatus::Focusing
+        } else if bits & Self::REACHED != 0 {
+            FocusStatus::Reached
+        } else {
+            FocusStatus::Idle
+        })
+    }
+}
+
+impl Display for FocusStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Focus Status {self:?}")
+    }
+}
+
+/// The state of a camera's flash, as read from [`ControlId::LightingStatus`].
+#[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum FlashStatus {
+    /// The flash is not firing.
+    Idle,
+    /// The flash is firing (strobing) right now.
+    Strobing,
+}
+
+impl FlashStatus {
+    /// Interprets a [`ControlId::LightingStatus`] value (whether the flash is strobing).
+    #[must_use]
+    pub fn from_control_value(value: &ControlValue) -> Option<Self> {
+        let strobing = match value {
+            ControlValue::Boolean(strobing) => *strobing,
+            ControlValue::Integer(value) | ControlValue::BitMask(value) => *value != 0,
+            _ => return None,
+        };
+
+        Some(if strobing {
+            FlashStatus::Strobing
+        } else {
+            FlashStatus::Idle
+        })
+    }
+}
+
+impl Display for FlashStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Flash Status {self:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn description(descriptor: ControlValueDescriptor, default_value: Option<ControlValue>) -> ControlDescription {
+        ControlDescription::new(HashSet::new(), descriptor, default_value).unwrap()
+    }
+
+    // brightness in 0..=100, in steps of 10.
+    fn brightness() -> ControlDescription {
+        description(ControlValueDescriptor::Integer(Range::new(0, 100, Some(10))), Some(ControlValue::Integer(50)))
+    }
+
+    #[test]
+    fn set_control_value_accepts_values_in_range() {
+        // a described control without a value yet can still be set.
+        let mut controls = Controls::unchecked_new(HashMap::from([(ControlId::Brightness, brightness())]), HashMap::new());
+
+        controls.set_control_value(&ControlId::Brightness, ControlValue::Integer(70)).unwrap();
+        assert_eq!(controls.value(&ControlId::Brightness), Some(&ControlValue::Integer(70)));
+
+        assert!(controls.set_control_value(&ControlId::Brightness, ControlValue::Integer(110)).is_err());
+        assert!(controls.set_control_value(&ControlId::Brightness, ControlValue::Integer(-10)).is_err());
+        assert_eq!(controls.value(&ControlId::Brightness), Some(&ControlValue::Integer(70)));
+    }
+
+    #[test]
+    fn null_and_array_descriptors_validate_their_values() {
+        assert!(ControlValueDescriptor::Null.validate(&ControlValue::Null));
+        assert!(!ControlValueDescriptor::Null.validate(&ControlValue::Integer(0)));
+
+        let array = ControlValueDescriptor::Array(Box::new(ControlValueDescriptor::Integer(Range::new(0, 10, None))));
+        assert!(array.validate(&ControlValue::Array(vec![ControlValue::Integer(0), ControlValue::Integer(10)])));
+        assert!(!array.validate(&ControlValue::Array(vec![ControlValue::Integer(11)])));
+    }
+
+    #[test]
+    fn set_control_value_rejects_mismatched_and_out_of_range_values() {
+        let mut controls = Controls::new(
+            HashMap::from([(ControlId::Brightness, brightness())]),
+            HashMap::from([(ControlId::Brightness, ControlValue::Integer(50))]),
+        ).unwrap();
+
+        assert!(controls.set_control_value(&ControlId::Brightness, ControlValue::Boolean(true)).is_err());
+        assert!(controls.set_control_value(&ControlId::Brightness, ControlValue::Float(OrderedFloat(50.0))).is_err());
+        // off the step grid.
+        assert!(controls.set_control_value(&ControlId::Brightness, ControlValue::Integer(55)).is_err());
+        assert!(controls.set_control_value(&ControlId::Contrast, ControlValue::Integer(50)).is_err());
+        assert_eq!(controls.value(&ControlId::Brightness), Some(&ControlValue::Integer(50)));
+
+        // values that don't fit their description are caught on creation too.
+        assert!(Controls::new(
+            HashMap::from([(ControlId::Brightness, brightness())]),
+            HashMap::from([(ControlId::Brightness, ControlValue::String("bright".to_string()))]),
+        ).is_none());
+    }
+
+    #[test]
+    fn reset_to_defaults_skips_read_only_controls_and_those_without_a_default() {
+        let mut orientation = description(ControlValueDescriptor::Orientation(vec![]), Some(ControlValue::Orientation(Orientation::User)));
+        orientation.add_flag(ControlFlags::ReadOnly);
+        let mut controls = Controls::unchecked_new(
+            HashMap::from([
+                (ControlId::Brightness, brightness()),
+                (ControlId::Orientation, orientation),
+                (ControlId::Hue, description(ControlValueDescriptor::Integer(Range::new(-180, 180, None)), None)),
+            ]),
+            HashMap::from([
+                (ControlId::Brightness, ControlValue::Integer(90)),
+                (ControlId::Orientation, ControlValue::Orientation(Orientation::Environment)),
+                (ControlId::Hue, ControlValue::Integer(20)),
+            ]),
+        );
+
+        assert!(controls.reset_to_defaults().is_empty());
+        assert_eq!(controls.value(&ControlId::Brightness), Some(&ControlValue::Integer(50)));
+        assert_eq!(controls.value(&ControlId::Orientation), Some(&ControlValue::Orientation(Orientation::Environment)));
+        assert_eq!(controls.value(&ControlId::Hue), Some(&ControlValue::Integer(20)));
+    }
+
+    #[test]
+    fn clamp_pulls_values_into_range_and_onto_the_step_grid() {
+        let descriptor = brightness().descriptor().clone();
+        let clamp = |value| descriptor.clamp(ControlValue::Integer(value));
+
+        assert_eq!(clamp(150), Some(ControlValue::Integer(100)));
+        assert_eq!(clamp(-5), Some(ControlValue::Integer(0)));
+        assert_eq!(clamp(44), Some(ControlValue::Integer(40)));
+        assert_eq!(clamp(46), Some(ControlValue::Integer(50)));
+        assert_eq!(descriptor.clamp(ControlValue::Boolean(true)), None);
+
+        // the nearest step past the maximum is out, so the one below it is taken.
+        let uneven = ControlValueDescriptor::Integer(Range::new(0, 95, Some(10)));
+        assert_eq!(uneven.clamp(ControlValue::Integer(94)), Some(ControlValue::Integer(90)));
+
+        let float = ControlValueDescriptor::Float(Range::new(OrderedFloat(0.0), OrderedFloat(1.0), Some(OrderedFloat(0.25))));
+        assert_eq!(float.clamp(ControlValue::Float(OrderedFloat(0.3))), Some(ControlValue::Float(OrderedFloat(0.25))));
+        assert_eq!(float.clamp(ControlValue::Float(OrderedFloat(2.0))), Some(ControlValue::Float(OrderedFloat(1.0))));
+        assert_eq!(float.clamp(ControlValue::Float(OrderedFloat(f64::NAN))), None);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn controls_round_trip_through_json() {
+        let choices = HashMap::from([
+            (ControlValue::Integer(0), ControlValue::String("Disabled".to_string())),
+            (ControlValue::Integer(1), ControlValue::String("50 Hz".to_string())),
+        ]);
+        let power_line = description(ControlValueDescriptor::Menu(choices), Some(ControlValue::EnumPick(Box::new(ControlValue::Integer(1)))));
+        let controls = Controls::new(
+            HashMap::from([(ControlId::Brightness, brightness()), (ControlId::PowerLineFrequency, power_line)]),
+            HashMap::from([
+                (ControlId::Brightness, ControlValue::Integer(30)),
+                (ControlId::PowerLineFrequency, ControlValue::EnumPick(Box::new(ControlValue::Integer(0)))),
+            ]),
+        ).unwrap();
+
+        let json = serde_json::to_string(&controls).unwrap();
+        assert_eq!(serde_json::from_str::<Controls>(&json).unwrap(), controls);
+    }
+
+    #[test]
+    fn dependent_controls_follow_their_parent() {
+        let manual = ControlValue::EnumPick(Box::new(ControlValue::Integer(1)));
+        let auto = ControlValue::EnumPick(Box::new(ControlValue::Integer(3)));
+        let modes = HashMap::from([
+            (ControlValue::Integer(1), ControlValue::String("Manual".to_string())),
+            (ControlValue::Integer(3), ControlValue::String("Aperture Priority".to_string())),
+        ]);
+        let mut exposure = description(ControlValueDescriptor::Integer(Range::new(1, 5000, None)), None);
+        exposure.set_depends_on(Some((ControlId::ExposureMode, manual.clone())));
+        let mut disabled = brightness();
+        disabled.add_flag(ControlFlags::Disabled);
+        let mut controls = Controls::unchecked_new(
+            HashMap::from([
+                (ControlId::ExposureMode, description(ControlValueDescriptor::Menu(modes), None)),
+                (ControlId::ExposureAbsolute, exposure),
+                (ControlId::Brightness, disabled),
+            ]),
+            HashMap::from([(ControlId::ExposureMode, auto.clone())]),
+        );
+
+        assert!(!controls.is_active(&ControlId::ExposureAbsolute));
+        controls.set_control_value(&ControlId::ExposureMode, manual).unwrap();
+        assert!(controls.is_active(&ControlId::ExposureAbsolute));
+        controls.set_control_value(&ControlId::ExposureMode, auto).unwrap();
+        assert!(!controls.is_active(&ControlId::ExposureAbsolute));
+
+        assert!(controls.is_active(&ControlId::ExposureMode));
+        assert!(!controls.is_active(&ControlId::Brightness));
+        assert!(!controls.is_active(&ControlId::Hue));
+    }
+
+    #[test]
+    fn accessors_only_read_their_own_kind_of_value() {
+        assert_eq!(ControlValue::Integer(-4).as_i64(), Some(-4));
+        assert_eq!(ControlValue::BitMask(0b11).as_i64(), Some(0b11));
+        assert_eq!(ControlValue::Boolean(true).as_i64(), None);
+
+        assert_eq!(ControlValue::Float(OrderedFloat(0.5)).as_f64(), Some(0.5));
+        assert_eq!(ControlValue::Integer(2).as_f64(), Some(2.0));
+        assert_eq!(ControlValue::String("2".to_string()).as_f64(), None);
+
+        assert_eq!(ControlValue::Boolean(false).as_bool(), Some(false));
+        assert_eq!(ControlValue::Integer(0).as_bool(), None);
+        assert_eq!(ControlValue::String("auto".to_string()).as_str(), Some("auto"));
+        assert_eq!(ControlValue::Binary(vec![1, 2]).as_bytes(), Some(&[1, 2][..]));
+        assert_eq!(ControlValue::Null.as_bytes(), None);
+
+        assert_eq!(ControlValue::Integer(7).try_into_i64().unwrap(), 7);
+        assert!(ControlValue::Null.try_into_bool().is_err());
+        assert_eq!(ControlValue::String("auto".to_string()).try_into_string().unwrap(), "auto");
+        assert!(ControlValue::Integer(1).try_into_bytes().is_err());
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_changed_values_by_id() {
+        let old = Controls::unchecked_new(HashMap::new(), HashMap::from([
+            (ControlId::Brightness, ControlValue::Integer(50)),
+            (ControlId::Contrast, ControlValue::Integer(10)),
+            (ControlId::Hue, ControlValue::Integer(0)),
+        ]));
+        let new = Controls::unchecked_new(HashMap::new(), HashMap::from([
+            (ControlId::Brightness, ControlValue::Integer(60)),
+            (ControlId::Hue, ControlValue::Integer(0)),
+            (ControlId::Gamma, ControlValue::Integer(100)),
+        ]));
+
+        assert_eq!(old.diff(&new), vec![
+            (ControlId::Brightness, Some(ControlValue::Integer(50)), Some(ControlValue::Integer(60))),
+            (ControlId::Contrast, Some(ControlValue::Integer(10)), None),
+            (ControlId::Gamma, None, Some(ControlValue::Integer(100))),
+        ]);
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn current_menu_label_names_the_current_choice() {
+        let choices = HashMap::from([
+            (ControlValue::Integer(0), ControlValue::String("Disabled".to_string())),
+            (ControlValue::Integer(1), ControlValue::String("50 Hz".to_string())),
+            (ControlValue::Integer(2), ControlValue::Integer(60)),
+        ]);
+        let pick = |index| ControlValue::EnumPick(Box::new(ControlValue::Integer(index)));
+        let mut controls = Controls::unchecked_new(
+            HashMap::from([
+                (ControlId::PowerLineFrequency, description(ControlValueDescriptor::Menu(choices), None)),
+                (ControlId::Brightness, brightness()),
+            ]),
+            HashMap::from([(ControlId::PowerLineFrequency, pick(1)), (ControlId::Brightness, ControlValue::Integer(50))]),
+        );
+
+        assert_eq!(controls.current_menu_label(&ControlId::PowerLineFrequency), Some("50 Hz"));
+        controls.set_control_value(&ControlId::PowerLineFrequency, pick(0)).unwrap();
+        assert_eq!(controls.current_menu_label(&ControlId::PowerLineFrequency), Some("Disabled"));
+        // integer menus don't name their choices.
+        controls.set_control_value(&ControlId::PowerLineFrequency, pick(2)).unwrap();
+        assert_eq!(controls.current_menu_label(&ControlId::PowerLineFrequency), None);
+
+        assert_eq!(controls.current_menu_label(&ControlId::Brightness), None);
+        assert_eq!(controls.current_menu_label(&ControlId::Hue), None);
+    }
+}
@@ -0,0 +1,627 @@
+/*
+ * Copyright 2022 l1npengtul <l1npengtul@protonmail.com> / The Nokhwa Contributors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Software pixel conversions to packed RGB888.
+//!
+//! These are deliberately simple - if you need speed, use a [`crate::decoder::Codec`] backed by
+//! a real library.
+
+use crate::error::NokhwaError;
+use crate::frame_format::FrameFormat;
+use crate::types::Resolution;
+
+/// Converts a single BT.601 limited range YCbCr sample into an RGB888 pixel.
+#[must_use]
+#[inline]
+pub fn yuv_to_rgb888(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let luma = i32::from(y) - 16;
+    let cb = i32::from(u) - 128;
+    let cr = i32::from(v) - 128;
+
+    let red = (298 * luma + 409 * cr + 128) >> 8;
+    let green = (298 * luma - 100 * cb - 208 * cr + 128) >> 8;
+    let blue = (298 * luma + 516 * cb + 128) >> 8;
+
+    [clamp_u8(red), clamp_u8(green), clamp_u8(blue)]
+}
+
+/// The matrix used to turn YCbCr samples into RGB.
+///
+/// Most webcams use [`ColorMatrix::Bt601`], which is what [`to_rgb888`] assumes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ColorMatrix {
+    /// ITU-R BT.601 (SD video).
+    #[default]
+    Bt601,
+    /// ITU-R BT.709 (HD video).
+    Bt709,
+    /// ITU-R BT.2020 (UHD video), non-constant luminance.
+    Bt2020,
+    /// A custom row-major 3x3 matrix, applied to `[Y, Cb, Cr]` where Y is normalized to `0.0..=1.0`
+    /// and Cb/Cr to `-0.5..=0.5`, giving `[R, G, B]` in `0.0..=1.0`.
+    Custom([[f32; 3]; 3]),
+}
+
+impl ColorMatrix {
+    /// The row-major 3x3 `[Y, Cb, Cr]` to `[R, G, B]` matrix. See [`ColorMatrix::Custom`].
+    #[must_use]
+    pub fn coefficients(&self) -> [[f32; 3]; 3] {
+        // (Kr, Kb) luma coefficients of each standard.
+        let (kr, kb) = match self {
+            ColorMatrix::Bt601 => (0.299, 0.114),
+            ColorMatrix::Bt709 => (0.2126, 0.0722),
+            ColorMatrix::Bt2020 => (0.2627, 0.0593),
+            ColorMatrix::Custom(matrix) => return *matrix,
+        };
+        let kg = 1.0 - kr - kb;
+
+        [
+            [1.0, 0.0, 2.0 * (1.0 - kr)],
+            [1.0, -2.0 * kb * (1.0 - kb) / kg, -2.0 * kr * (1.0 - kr) / kg],
+            [1.0, 2.0 * (1.0 - kb), 0.0],
+        ]
+    }
+}
+
+/// The quantization range of YCbCr samples.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub enum QuantRange {
+    /// Y in `16..=235`, Cb/Cr in `16..=240`. This is what most webcams send.
+    #[default]
+    Limited,
+    /// Y, Cb and Cr use the full `0..=255`.
+    Full,
+}
+
+/// Converts a buffer of `frame_format` at `resolution` into packed RGB888 (3 bytes per pixel).
+///
+/// Supported formats are the packed 4:2:2 formats, the planar/semi-planar 4:2:0 formats, the
+/// grayscale formats, the uncompressed RGB formats and, with the `mjpeg` or `image` feature,
+/// [`FrameFormat::MJpeg`] (see [`mjpeg_to_rgb888`]).
+/// # Errors
+/// If the format is otherwise compressed or unsupported, the MJPEG frame can't be decoded, or the `data` is too small for the
+/// `resolution`, this will error.
+pub fn to_rgb888(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+) -> Result<Vec<u8>, NokhwaError> {
+    to_rgb888_by(frame_format, resolution, data, yuv_to_rgb888)
+}
+
+/// [`to_rgb888`], but YCbCr formats are converted using `matrix` and `range` instead of assuming
+/// limited range BT.601.
+/// # Errors
+/// If the format is compressed or otherwise unsupported, or the `data` is too small for the
+/// `resolution`, this will error.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn to_rgb888_with(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+    matrix: ColorMatrix,
+    range: QuantRange,
+) -> Result<Vec<u8>, NokhwaError> {
+    let coefficients = matrix.coefficients();
+    // (black level, luma scale, chroma scale) to normalize samples.
+    let (black, luma_scale, chroma_scale) = match range {
+        QuantRange::Limited => (16.0, 219.0, 224.0),
+        QuantRange::Full => (0.0, 255.0, 255.0),
+    };
+
+    to_rgb888_by(frame_format, resolution, data, |y, u, v| {
+        let ycbcr = [
+            (f32::from(y) - black) / luma_scale,
+            (f32::from(u) - 128.0) / chroma_scale,
+            (f32::from(v) - 128.0) / chroma_scale,
+        ];
+        coefficients.map(|row| {
+            let value = row[0] * ycbcr[0] + row[1] * ycbcr[1] + row[2] * ycbcr[2];
+            (value * 255.0).round().clamp(0.0, 255.0) as u8
+        })
+    })
+}
+
+fn to_rgb888_by(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+    yuv: impl Fn(u8, u8, u8) -> [u8; 3],
+) -> Result<Vec<u8>, NokhwaError> {
+    let pixels = resolution.width() as usize * resolution.height() as usize;
+
+    match frame_format {
+        // (Y0, U, Y1, V) byte offsets inside a macropixel
+        FrameFormat::Yuyv422 => packed_422_to_rgb888(frame_format, resolution, data, [0, 1, 2, 3], yuv),
+        FrameFormat::Uyvy422 => packed_422_to_rgb888(frame_format, resolution, data, [1, 0, 3, 2], yuv),
+        FrameFormat::Yvyu422 => packed_422_to_rgb888(frame_format, resolution, data, [0, 3, 2, 1], yuv),
+        FrameFormat::Nv12 | FrameFormat::Nv21 | FrameFormat::I420 | FrameFormat::Yv12 => {
+            planar_420_to_rgb888(frame_format, resolution, data, yuv)
+        }
+        FrameFormat::Luma8 => {
+            check_size(frame_format, data, pixels)?;
+            Ok(data[..pixels].iter().flat_map(|y| [*y; 3]).collect())
+        }
+        FrameFormat::Luma16 => {
+            check_size(frame_format, data, pixels * 2)?;
+            Ok(data[..pixels * 2]
+                .chunks_exact(2)
+                .flat_map(|y| [y[1]; 3])
+                .collect())
+        }
+        FrameFormat::Rgb332 => {
+            check_size(frame_format, data, pixels)?;
+            Ok(data[..pixels]
+                .iter()
+                .flat_map(|px| {
+                    let r = (px >> 5) & 0b111;
+                    let g = (px >> 2) & 0b111;
+                    let b = px & 0b11;
+                    [scale_to_u8(r, 7), scale_to_u8(g, 7), scale_to_u8(b, 3)]
+                })
+                .collect())
+        }
+        FrameFormat::Rgb888 => {
+            check_size(frame_format, data, pixels * 3)?;
+            Ok(data[..pixels * 3].to_vec())
+        }
+        FrameFormat::Bgr888 => {
+            check_size(frame_format, data, pixels * 3)?;
+            Ok(data[..pixels * 3]
+                .chunks_exact(3)
+                .flat_map(|px| [px[2], px[1], px[0]])
+                .collect())
+        }
+        FrameFormat::RgbA8888 => {
+            check_size(frame_format, data, pixels * 4)?;
+            Ok(data[..pixels * 4]
+                .chunks_exact(4)
+                .flat_map(|px| [px[0], px[1], px[2]])
+                .collect())
+        }
+        FrameFormat::BgrA8888 => {
+            check_size(frame_format, data, pixels * 4)?;
+            Ok(data[..pixels * 4]
+                .chunks_exact(4)
+                .flat_map(|px| [px[2], px[1], px[0]])
+                .collect())
+        }
+        FrameFormat::ARgb8888 => {
+            check_size(frame_format, data, pixels * 4)?;
+            Ok(data[..pixels * 4]
+                .chunks_exact(4)
+                .flat_map(|px| [px[1], px[2], px[3]])
+                .collect())
+        }
+        FrameFormat::MJpeg => mjpeg_to_rgb888(resolution, data),
+        _ => Err(NokhwaError::ProcessFrameError {
+            src: frame_format,
+            destination: "Rgb888".to_string(),
+            error: "No software conversion available for this format".to_string(),
+        }),
+    }
+}
+
+/// Decodes an MJPEG frame into packed RGB888, using `jpeg-decoder` with the `mjpeg` feature or
+/// `image`'s JPEG decoder with the `image` feature.
+/// # Errors
+/// If neither feature is enabled, the frame is corrupt or CMYK, or it doesn't decode to
+/// `resolution`, this will error.
+pub fn mjpeg_to_rgb888(resolution: Resolution, data: &[u8]) -> Result<Vec<u8>, NokhwaError> {
+    let mut output = Vec::new();
+    decode_mjpeg_into(resolution, data, &mut output)?;
+    Ok(output)
+}
+
+/// Extracts an 8-bit grayscale image, one byte per pixel, from a buffer of `frame_format`.
+///
+/// YCbCr formats hand back their (unscaled) Y samples, [`FrameFormat::Luma16`] is shifted down to
+/// its top 8 bits and everything else is decoded with [`to_rgb888`] and weighted with BT.601.
+/// # Errors
+/// If the format has no software conversion, or the `data` is too small for the `resolution`, this will error.
+pub fn to_luma8(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+) -> Result<Vec<u8>, NokhwaError> {
+    let width = resolution.width() as usize;
+    let pixels = width * resolution.height() as usize;
+
+    match frame_format {
+        // planar YCbCr formats start with the Y plane, that is all there is to Luma8.
+        FrameFormat::Luma8 | FrameFormat::Nv12 | FrameFormat::Nv21 | FrameFormat::I420 | FrameFormat::Yv12 => {
+            check_size(frame_format, data, pixels)?;
+            Ok(data[..pixels].to_vec())
+        }
+        FrameFormat::Luma16 => {
+            check_size(frame_format, data, pixels * 2)?;
+            Ok(data[..pixels * 2].chunks_exact(2).map(|y| y[1]).collect())
+        }
+        FrameFormat::Yuyv422 | FrameFormat::Yvyu422 | FrameFormat::Uyvy422 => {
+            let row_stride = width.div_ceil(2) * 4;
+            check_size(frame_format, data, row_stride * resolution.height() as usize)?;
+            let offset = usize::from(frame_format == FrameFormat::Uyvy422);
+            Ok(data
+                .chunks_exact(row_stride)
+                .take(resolution.height() as usize)
+                .flat_map(|row| row.iter().skip(offset).step_by(2).take(width).copied())
+                .collect())
+        }
+        FrameFormat::MJpeg => Ok(mjpeg_to_rgb888(resolution, data)?
+            .chunks_exact(3)
+            .map(|px| rgb_to_luma(px[0], px[1], px[2]))
+            .collect()),
+        _ => Ok(to_rgb888(frame_format, resolution, data)?
+            .chunks_exact(3)
+            .map(|px| rgb_to_luma(px[0], px[1], px[2]))
+            .collect()),
+    }
+}
+
+/// Whether [`convert`] can turn `source` buffers into `output` buffers.
+#[must_use]
+pub fn can_convert(source: FrameFormat, output: FrameFormat) -> bool {
+    if source == output {
+        return true;
+    }
+    if source == FrameFormat::MJpeg {
+        return output == FrameFormat::Rgb888 && cfg!(any(feature = "mjpeg", feature = "image"));
+    }
+
+    match output {
+        FrameFormat::Rgb888 => matches!(
+            source,
+            FrameFormat::Yuyv422
+                | FrameFormat::Uyvy422
+                | FrameFormat::Yvyu422
+                | FrameFormat::Nv12
+                | FrameFormat::Nv21
+                | FrameFormat::I420
+                | FrameFormat::Yv12
+                | FrameFormat::Luma8
+                | FrameFormat::Luma16
+                | FrameFormat::Rgb332
+                | FrameFormat::Bgr888
+                | FrameFormat::RgbA8888
+                | FrameFormat::BgrA8888
+                | FrameFormat::ARgb8888
+        ),
+        _ => false,
+    }
+}
+
+/// Converts a buffer of `frame_format` at `resolution` into a buffer of `output`.
+///
+/// Buffers already in `output` are copied as-is.
+/// # Errors
+/// If there is no conversion from `frame_format` to `output` (see [`can_convert`]), or the `data`
+/// is too small for the `resolution`, this will error.
+pub fn convert(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+    output: FrameFormat,
+) -> Result<Vec<u8>, NokhwaError> {
+    if frame_format == output {
+        return Ok(data.to_vec());
+    }
+
+    match output {
+        FrameFormat::Rgb888 => to_rgb888(frame_format, resolution, data),
+        _ => Err(NokhwaError::ProcessFrameError {
+            src: frame_format,
+            destination: output.to_string(),
+            error: "No software conversion available for this format".to_string(),
+        }),
+    }
+}
+
+/// Resizes packed RGB888 `data` from `source` to `target` using bilinear sampling.
+///
+/// `data` must be exactly `source.width() * source.height() * 3` bytes.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+pub fn resize_rgb888(data: &[u8], source: Resolution, target: Resolution) -> Vec<u8> {
+    let (src_w, src_h) = (source.width() as usize, source.height() as usize);
+    let (dst_w, dst_h) = (target.width() as usize, target.height() as usize);
+
+    if source == target {
+        return data.to_vec();
+    }
+    if src_w == 0 || src_h == 0 {
+        return vec![0; dst_w * dst_h * 3];
+    }
+
+    let x_ratio = src_w as f32 / dst_w as f32;
+    let y_ratio = src_h as f32 / dst_h as f32;
+    let mut resized = Vec::with_capacity(dst_w * dst_h * 3);
+
+    for dst_y in 0..dst_h {
+        let src_y = ((dst_y as f32 + 0.5) * y_ratio - 0.5).clamp(0.0, (src_h - 1) as f32);
+        let y0 = src_y as usize;
+        let y1 = (y0 + 1).min(src_h - 1);
+        let y_weight = src_y - y0 as f32;
+
+        for dst_x in 0..dst_w {
+            let src_x = ((dst_x as f32 + 0.5) * x_ratio - 0.5).clamp(0.0, (src_w - 1) as f32);
+            let x0 = src_x as usize;
+            let x1 = (x0 + 1).min(src_w - 1);
+            let x_weight = src_x - x0 as f32;
+
+            for channel in 0..3 {
+                let sample = |x: usize, y: usize| f32::from(data[(y * src_w + x) * 3 + channel]);
+                let top = sample(x0, y0) * (1.0 - x_weight) + sample(x1, y0) * x_weight;
+                let bottom = sample(x0, y1) * (1.0 - x_weight) + sample(x1, y1) * x_weight;
+                resized.push((top * (1.0 - y_weight) + bottom * y_weight).round() as u8);
+            }
+        }
+    }
+    resized
+}
+
+/// Shrinks packed RGB888 `data` from `source` to `target` with a box filter, so every target pixel is the
+/// average of the source pixels it covers. With a non-integer scale factor, a source pixel that is only partly
+/// covered counts fully.
+///
+/// `data` must be exactly `source.width() * source.height() * 3` bytes, and `target` must not be larger than
+/// `source` in either dimension.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn downscale_rgb888(data: &[u8], source: Resolution, target: Resolution) -> Vec<u8> {
+    let (src_w, src_h) = (source.width() as usize, source.height() as usize);
+    let (dst_w, dst_h) = (target.width() as usize, target.height() as usize);
+
+    if source == target {
+        return data.to_vec();
+    }
+    // the source pixels `[start, end)` covering target pixel `index`, always at least one.
+    let span = |index: usize, src: usize, dst: usize| {
+        let start = index * src / dst;
+        let end = ((index + 1) * src).div_ceil(dst).max(start + 1).min(src);
+        start..end
+    };
+
+    let mut scaled = Vec::with_capacity(dst_w * dst_h * 3);
+    for dst_y in 0..dst_h {
+        let rows = span(dst_y, src_h, dst_h);
+        for dst_x in 0..dst_w {
+            let columns = span(dst_x, src_w, dst_w);
+            let count = (rows.len() * columns.len()) as u32;
+            let mut sum = [0_u32; 3];
+            for y in rows.clone() {
+                for x in columns.clone() {
+                    let pixel = (y * src_w + x) * 3;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += u32::from(data[pixel + channel]);
+                    }
+                }
+            }
+            scaled.extend(sum.map(|total| ((total + count / 2) / count.max(1)) as u8));
+        }
+    }
+    scaled
+}
+
+fn packed_422_to_rgb888(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+    [y0, u, y1, v]: [usize; 4],
+    yuv: impl Fn(u8, u8, u8) -> [u8; 3],
+) -> Result<Vec<u8>, NokhwaError> {
+    let width = resolution.width() as usize;
+    let height = resolution.height() as usize;
+    // each macropixel covers two horizontal pixels, so odd widths are padded out by one.
+    let row_stride = width.div_ceil(2) * 4;
+    check_size(frame_format, data, row_stride * height)?;
+
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for row in data.chunks_exact(row_stride).take(height) {
+        for (idx, macropixel) in row.chunks_exact(4).enumerate() {
+            rgb.extend_from_slice(&yuv(macropixel[y0], macropixel[u], macropixel[v]));
+            if idx * 2 + 1 < width {
+                rgb.extend_from_slice(&yuv(macropixel[y1], macropixel[u], macropixel[v]));
+            }
+        }
+    }
+    Ok(rgb)
+}
+
+fn planar_420_to_rgb888(
+    frame_format: FrameFormat,
+    resolution: Resolution,
+    data: &[u8],
+    yuv: impl Fn(u8, u8, u8) -> [u8; 3],
+) -> Result<Vec<u8>, NokhwaError> {
+    let width = resolution.width() as usize;
+    let height = resolution.height() as usize;
+    let chroma_width = width.div_ceil(2);
+    let chroma_height = height.div_ceil(2);
+    let luma_size = width * height;
+    let chroma_size = chroma_width * chroma_height;
+    check_size(frame_format, data, luma_size + chroma_size * 2)?;
+
+    let (luma, chroma) = data.split_at(luma_size);
+    let chroma_at = |x: usize, y: usize| -> (u8, u8) {
+        let index = (y / 2) * chroma_width + (x / 2);
+        match frame_format {
+            FrameFormat::Nv12 => (chroma[index * 2], chroma[index * 2 + 1]),
+            FrameFormat::Nv21 => (chroma[index * 2 + 1], chroma[index * 2]),
+            FrameFormat::I420 => (chroma[index], chroma[chroma_size + index]),
+            // Yv12
+            _ => (chroma[chroma_size + index], chroma[index]),
+        }
+    };
+
+    let mut rgb = Vec::with_capacity(luma_size * 3);
+    for y in 0..height {
+        for x in 0..width {
+            let (u, v) = chroma_at(x, y);
+            rgb.extend_from_slice(&yuv(luma[y * width + x], u, v));
+        }
+    }
+    Ok(rgb)
+}
+
+// Shared with `MjpegDecoder`, which reuses `output` between frames.
+#[cfg(feature = "mjpeg")]
+pub(crate) fn decode_mjpeg_into(resolution: Resolution, data: &[u8], output: &mut Vec<u8>) -> Result<(), NokhwaError> {
+    use jpeg_decoder::PixelFormat;
+
+    let mut decoder = jpeg_decoder::Decoder::new(data);
+    let pixels = decoder.decode().map_err(|why| mjpeg_error(&why))?;
+    let info = decoder.info().ok_or_else(|| mjpeg_error("Missing JPEG header"))?;
+    check_mjpeg_resolution(resolution, Resolution::new(u32::from(info.width), u32::from(info.height)))?;
+
+    output.clear();
+    match info.pixel_format {
+        PixelFormat::RGB24 => output.extend_from_slice(&pixels),
+        PixelFormat::L8 => output.extend(pixels.iter().flat_map(|y| [*y; 3])),
+        // big endian, keep the high byte
+        PixelFormat::L16 => output.extend(pixels.chunks_exact(2).flat_map(|y| [y[0]; 3])),
+        PixelFormat::CMYK32 => return Err(mjpeg_error("CMYK JPEGs are not supported")),
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "image", not(feature = "mjpeg")))]
+pub(crate) fn decode_mjpeg_into(resolution: Resolution, data: &[u8], output: &mut Vec<u8>) -> Result<(), NokhwaError> {
+    let image = image::load_from_memory_with_format(data, image::ImageFormat::Jpeg).map_err(|why| mjpeg_error(&why))?;
+    check_mjpeg_resolution(resolution, Resolution::new(image.width(), image.height()))?;
+
+    output.clear();
+    output.extend_from_slice(image.to_rgb8().as_raw());
+    Ok(())
+}
+
+#[cfg(not(any(feature = "mjpeg", feature = "image")))]
+fn decode_mjpeg_into(_: Resolution, _: &[u8], _: &mut Vec<u8>) -> Result<(), NokhwaError> {
+    Err(mjpeg_error("Decoding MJPEG needs the `mjpeg` or `image` feature"))
+}
+
+#[cfg(any(feature = "mjpeg", feature = "image"))]
+fn check_mjpeg_resolution(expected: Resolution, decoded: Resolution) -> Result<(), NokhwaError> {
+    if decoded == expected {
+        Ok(())
+    } else {
+        Err(mjpeg_error(&format!("Expected a {expected} frame, got {decoded}")))
+    }
+}
+
+fn mjpeg_error(error: &(impl std::fmt::Display + ?Sized)) -> NokhwaError {
+    NokhwaError::ProcessFrameError {
+        src: FrameFormat::MJpeg,
+        destination: FrameFormat::Rgb888.to_string(),
+        error: error.to_string(),
+    }
+}
+
+fn check_size(frame_format: FrameFormat, data: &[u8], required: usize) -> Result<(), NokhwaError> {
+    if data.len() < required {
+        return Err(NokhwaError::ProcessFrameError {
+            src: frame_format,
+            destination: "Rgb888".to_string(),
+            error: format!("Buffer too small: expected {required} bytes, got {}", data.len()),
+        });
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[inline]
+fn rgb_to_luma(r: u8, g: u8, b: u8) -> u8 {
+    // BT.601 weights in 8.8 fixed point, they sum up to 256.
+    ((77 * u32::from(r) + 150 * u32::from(g) + 29 * u32::from(b) + 128) >> 8) as u8
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[inline]
+fn scale_to_u8(value: u8, max: u16) -> u8 {
+    (u16::from(value) * 255 / max) as u8
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+#[inline]
+fn clamp_u8(value: i32) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 32x16, the left half (200, 40, 40) and the right half (40, 40, 200).
+    #[cfg(any(feature = "mjpeg", feature = "image"))]
+    const TWO_COLORS: &[u8] = include_bytes!("decoder/testdata/two_colors.jpg");
+
+    #[cfg(any(feature = "mjpeg", feature = "image"))]
+    #[test]
+    fn to_rgb888_decodes_mjpeg() {
+        let rgb = to_rgb888(FrameFormat::MJpeg, Resolution::new(32, 16), TWO_COLORS).unwrap();
+
+        assert_eq!(rgb.len(), 32 * 16 * 3);
+        for (channel, expected) in rgb[..3].iter().zip([200, 40, 40]) {
+            assert!(channel.abs_diff(expected) <= 8, "{:?} is not close to (200, 40, 40)", &rgb[..3]);
+        }
+        assert!(can_convert(FrameFormat::MJpeg, FrameFormat::Rgb888));
+        assert!(to_rgb888(FrameFormat::MJpeg, Resolution::new(16, 16), TWO_COLORS).is_err());
+    }
+
+    #[cfg(not(any(feature = "mjpeg", feature = "image")))]
+    #[test]
+    fn to_rgb888_explains_how_to_decode_mjpeg() {
+        let error = to_rgb888(FrameFormat::MJpeg, Resolution::new(32, 16), &[0xFF, 0xD8]).unwrap_err();
+
+        assert!(error.to_string().contains("`mjpeg` or `image` feature"), "{error}");
+        assert!(!can_convert(FrameFormat::MJpeg, FrameFormat::Rgb888));
+    }
+
+    #[test]
+    fn to_luma8_takes_the_y_samples() {
+        let resolution = Resolution::new(2, 2);
+
+        assert_eq!(to_luma8(FrameFormat::Luma8, resolution, &[1, 2, 3, 4]).unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(to_luma8(FrameFormat::Luma16, resolution, &[0, 1, 0, 2, 0, 3, 0, 4]).unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            to_luma8(FrameFormat::Yuyv422, resolution, &[1, 128, 2, 128, 3, 128, 4, 128]).unwrap(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            to_luma8(FrameFormat::Uyvy422, resolution, &[128, 1, 128, 2, 128, 3, 128, 4]).unwrap(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(to_luma8(FrameFormat::Nv12, resolution, &[1, 2, 3, 4, 128, 128]).unwrap(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn to_luma8_weights_rgb() {
+        let rgb = [255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0];
+
+        assert_eq!(to_luma8(FrameFormat::Rgb888, Resolution::new(2, 2), &rgb).unwrap(), vec![255, 0, 77, 149]);
+        assert!(to_luma8(FrameFormat::Rgb888, Resolution::new(2, 2), &rgb[..6]).is_err());
+    }
+
+    #[cfg(any(feature = "mjpeg", feature = "image"))]
+    #[test]
+    fn to_luma8_decodes_mjpeg() {
+        let luma = to_luma8(FrameFormat::MJpeg, Resolution::new(32, 16), TWO_COLORS).unwrap();
+
+        assert_eq!(luma.len(), 32 * 16);
+        // (200, 40, 40) and (40, 40, 200) weighted with BT.601.
+        assert!(luma[0].abs_diff(rgb_to_luma(200, 40, 40)) <= 8);
+        assert!(luma[luma.len() - 1].abs_diff(rgb_to_luma(40, 40, 200)) <= 8);
+    }
+}
@@ -6,11 +6,18 @@ use crate::frame_format::FrameFormat;
 use crate::stream::{StreamHandle};
 use crate::types::{CameraFormat, FrameRate, Resolution};
 
+pub mod chroma;
+#[cfg(feature = "mjpeg")]
+pub mod mjpeg;
+pub mod scale;
+pub mod yuyv;
+
 #[derive(Debug)]
 pub struct Decoder<'stream, Video> where
     Video: Codec {
     video: Video,
-    stream: &'stream mut StreamHandle
+    stream: &'stream mut StreamHandle,
+    format: CameraFormat,
 }
 
 impl<'stream, Video> Decoder<'stream, Video> where Video: Codec {
@@ -19,10 +26,41 @@ impl<'stream, Video> Decoder<'stream, Video> where Video: Codec {
         
         let mut decoder = decoder;
         decoder.initialize(format)?;
-        Ok(Self { video: decoder, stream })
+        Ok(Self { video: decoder, stream, format })
+    }
+
+    /// Reads the next frame off the stream and decodes it.
+    ///
+    /// If the stream sent an [`crate::stream::Event::FormatChange`] since the last frame, the codec is renegotiated
+    /// with [`Codec::on_format_change`] first. Should that fail, the error is returned and the frame that came with
+    /// the new format is skipped, the next call tries again with the frame after it.
+    /// # Errors
+    /// If reading the frame, renegotiating the format or decoding fails, this will error.
+    pub fn decode_next(&mut self) -> Result<Cow<'_, [u8]>, NokhwaError> {
+        let frame = self.stream.next_frame()?;
+
+        let format = self.stream.format();
+        if format != self.format {
+            self.video.on_format_change(format)?;
+            self.format = format;
+        }
+
+        self.video.decode_frame(&frame)
+    }
+
+    /// Decodes a frame that didn't come from the held stream.
+    /// # Errors
+    /// If the codec can't decode `buffer`, this will error.
+    pub fn decode(&mut self, buffer: &FrameBuffer) -> Result<Cow<'_, [u8]>, NokhwaError> {
+        self.video.decode_frame(buffer)
+    }
+
+    /// Stops the codec. It has to be reinitialized with [`Codec::initialize`] (e.g. through [`Decoder::new`]) before decoding again.
+    /// # Errors
+    /// If the codec fails to stop, this will error.
+    pub fn stop(&mut self) -> Result<(), NokhwaError> {
+        self.video.stop()
     }
-    
-    pub fn 
 }
 
 #[cfg(feature = "async")]
@@ -41,6 +79,14 @@ pub trait Codec: Debug {
     fn stop(&mut self) -> Result<(), NokhwaError>;
     
     fn frame_format(&self) -> Result<FrameFormat, NokhwaError>;
+
+    /// The [`FrameFormat`] of the data [`Codec::decode_frame`] produces (e.g. [`FrameFormat::Rgb888`] for an MJPEG decoder).
+    ///
+    /// [`Codec::frame_format`] is the format a codec *takes*, matching [`Codec::set_frame_format`], so the output is
+    /// asked for separately. Defaults to [`FrameFormat::Rgb888`], what most codecs decode to.
+    fn output_format(&self) -> FrameFormat {
+        FrameFormat::Rgb888
+    }
     
     fn resolution(&self) -> Result<Resolution, NokhwaError>;
     
@@ -51,9 +97,59 @@ pub trait Codec: Debug {
     fn set_resolution(&mut self, resolution: Resolution) -> Result<(), NokhwaError>;
     
     fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), NokhwaError>;
+
+    /// Called when the stream switches to `new_format` mid-stream, so the codec can reconfigure itself.
+    ///
+    /// The default implementation applies the new format through [`Codec::set_frame_format`], [`Codec::set_resolution`]
+    /// and [`Codec::set_frame_rate`]. Codecs that keep state tied to the old format should override this.
+    /// # Errors
+    /// If the codec can't take `new_format`, this will error.
+    fn on_format_change(&mut self, new_format: CameraFormat) -> Result<(), NokhwaError> {
+        self.set_frame_format(*new_format.format())?;
+        self.set_resolution(*new_format.resolution())?;
+        self.set_frame_rate(*new_format.frame_rate())
+    }
     
     fn decode_frame(&mut self, buffer: &FrameBuffer) -> Result<Cow<'_, [u8]>, NokhwaError>;
+
+    /// [`Codec::decode_frame`], but wraps the output in a [`FrameBuffer`] tagged with [`Codec::output_format`].
+    /// The resolution and metadata are carried over from `buffer`.
+    /// # Errors
+    /// If decoding the frame fails, this will error.
+    fn decode_frame_buffer(&mut self, buffer: &FrameBuffer) -> Result<FrameBuffer, NokhwaError> {
+        let output_format = self.output_format();
+        let decoded = self.decode_frame(buffer)?.into_owned();
+        Ok(FrameBuffer::new(buffer.resolution(), decoded, output_format, buffer.metadata().cloned()))
+    }
 }
 
 #[cfg(feature = "async")]
 pub trait CodecAsync: Codec + Debug {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::yuyv::YuyvToRgbDecoder;
+    use crate::stream::{Event, StreamConfiguration};
+
+    #[test]
+    fn decode_next_renegotiates_on_a_format_change() {
+        let old = CameraFormat::new(Resolution::new(2, 1), FrameFormat::Yuyv422, FrameRate::frame_rate(30));
+        let new = CameraFormat::new(Resolution::new(4, 1), FrameFormat::Yuyv422, FrameRate::frame_rate(15));
+        let frame = FrameBuffer::new(Resolution::new(4, 1), vec![235, 128, 235, 128, 16, 128, 16, 128], FrameFormat::Yuyv422, None);
+
+        let (sender, receiver) = flume::unbounded();
+        sender.send(Event::FormatChange(new)).unwrap();
+        sender.send(Event::NewFrame(frame)).unwrap();
+        let (control, _stop) = flume::bounded(1);
+        let mut stream = StreamHandle::new(receiver, control, StreamConfiguration::default(), old);
+        let mut decoder = Decoder::new(&mut stream, YuyvToRgbDecoder::new()).unwrap();
+        assert_eq!(decoder.video.resolution().unwrap(), Resolution::new(2, 1));
+
+        let rgb = decoder.decode_next().unwrap().into_owned();
+        assert_eq!(rgb, [[255; 6], [0; 6]].concat());
+        assert_eq!(decoder.format, new);
+        assert_eq!(decoder.video.resolution().unwrap(), Resolution::new(4, 1));
+        assert_eq!(decoder.video.frame_rate().unwrap(), FrameRate::frame_rate(15));
+    }
+}
@@ -0,0 +1,164 @@
+use std::borrow::Cow;
+use crate::decoder::Codec;
+use crate::error::NokhwaError;
+use crate::frame_buffer::FrameBuffer;
+use crate::frame_format::FrameFormat;
+use crate::types::{CameraFormat, FrameRate, Resolution};
+
+/// A software [`Codec`] turning semi-planar [`FrameFormat::Nv12`] frames into planar [`FrameFormat::I420`], e.g. for
+/// encoders that only take I420.
+///
+/// The pixels are untouched, only the interleaved chroma plane is split into a U and a V plane. For odd sizes the
+/// chroma planes are rounded up, i.e. they are `ceil(width / 2)` by `ceil(height / 2)`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Nv12ToI420Decoder {
+    format: Option<CameraFormat>,
+}
+
+impl Nv12ToI420Decoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn format(&self) -> Result<CameraFormat, NokhwaError> {
+        self.format.ok_or_else(|| NokhwaError::GetPropertyError {
+            property: "format".to_string(),
+            error: "Decoder not initialized".to_string(),
+            source: None,
+        })
+    }
+
+    fn format_mut(&mut self) -> Result<&mut CameraFormat, NokhwaError> {
+        self.format.as_mut().ok_or_else(|| NokhwaError::GetPropertyError {
+            property: "format".to_string(),
+            error: "Decoder not initialized".to_string(),
+            source: None,
+        })
+    }
+}
+
+impl Codec for Nv12ToI420Decoder {
+    const ALLOWED_FORMATS: &'static [FrameFormat] = &[FrameFormat::Nv12];
+
+    fn initialize(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        if !Self::ALLOWED_FORMATS.contains(camera_format.format()) {
+            return Err(NokhwaError::UnsupportedFormat { format: *camera_format.format() });
+        }
+        self.format = Some(camera_format);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), NokhwaError> {
+        self.format = None;
+        Ok(())
+    }
+
+    fn frame_format(&self) -> Result<FrameFormat, NokhwaError> {
+        Ok(*self.format()?.format())
+    }
+
+    fn output_format(&self) -> FrameFormat {
+        FrameFormat::I420
+    }
+
+    fn resolution(&self) -> Result<Resolution, NokhwaError> {
+        Ok(*self.format()?.resolution())
+    }
+
+    fn frame_rate(&self) -> Result<FrameRate, NokhwaError> {
+        Ok(*self.format()?.frame_rate())
+    }
+
+    fn set_frame_format(&mut self, frame_format: FrameFormat) -> Result<(), NokhwaError> {
+        if !Self::ALLOWED_FORMATS.contains(&frame_format) {
+            return Err(NokhwaError::UnsupportedFormat { format: frame_format });
+        }
+        self.format_mut()?.set_format(frame_format);
+        Ok(())
+    }
+
+    fn set_resolution(&mut self, resolution: Resolution) -> Result<(), NokhwaError> {
+        self.format_mut()?.set_resolution(resolution);
+        Ok(())
+    }
+
+    fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), NokhwaError> {
+        self.format_mut()?.set_frame_rate(frame_rate);
+        Ok(())
+    }
+
+    fn decode_frame(&mut self, buffer: &FrameBuffer) -> Result<Cow<'_, [u8]>, NokhwaError> {
+        let source = buffer.source_frame_format();
+        if !Self::ALLOWED_FORMATS.contains(&source) {
+            return Err(NokhwaError::UnsupportedFormat { format: source });
+        }
+
+        let resolution = buffer.resolution();
+        let expected = source.expected_buffer_size(resolution).unwrap_or_default();
+        if buffer.buffer().len() != expected {
+            return Err(NokhwaError::ProcessFrameError {
+                src: source,
+                destination: FrameFormat::I420.to_string(),
+                error: format!("Expected {expected} bytes for {resolution}, got {}", buffer.buffer().len()),
+            });
+        }
+
+        let luma_size = resolution.width() as usize * resolution.height() as usize;
+        let (luma, chroma) = buffer.buffer().split_at(luma_size);
+
+        let mut output = Vec::with_capacity(expected);
+        output.extend_from_slice(luma);
+        // NV12 interleaves the chroma as UVUV..., I420 wants all of U followed by all of V.
+        output.extend(chroma.iter().step_by(2));
+        output.extend(chroma.iter().skip(1).step_by(2));
+
+        Ok(Cow::Owned(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(resolution: Resolution, data: Vec<u8>) -> Result<Vec<u8>, NokhwaError> {
+        let mut decoder = Nv12ToI420Decoder::new();
+        decoder.initialize(CameraFormat::new(resolution, FrameFormat::Nv12, FrameRate::default()))?;
+        let frame = FrameBuffer::new(resolution, data, FrameFormat::Nv12, None);
+        decoder.decode_frame(&frame).map(Cow::into_owned)
+    }
+
+    #[test]
+    fn splits_the_chroma_planes() {
+        let luma = (0..16).collect::<Vec<u8>>();
+        let chroma = vec![100, 200, 101, 201, 102, 202, 103, 203];
+        let decoded = decode(Resolution::new(4, 4), [luma.clone(), chroma].concat()).unwrap();
+
+        assert_eq!(&decoded[..16], &luma[..]);
+        assert_eq!(&decoded[16..20], &[100, 101, 102, 103]);
+        assert_eq!(&decoded[20..], &[200, 201, 202, 203]);
+    }
+
+    #[test]
+    fn rounds_odd_sizes_up() {
+        // 3x3 has 2x2 chroma samples.
+        let luma = vec![16; 9];
+        let chroma = vec![1, 5, 2, 6, 3, 7, 4, 8];
+        let decoded = decode(Resolution::new(3, 3), [luma, chroma].concat()).unwrap();
+
+        assert_eq!(decoded.len(), 9 + 4 + 4);
+        assert_eq!(&decoded[9..13], &[1, 2, 3, 4]);
+        assert_eq!(&decoded[13..], &[5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn rejects_a_buffer_of_the_wrong_size() {
+        assert!(matches!(decode(Resolution::new(4, 4), vec![0; 16]), Err(NokhwaError::ProcessFrameError { .. })));
+        assert!(matches!(decode(Resolution::new(4, 4), vec![0; 25]), Err(NokhwaError::ProcessFrameError { .. })));
+    }
+
+    #[test]
+    fn outputs_i420() {
+        assert_eq!(Nv12ToI420Decoder::new().output_format(), FrameFormat::I420);
+    }
+}
@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use crate::conversion::decode_mjpeg_into;
+use crate::decoder::Codec;
+use crate::error::NokhwaError;
+use crate::frame_buffer::FrameBuffer;
+use crate::frame_format::FrameFormat;
+use crate::types::{CameraFormat, FrameRate, Resolution};
+
+/// A software [`Codec`] turning [`FrameFormat::MJpeg`] frames into [`FrameFormat::Rgb888`] using `jpeg-decoder`.
+///
+/// The decoded frame is kept in a buffer owned by the decoder, which is reused for every frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MjpegDecoder {
+    format: Option<CameraFormat>,
+    output: Vec<u8>,
+}
+
+impl MjpegDecoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn format(&self) -> Result<CameraFormat, NokhwaError> {
+        self.format.ok_or_else(|| NokhwaError::GetPropertyError {
+            property: "format".to_string(),
+            error: "Decoder not initialized".to_string(),
+            source: None,
+        })
+    }
+
+    fn format_mut(&mut self) -> Result<&mut CameraFormat, NokhwaError> {
+        self.format.as_mut().ok_or_else(|| NokhwaError::GetPropertyError {
+            property: "format".to_string(),
+            error: "Decoder not initialized".to_string(),
+            source: None,
+        })
+    }
+}
+
+impl Codec for MjpegDecoder {
+    const ALLOWED_FORMATS: &'static [FrameFormat] = &[FrameFormat::MJpeg];
+
+    fn initialize(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        if !Self::ALLOWED_FORMATS.contains(camera_format.format()) {
+            return Err(NokhwaError::UnsupportedFormat { format: *camera_format.format() });
+        }
+        self.format = Some(camera_format);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), NokhwaError> {
+        self.format = None;
+        self.output = vec![];
+        Ok(())
+    }
+
+    fn frame_format(&self) -> Result<FrameFormat, NokhwaError> {
+        Ok(*self.format()?.format())
+    }
+
+    fn output_format(&self) -> FrameFormat {
+        FrameFormat::Rgb888
+    }
+
+    fn resolution(&self) -> Result<Resolution, NokhwaError> {
+        Ok(*self.format()?.resolution())
+    }
+
+    fn frame_rate(&self) -> Result<FrameRate, NokhwaError> {
+        Ok(*self.format()?.frame_rate())
+    }
+
+    fn set_frame_format(&mut self, frame_format: FrameFormat) -> Result<(), NokhwaError> {
+        if !Self::ALLOWED_FORMATS.contains(&frame_format) {
+            return Err(NokhwaError::UnsupportedFormat { format: frame_format });
+        }
+        self.format_mut()?.set_format(frame_format);
+        Ok(())
+    }
+
+    fn set_resolution(&mut self, resolution: Resolution) -> Result<(), NokhwaError> {
+        self.format_mut()?.set_resolution(resolution);
+        Ok(())
+    }
+
+    fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), NokhwaError> {
+        self.format_mut()?.set_frame_rate(frame_rate);
+        Ok(())
+    }
+
+    fn decode_frame(&mut self, buffer: &FrameBuffer) -> Result<Cow<'_, [u8]>, NokhwaError> {
+        let expected = self.resolution()?;
+        if buffer.source_frame_format() != FrameFormat::MJpeg {
+            return Err(NokhwaError::UnsupportedFormat { format: buffer.source_frame_format() });
+        }
+
+        // a corrupt frame shouldn't take the whole stream down, so make sure it surfaces as an error.
+        decode_mjpeg_into(expected, buffer.buffer(), &mut self.output)?;
+        Ok(Cow::Borrowed(&self.output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 32x16, the left half (200, 40, 40) and the right half (40, 40, 200).
+    const TWO_COLORS: &[u8] = include_bytes!("testdata/two_colors.jpg");
+    // 8x8, all 128.
+    const GRAY: &[u8] = include_bytes!("testdata/gray.jpg");
+
+    fn initialized(resolution: Resolution) -> MjpegDecoder {
+        let mut decoder = MjpegDecoder::new();
+        decoder.initialize(CameraFormat::new(resolution, FrameFormat::MJpeg, FrameRate::default())).unwrap();
+        decoder
+    }
+
+    fn assert_close(pixel: &[u8], expected: [u8; 3]) {
+        for (channel, expected) in pixel.iter().zip(expected) {
+            assert!(channel.abs_diff(expected) <= 8, "{pixel:?} is not close to {expected:?}");
+        }
+    }
+
+    #[test]
+    fn decodes_an_embedded_jpeg() {
+        let resolution = Resolution::new(32, 16);
+        let mut decoder = initialized(resolution);
+        let frame = FrameBuffer::new(resolution, TWO_COLORS.to_vec(), FrameFormat::MJpeg, None);
+
+        let rgb = decoder.decode_frame(&frame).unwrap();
+        assert_eq!(rgb.len(), 32 * 16 * 3);
+        assert_close(&rgb[..3], [200, 40, 40]);
+        let last = rgb.len() - 3;
+        assert_close(&rgb[last..], [40, 40, 200]);
+    }
+
+    #[test]
+    fn expands_grayscale_jpegs() {
+        let resolution = Resolution::new(8, 8);
+        let mut decoder = initialized(resolution);
+        let frame = FrameBuffer::new(resolution, GRAY.to_vec(), FrameFormat::MJpeg, None);
+
+        let rgb = decoder.decode_frame(&frame).unwrap();
+        assert_eq!(rgb.len(), 8 * 8 * 3);
+        assert_close(&rgb[..3], [128, 128, 128]);
+    }
+
+    #[test]
+    fn rejects_a_mismatched_resolution() {
+        let resolution = Resolution::new(32, 16);
+        let mut decoder = initialized(Resolution::new(16, 16));
+        let frame = FrameBuffer::new(resolution, TWO_COLORS.to_vec(), FrameFormat::MJpeg, None);
+
+        assert!(matches!(decoder.decode_frame(&frame), Err(NokhwaError::ProcessFrameError { .. })));
+    }
+
+    #[test]
+    fn surfaces_corrupt_frames_as_errors() {
+        let resolution = Resolution::new(32, 16);
+        let mut decoder = initialized(resolution);
+        let frame = FrameBuffer::new(resolution, TWO_COLORS[..TWO_COLORS.len() / 2].to_vec(), FrameFormat::MJpeg, None);
+
+        assert!(matches!(decoder.decode_frame(&frame), Err(NokhwaError::ProcessFrameError { .. })));
+        let garbage = FrameBuffer::new(resolution, vec![0xFF; 64], FrameFormat::MJpeg, None);
+        assert!(matches!(decoder.decode_frame(&garbage), Err(NokhwaError::ProcessFrameError { .. })));
+    }
+}
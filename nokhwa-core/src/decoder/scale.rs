@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+use crate::conversion::downscale_rgb888;
+use crate::decoder::Codec;
+use crate::error::NokhwaError;
+use crate::frame_buffer::FrameBuffer;
+use crate::frame_format::FrameFormat;
+use crate::types::{CameraFormat, FrameRate, Resolution};
+
+/// A [`Codec`] that shrinks the [`FrameFormat::Rgb888`] output of another codec to a fixed resolution in the same
+/// pass, e.g. for thumbnails. See [`downscale_rgb888`].
+///
+/// [`Codec::resolution`] is the target resolution, everything else is that of the wrapped codec.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScalingDecoder<D: Codec> {
+    inner: D,
+    target: Resolution,
+}
+
+impl<D: Codec> ScalingDecoder<D> {
+    #[must_use]
+    pub fn new(inner: D, target: Resolution) -> Self {
+        Self { inner, target }
+    }
+
+    pub fn target(&self) -> Resolution {
+        self.target
+    }
+
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+
+    fn check_source(&self, source: Resolution) -> Result<(), NokhwaError> {
+        if self.target.width() > source.width() || self.target.height() > source.height() {
+            return Err(NokhwaError::SetPropertyError {
+                property: "resolution".to_string(),
+                value: source.to_string(),
+                error: format!("Cannot downscale to the larger {}", self.target),
+                source: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<D: Codec> Codec for ScalingDecoder<D> {
+    const ALLOWED_FORMATS: &'static [FrameFormat] = D::ALLOWED_FORMATS;
+
+    fn initialize(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        if self.inner.output_format() != FrameFormat::Rgb888 {
+            return Err(NokhwaError::UnsupportedFormat { format: self.inner.output_format() });
+        }
+        self.check_source(*camera_format.resolution())?;
+        self.inner.initialize(camera_format)
+    }
+
+    fn stop(&mut self) -> Result<(), NokhwaError> {
+        self.inner.stop()
+    }
+
+    fn frame_format(&self) -> Result<FrameFormat, NokhwaError> {
+        self.inner.frame_format()
+    }
+
+    fn output_format(&self) -> FrameFormat {
+        FrameFormat::Rgb888
+    }
+
+    fn resolution(&self) -> Result<Resolution, NokhwaError> {
+        Ok(self.target)
+    }
+
+    fn frame_rate(&self) -> Result<FrameRate, NokhwaError> {
+        self.inner.frame_rate()
+    }
+
+    fn set_frame_format(&mut self, frame_format: FrameFormat) -> Result<(), NokhwaError> {
+        self.inner.set_frame_format(frame_format)
+    }
+
+    /// Sets the resolution of the *source* frames, the target stays as is.
+    fn set_resolution(&mut self, resolution: Resolution) -> Result<(), NokhwaError> {
+        self.check_source(resolution)?;
+        self.inner.set_resolution(resolution)
+    }
+
+    fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), NokhwaError> {
+        self.inner.set_frame_rate(frame_rate)
+    }
+
+    fn decode_frame(&mut self, buffer: &FrameBuffer) -> Result<Cow<'_, [u8]>, NokhwaError> {
+        let source = buffer.resolution();
+        let target = self.target;
+        if target.width() > source.width() || target.height() > source.height() {
+            return Err(NokhwaError::ProcessFrameError {
+                src: buffer.source_frame_format(),
+                destination: FrameFormat::Rgb888.to_string(),
+                error: format!("Cannot downscale {source} to the larger {target}"),
+            });
+        }
+
+        let decoded = self.inner.decode_frame(buffer)?;
+        let expected = FrameFormat::Rgb888.expected_buffer_size(source).unwrap_or_default();
+        if decoded.len() != expected {
+            return Err(NokhwaError::ProcessFrameError {
+                src: buffer.source_frame_format(),
+                destination: FrameFormat::Rgb888.to_string(),
+                error: format!("Expected {expected} decoded bytes for {source}, got {}", decoded.len()),
+            });
+        }
+        Ok(Cow::Owned(downscale_rgb888(&decoded, source, target)))
+    }
+
+    // the default would tag the output with the source resolution.
+    fn decode_frame_buffer(&mut self, buffer: &FrameBuffer) -> Result<FrameBuffer, NokhwaError> {
+        let decoded = self.decode_frame(buffer)?.into_owned();
+        Ok(FrameBuffer::new(self.target, decoded, FrameFormat::Rgb888, buffer.metadata().cloned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::yuyv::YuyvToRgbDecoder;
+
+    // a colorless YUYV row, 235 decodes to white and 16 to black.
+    fn yuyv_row(luma: [u8; 4]) -> [u8; 8] {
+        [luma[0], 128, luma[1], 128, luma[2], 128, luma[3], 128]
+    }
+
+    fn initialized(source: Resolution, target: Resolution) -> Result<ScalingDecoder<YuyvToRgbDecoder>, NokhwaError> {
+        let mut decoder = ScalingDecoder::new(YuyvToRgbDecoder::new(), target);
+        decoder.initialize(CameraFormat::new(source, FrameFormat::Yuyv422, FrameRate::default()))?;
+        Ok(decoder)
+    }
+
+    #[test]
+    fn averages_4x4_into_2x2() {
+        let source = Resolution::new(4, 4);
+        let mut decoder = initialized(source, Resolution::new(2, 2)).unwrap();
+        let data = [
+            yuyv_row([235, 235, 16, 16]),
+            yuyv_row([235, 235, 16, 235]),
+            yuyv_row([16, 16, 235, 235]),
+            yuyv_row([235, 16, 235, 235]),
+        ]
+        .concat();
+        let frame = FrameBuffer::new(source, data, FrameFormat::Yuyv422, None);
+
+        let scaled = decoder.decode_frame_buffer(&frame).unwrap();
+        assert_eq!(scaled.resolution(), Resolution::new(2, 2));
+        assert_eq!(scaled.source_frame_format(), FrameFormat::Rgb888);
+        // one of four white pixels is 255 / 4, rounded.
+        assert_eq!(scaled.buffer(), &[255, 255, 255, 64, 64, 64, 64, 64, 64, 255, 255, 255]);
+        assert_eq!(decoder.resolution().unwrap(), Resolution::new(2, 2));
+    }
+
+    #[test]
+    fn handles_non_integer_factors() {
+        let source = Resolution::new(4, 1);
+        let mut decoder = initialized(source, Resolution::new(3, 1)).unwrap();
+        let frame = FrameBuffer::new(source, yuyv_row([235, 16, 235, 16]).to_vec(), FrameFormat::Yuyv422, None);
+
+        // every target pixel covers one white and one black source pixel.
+        let scaled = decoder.decode_frame(&frame).unwrap();
+        assert_eq!(&*scaled, &[128; 3 * 3]);
+    }
+
+    #[test]
+    fn refuses_to_upscale() {
+        assert!(initialized(Resolution::new(2, 2), Resolution::new(4, 4)).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use std::borrow::Cow;
+use crate::conversion::{to_rgb888, to_rgb888_with, ColorMatrix, QuantRange};
+use crate::decoder::Codec;
+use crate::error::NokhwaError;
+use crate::frame_buffer::{FrameBuffer, Metadata};
+use crate::frame_format::FrameFormat;
+use crate::types::{CameraFormat, FrameRate, Resolution};
+
+/// A software [`Codec`] turning [`FrameFormat::Yuyv422`] frames into [`FrameFormat::Rgb888`].
+///
+/// Frames are decoded as limited range BT.601 unless told otherwise, which is what most webcams send.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct YuyvToRgbDecoder {
+    format: Option<CameraFormat>,
+    matrix: ColorMatrix,
+    range: QuantRange,
+    detect_color: bool,
+}
+
+impl YuyvToRgbDecoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A decoder using `matrix` and `range` instead of limited range BT.601.
+    #[must_use]
+    pub fn with_color(matrix: ColorMatrix, range: QuantRange) -> Self {
+        Self {
+            matrix,
+            range,
+            ..Self::default()
+        }
+    }
+
+    /// Prefer the [`crate::frame_buffer::Metadata::color_matrix`] and [`crate::frame_buffer::Metadata::quant_range`]
+    /// the backend recorded on each frame, falling back to the configured ones when a frame doesn't say.
+    #[must_use]
+    pub fn detect_color(mut self, detect: bool) -> Self {
+        self.detect_color = detect;
+        self
+    }
+
+    #[must_use]
+    pub fn color_matrix(&self) -> ColorMatrix {
+        self.matrix
+    }
+
+    #[must_use]
+    pub fn quant_range(&self) -> QuantRange {
+        self.range
+    }
+
+    // the matrix and range to decode `buffer` with.
+    fn color_of(&self, buffer: &FrameBuffer) -> (ColorMatrix, QuantRange) {
+        let metadata = buffer.metadata().filter(|_| self.detect_color);
+        (
+            metadata.and_then(Metadata::color_matrix).unwrap_or(self.matrix),
+            metadata.and_then(Metadata::quant_range).unwrap_or(self.range),
+        )
+    }
+
+    fn format(&self) -> Result<CameraFormat, NokhwaError> {
+        self.format.ok_or_else(|| NokhwaError::GetPropertyError {
+            property: "format".to_string(),
+            error: "Decoder not initialized".to_string(),
+            source: None,
+        })
+    }
+
+    fn format_mut(&mut self) -> Result<&mut CameraFormat, NokhwaError> {
+        self.format.as_mut().ok_or_else(|| NokhwaError::GetPropertyError {
+            property: "format".to_string(),
+            error: "Decoder not initialized".to_string(),
+            source: None,
+        })
+    }
+}
+
+impl Codec for YuyvToRgbDecoder {
+    const ALLOWED_FORMATS: &'static [FrameFormat] = &[FrameFormat::Yuyv422];
+
+    fn initialize(&mut self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
+        if !Self::ALLOWED_FORMATS.contains(camera_format.format()) {
+            return Err(NokhwaError::UnsupportedFormat { format: *camera_format.format() });
+        }
+        self.format = Some(camera_format);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), NokhwaError> {
+        self.format = None;
+        Ok(())
+    }
+
+    fn frame_format(&self) -> Result<FrameFormat, NokhwaError> {
+        Ok(*self.format()?.format())
+    }
+
+    fn output_format(&self) -> FrameFormat {
+        FrameFormat::Rgb888
+    }
+
+    fn resolution(&self) -> Result<Resolution, NokhwaError> {
+        Ok(*self.format()?.resolution())
+    }
+
+    fn frame_rate(&self) -> Result<FrameRate, NokhwaError> {
+        Ok(*self.format()?.frame_rate())
+    }
+
+    fn set_frame_format(&mut self, frame_format: FrameFormat) -> Result<(), NokhwaError> {
+        if !Self::ALLOWED_FORMATS.contains(&frame_format) {
+            return Err(NokhwaError::UnsupportedFormat { format: frame_format });
+        }
+        self.format_mut()?.set_format(frame_format);
+        Ok(())
+    }
+
+    fn set_resolution(&mut self, resolution: Resolution) -> Result<(), NokhwaError> {
+        self.format_mut()?.set_resolution(resolution);
+        Ok(())
+    }
+
+    fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), NokhwaError> {
+        self.format_mut()?.set_frame_rate(frame_rate);
+        Ok(())
+    }
+
+    fn decode_frame(&mut self, buffer: &FrameBuffer) -> Result<Cow<'_, [u8]>, NokhwaError> {
+        let source = buffer.source_frame_format();
+        if !Self::ALLOWED_FORMATS.contains(&source) {
+            return Err(NokhwaError::UnsupportedFormat { format: source });
+        }
+
+        let expected = source.expected_buffer_size(buffer.resolution()).unwrap_or_default();
+        if buffer.buffer().len() != expected {
+            return Err(NokhwaError::ProcessFrameError {
+                src: source,
+                destination: FrameFormat::Rgb888.to_string(),
+                error: format!("Expected {expected} bytes for {}, got {}", buffer.resolution(), buffer.buffer().len()),
+            });
+        }
+
+        let decoded = match self.color_of(buffer) {
+            // the integer fast path is limited range BT.601.
+            (ColorMatrix::Bt601, QuantRange::Limited) => to_rgb888(source, buffer.resolution(), buffer.buffer())?,
+            (matrix, range) => to_rgb888_with(source, buffer.resolution(), buffer.buffer(), matrix, range)?,
+        };
+        Ok(Cow::Owned(decoded))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame_buffer::Metadata;
+
+    fn initialized(mut decoder: YuyvToRgbDecoder, resolution: Resolution) -> YuyvToRgbDecoder {
+        decoder.initialize(CameraFormat::new(resolution, FrameFormat::Yuyv422, FrameRate::default())).unwrap();
+        decoder
+    }
+
+    #[test]
+    fn decodes_hand_computed_pixels() {
+        let mut decoder = initialized(YuyvToRgbDecoder::new(), Resolution::new(2, 2));
+        // white and black, then BT.601 red twice.
+        let frame = FrameBuffer::new(
+            Resolution::new(2, 2),
+            vec![235, 128, 16, 128, 81, 90, 81, 240],
+            FrameFormat::Yuyv422,
+            None,
+        );
+
+        let rgb = decoder.decode_frame(&frame).unwrap();
+        assert_eq!(&*rgb, &[255, 255, 255, 0, 0, 0, 255, 0, 0, 255, 0, 0]);
+    }
+
+    #[test]
+    fn decode_frame_buffer_is_rgb888() {
+        let mut decoder = initialized(YuyvToRgbDecoder::new(), Resolution::new(2, 1));
+        let frame = FrameBuffer::new(Resolution::new(2, 1), vec![235, 128, 235, 128], FrameFormat::Yuyv422, None);
+
+        let rgb = decoder.decode_frame_buffer(&frame).unwrap();
+        assert_eq!(decoder.frame_format().unwrap(), FrameFormat::Yuyv422);
+        assert_eq!(decoder.output_format(), FrameFormat::Rgb888);
+        assert_eq!(rgb.source_frame_format(), FrameFormat::Rgb888);
+        assert_eq!(rgb.buffer().len(), 2 * 3);
+    }
+
+    #[test]
+    fn rejects_a_buffer_of_the_wrong_size() {
+        let mut decoder = initialized(YuyvToRgbDecoder::new(), Resolution::new(2, 2));
+        let frame = FrameBuffer::new(Resolution::new(2, 2), vec![235, 128, 16, 128], FrameFormat::Yuyv422, None);
+
+        assert!(matches!(decoder.decode_frame(&frame), Err(NokhwaError::ProcessFrameError { .. })));
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        let mut decoder = YuyvToRgbDecoder::new();
+        let format = CameraFormat::new(Resolution::new(2, 2), FrameFormat::Nv12, FrameRate::default());
+
+        assert!(matches!(decoder.initialize(format), Err(NokhwaError::UnsupportedFormat { .. })));
+    }
+
+    #[test]
+    fn bt601_and_bt709_decode_differently() {
+        // limited range Y 126, Cr 170: R and G depend on the matrix, B doesn't.
+        let frame = FrameBuffer::new(Resolution::new(2, 1), vec![126, 128, 126, 170], FrameFormat::Yuyv422, None);
+        let mut bt601 = initialized(YuyvToRgbDecoder::new(), Resolution::new(2, 1));
+        let mut full_range_bt601 = initialized(YuyvToRgbDecoder::with_color(ColorMatrix::Bt601, QuantRange::Full), Resolution::new(2, 1));
+        let mut bt709 = initialized(YuyvToRgbDecoder::with_color(ColorMatrix::Bt709, QuantRange::Limited), Resolution::new(2, 1));
+
+        assert_eq!(&bt601.decode_frame(&frame).unwrap()[..3], &[195, 94, 128]);
+        assert_eq!(&bt709.decode_frame(&frame).unwrap()[..3], &[203, 106, 128]);
+        // full range doesn't stretch the samples.
+        assert_eq!(&full_range_bt601.decode_frame(&frame).unwrap()[..3], &[185, 96, 126]);
+    }
+
+    #[test]
+    fn detect_color_prefers_the_frame_metadata() {
+        let metadata = Metadata::new().with_color_matrix(ColorMatrix::Bt709);
+        let frame = FrameBuffer::new(Resolution::new(2, 1), vec![126, 128, 126, 170], FrameFormat::Yuyv422, Some(metadata));
+        let mut detecting = initialized(YuyvToRgbDecoder::new().detect_color(true), Resolution::new(2, 1));
+        let mut ignoring = initialized(YuyvToRgbDecoder::new(), Resolution::new(2, 1));
+
+        assert_eq!(&detecting.decode_frame(&frame).unwrap()[..3], &[203, 106, 128]);
+        assert_eq!(&ignoring.decode_frame(&frame).unwrap()[..3], &[195, 94, 128]);
+    }
+}
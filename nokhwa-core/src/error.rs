@@ -14,7 +14,9 @@
  * limitations under the License.
  */
 use crate::{frame_format::FrameFormat};
-use std::fmt::{Debug};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
 use thiserror::Error;
 use crate::platform::Backends;
 
@@ -22,7 +24,8 @@ pub type NokhwaResult<T> = Result<T, NokhwaError>;
 
 /// All errors in `nokhwa`.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Error, Debug, Clone)]
+#[derive(Error, Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum NokhwaError {
     #[error("Could not initialize {backend}: {error}")]
     InitializeError { backend: Backends, error: String },
@@ -33,14 +36,21 @@ pub enum NokhwaError {
     #[error("Could not generate required structure {structure}: {error}")]
     StructureError { structure: String, error: String },
     #[error("Could not open device {0}: {1}")]
-    OpenDeviceError(String, String),
+    OpenDeviceError(String, String, #[source] Option<ErrorSource>),
     #[error("Could not get device property {property}: {error}")]
-    GetPropertyError { property: String, error: String },
+    GetPropertyError {
+        property: String,
+        error: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
     #[error("Could not set device property {property} with value {value}: {error}")]
     SetPropertyError {
         property: String,
         value: String,
         error: String,
+        #[source]
+        source: Option<ErrorSource>,
     },
     #[error("Could not open device stream: {0}")]
     OpenStreamError(String),
@@ -62,4 +72,70 @@ pub enum NokhwaError {
     ConversionError(String),
     #[error("Permission denied by user.")]
     PermissionDenied,
+    /// The stream was closed or is terminating. This is a normal end of the stream, not a failure to read.
+    #[error("The stream is closed.")]
+    StreamClosed,
+    #[error("Frame format {format} is not supported.")]
+    UnsupportedFormat { format: FrameFormat },
+}
+
+/// The underlying error that caused a [`NokhwaError`], returned by its [`std::error::Error::source`].
+///
+/// This derefs to the original error, so e.g. an [`std::io::Error`] from the OS can be recovered with
+/// `error.source().and_then(|source| source.downcast_ref::<std::io::Error>())`.
+///
+/// Two sources are only equal if they are the same error (i.e. one is a clone of the other).
+#[derive(Clone, Debug)]
+pub struct ErrorSource(Arc<dyn std::error::Error + Send + Sync>);
+
+impl ErrorSource {
+    pub fn new<E: std::error::Error + Send + Sync + 'static>(error: E) -> Self {
+        Self(Arc::new(error))
+    }
+}
+
+impl Deref for ErrorSource {
+    type Target = dyn std::error::Error + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl Display for ErrorSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl PartialEq for ErrorSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn source_is_the_original_error() {
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "no access to /dev/video0");
+        let error = NokhwaError::OpenDeviceError("/dev/video0".to_string(), "could not open".to_string(), Some(ErrorSource::new(io)));
+
+        let source = error.source().and_then(|source| source.downcast_ref::<std::io::Error>()).unwrap();
+        assert_eq!(source.kind(), std::io::ErrorKind::PermissionDenied);
+        assert_eq!(source.to_string(), "no access to /dev/video0");
+        assert!(NokhwaError::OpenDeviceError(String::new(), String::new(), None).source().is_none());
+    }
+
+    #[test]
+    fn sources_are_only_equal_to_their_clones() {
+        let source = ErrorSource::new(std::io::Error::other("gone"));
+        let property = |source| NokhwaError::GetPropertyError { property: "Brightness".to_string(), error: "gone".to_string(), source };
+
+        assert_eq!(property(Some(source.clone())), property(Some(source)));
+        assert_ne!(property(Some(ErrorSource::new(std::io::Error::other("gone")))), property(Some(ErrorSource::new(std::io::Error::other("gone")))));
+    }
 }
@@ -5,7 +5,48 @@ use crate::{
     types::{CameraFormat, FrameRate, Resolution},
 };
 use crate::ranges::ValidatableRange;
+use typed_builder::TypedBuilder;
 
+/// Limits applied while enumerating formats, so that formats an application will never use
+/// are skipped as early as possible (e.g. before probing a resolution's frame rates).
+///
+/// A resolution is "above" the maximum if *either* its width or its height exceeds it.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq, TypedBuilder)]
+pub struct FormatLimits {
+    #[builder(default, setter(strip_option))]
+    pub max_resolution: Option<Resolution>,
+    #[builder(default, setter(strip_option))]
+    pub min_frame_rate: Option<FrameRate>,
+}
+
+impl FormatLimits {
+    /// No limits, everything is allowed.
+    #[must_use]
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn allows_resolution(&self, resolution: Resolution) -> bool {
+        match self.max_resolution {
+            Some(max) => resolution.width() <= max.width() && resolution.height() <= max.height(),
+            None => true,
+        }
+    }
+
+    #[must_use]
+    pub fn allows_frame_rate(&self, frame_rate: FrameRate) -> bool {
+        match self.min_frame_rate {
+            Some(min) => frame_rate >= min,
+            None => true,
+        }
+    }
+
+    #[must_use]
+    pub fn allows(&self, camera_format: &CameraFormat) -> bool {
+        self.allows_resolution(*camera_format.resolution()) && self.allows_frame_rate(*camera_format.frame_rate())
+    }
+}
 
 /// A helper for choosing a [`CameraFormat`].
 /// The use of this is completely optional - for a simpler way try [`crate::camera::Camera::enumerate_formats`].
@@ -14,9 +55,14 @@ use crate::ranges::ValidatableRange;
 #[derive(Clone, Debug, PartialEq)]
 pub enum FormatRequestType {
     /// Pick the closest [`CameraFormat`] to the one requested
+    ///
+    /// Closeness is measured in relative error (see [`format_distance_to_point`]). `resolution_weight` scales the
+    /// resolution's share of it, `> 1.0` favours getting the resolution right over the frame rate and `< 1.0` the
+    /// other way round. Defaults to `1.0`.
     Closest {
         resolution: Option<Range<Resolution>>,
         frame_rate: Option<Range<FrameRate>>,
+        resolution_weight: Option<f32>,
     },
     HighestFrameRate {
         frame_rate: Range<FrameRate>,
@@ -24,6 +70,13 @@ pub enum FormatRequestType {
     HighestResolution {
         resolution: Range<Resolution>,
     },
+    /// Pick the highest frame rate at `resolution`, where any resolution whose width and height are both within
+    /// `resolution_tolerance` pixels of it counts as a match (`None` means it has to be exact).
+    /// If nothing matches, falls back to the formats with the resolution closest to the requested one.
+    HighestFrameRateAtResolution {
+        resolution: Resolution,
+        resolution_tolerance: Option<u32>,
+    },
     Exact {
         resolution: Resolution,
         frame_rate: FrameRate,
@@ -45,11 +98,30 @@ impl FormatRequest {
         }
     }
 
+    /// Like [`FormatRequest::best_match`], but borrows the winner from `camera_formats`.
     pub fn best<'a>(&self, camera_formats: &'a Vec<CameraFormat>) -> Option<&'a CameraFormat> {
-        camera_formats.first()
+        let best = self.best_match(camera_formats)?;
+        camera_formats.iter().find(|candidate| **candidate == best)
+    }
+
+    /// Runs [`FormatRequest::sort_formats`] and returns the top candidate, if any survived.
+    ///
+    /// For [`FormatRequestType::Closest`], formats equally close to the requested point are ordered by
+    /// the higher frame rate first, then the higher resolution.
+    #[must_use]
+    pub fn best_match(&self, formats: &[CameraFormat]) -> Option<CameraFormat> {
+        self.sort_formats(formats.to_vec()).into_iter().next()
     }
 
-    pub fn sort_foramts(&self, mut camera_formats: Vec<CameraFormat>) -> Vec<CameraFormat> {
+    #[deprecated(note = "renamed to `sort_formats`")]
+    #[must_use]
+    pub fn sort_foramts(&self, camera_formats: Vec<CameraFormat>) -> Vec<CameraFormat> {
+        self.sort_formats(camera_formats)
+    }
+
+    /// Sorts `camera_formats` from best to worst match for this request, dropping the ones that don't match.
+    #[must_use]
+    pub fn sort_formats(&self, mut camera_formats: Vec<CameraFormat>) -> Vec<CameraFormat> {
         if camera_formats.is_empty() {
             return camera_formats;
         }
@@ -58,37 +130,34 @@ impl FormatRequest {
             FormatRequestType::Closest {
                 resolution,
                 frame_rate,
-                ..
+                resolution_weight,
             } => {
-                let resolution_point = resolution.map(|x| x.preferred());
-                let frame_rate_point = frame_rate.map(|x| x.preferred());
-                // lets calcuate distance in 3 dimensions (add both resolution and frame_rate together)
+                let resolution_point = resolution.and_then(|x| x.preferred());
+                let frame_rate_point = frame_rate.and_then(|x| x.preferred());
+                let resolution_weight = resolution_weight.unwrap_or(1.0);
 
                 camera_formats.sort_by(|a, b| {
-                    let a_distance = format_distance_to_point(&resolution_point, &frame_rate_point, a);
-                    let b_distance = format_distance_to_point(&resolution_point, &frame_rate_point, b);
+                    let a_distance = format_distance_to_point(&resolution_point, &frame_rate_point, resolution_weight, a);
+                    let b_distance = format_distance_to_point(&resolution_point, &frame_rate_point, resolution_weight, b);
 
+                    // on a tie, prefer the faster, then the bigger format.
                     a_distance.total_cmp(&b_distance)
+                        .then_with(|| b.frame_rate().cmp(a.frame_rate()))
+                        .then_with(|| b.resolution().cmp(a.resolution()))
                 });
 
                 camera_formats.into_iter().filter(|fmt| {
                     self.allowed_frame_formats.contains(fmt.format())
                 }).filter(|cam_fmt| {
-                    if let Some(res_range) = resolution {
-                        return res_range.validate(cam_fmt.resolution())
-                    }
-
-                    if let Some(frame_rate_range) = frame_rate {
-                        return frame_rate_range.validate(&cam_fmt.frame_rate())
-                    }
-                    true
+                    resolution.is_none_or(|res_range| res_range.validate(cam_fmt.resolution()))
+                        && frame_rate.is_none_or(|frame_rate_range| frame_rate_range.validate(cam_fmt.frame_rate()))
                 }).collect()
             }
             FormatRequestType::HighestFrameRate {
                 frame_rate
             } => {
                 camera_formats.sort_by(|a, b| {
-                    a.frame_rate().cmp(b.frame_rate())
+                    b.frame_rate().cmp(a.frame_rate())
                 });
 
                 camera_formats.into_iter().filter(|fmt| {
@@ -101,7 +170,7 @@ impl FormatRequest {
                 resolution
             } => {
                 camera_formats.sort_by(|a, b| {
-                    a.resolution().cmp(b.resolution())
+                    b.resolution().cmp(a.resolution())
                 });
 
                 camera_formats.into_iter().filter(|fmt| {
@@ -110,6 +179,32 @@ impl FormatRequest {
                     resolution.validate(a.resolution())
                 }).collect()
             }
+            FormatRequestType::HighestFrameRateAtResolution {
+                resolution,
+                resolution_tolerance,
+            } => {
+                let tolerance = resolution_tolerance.unwrap_or(0);
+                let allowed = camera_formats.into_iter().filter(|fmt| {
+                    self.allowed_frame_formats.contains(fmt.format())
+                }).collect::<Vec<CameraFormat>>();
+
+                let mut within = allowed.iter().copied().filter(|fmt| {
+                    fmt.resolution().width().abs_diff(resolution.width()) <= tolerance
+                        && fmt.resolution().height().abs_diff(resolution.height()) <= tolerance
+                }).collect::<Vec<CameraFormat>>();
+
+                if within.is_empty() {
+                    let nearest = allowed.iter().map(|fmt| fmt.resolution().squared_distance(&resolution)).min();
+                    within = allowed.into_iter().filter(|fmt| {
+                        Some(fmt.resolution().squared_distance(&resolution)) == nearest
+                    }).collect();
+                }
+
+                within.sort_by(|a, b| {
+                    b.frame_rate().cmp(a.frame_rate())
+                });
+                within
+            }
             FormatRequestType::Exact {
                 resolution,
                 frame_rate,
@@ -121,23 +216,183 @@ impl FormatRequest {
                 }).collect()
             }
             FormatRequestType::Any => {
-                // return as-is
-                camera_formats
+                // keep the order as-is
+                camera_formats.into_iter().filter(|fmt| {
+                    self.allowed_frame_formats.contains(fmt.format())
+                }).collect()
             }
         }
     }
 }
 
-pub fn format_distance_to_point(resolution: &Option<Resolution>, frame_rate: &Option<FrameRate>, format: &CameraFormat) -> f32 {
+/// How far `format` is from the requested point, as the sum of its relative errors:
+/// the resolution's distance divided by the requested resolution's diagonal (times `resolution_weight`)
+/// plus the frame rate's distance divided by the requested frame rate.
+///
+/// Dimensions that weren't requested (`None`) don't count.
+#[must_use]
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+pub fn format_distance_to_point(resolution: &Option<Resolution>, frame_rate: &Option<FrameRate>, resolution_weight: f32, format: &CameraFormat) -> f32 {
     let frame_rate_distance = match frame_rate {
-        Some(f_point) => (format.frame_rate() - f_point).approximate_float().unwrap_or(f32::INFINITY).abs(),
+        Some(f_point) => {
+            let distance = (format.frame_rate() - f_point).as_f32().abs();
+            let target = f_point.as_f32().abs();
+            if target.is_finite() && target > f32::EPSILON { distance / target } else { distance }
+        }
         None => 0_f32,
     };
 
     let resolution_point_distance = match resolution {
-        Some(res_pt) => format.resolution().distance_from(&res_pt) as f32,
+        Some(res_pt) => {
+            let distance = format.resolution().distance_from(res_pt) as f32;
+            let diagonal = (res_pt.width() as f32).hypot(res_pt.height() as f32);
+            if diagonal > 0.0 { distance / diagonal } else { distance }
+        }
         None => 0_f32,
     };
 
-    frame_rate_distance + resolution_point_distance
+    resolution_point_distance * resolution_weight + frame_rate_distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(width: u32, height: u32, fps: i32, frame_format: FrameFormat) -> CameraFormat {
+        CameraFormat::new(Resolution::new(width, height), frame_format, FrameRate::frame_rate(fps))
+    }
+
+    fn closest(resolution: Option<Range<Resolution>>, frame_rate: Option<Range<FrameRate>>) -> FormatRequest {
+        FormatRequest::new(
+            FormatRequestType::Closest { resolution, frame_rate, resolution_weight: None },
+            vec![FrameFormat::MJpeg],
+        )
+    }
+
+    #[test]
+    fn best_match_picks_the_closest_allowed_format() {
+        let request = closest(
+            Some(Range::new(Resolution::new(640, 480), Resolution::new(1920, 1080), None)),
+            Some(Range::new(FrameRate::frame_rate(15), FrameRate::frame_rate(30), None)),
+        );
+        let formats = [
+            format(1920, 1080, 15, FrameFormat::MJpeg),
+            format(1280, 720, 30, FrameFormat::MJpeg),
+            format(1920, 1080, 30, FrameFormat::Yuyv422),
+            format(1920, 1080, 25, FrameFormat::MJpeg),
+            format(3840, 2160, 30, FrameFormat::MJpeg),
+        ];
+
+        assert_eq!(request.best_match(&formats), Some(format(1920, 1080, 25, FrameFormat::MJpeg)));
+    }
+
+    #[test]
+    fn best_match_breaks_ties_by_frame_rate_then_resolution() {
+        let by_frame_rate = closest(Some(Range::at_least(Resolution::new(1280, 720))), None);
+        let formats = [format(1280, 720, 30, FrameFormat::MJpeg), format(1280, 720, 60, FrameFormat::MJpeg)];
+        assert_eq!(by_frame_rate.best_match(&formats), Some(format(1280, 720, 60, FrameFormat::MJpeg)));
+
+        let by_resolution = closest(None, Some(Range::new(FrameRate::frame_rate(30), FrameRate::frame_rate(30), None)));
+        let formats = [format(1280, 720, 30, FrameFormat::MJpeg), format(1920, 1080, 30, FrameFormat::MJpeg)];
+        assert_eq!(by_resolution.best_match(&formats), Some(format(1920, 1080, 30, FrameFormat::MJpeg)));
+    }
+
+    #[test]
+    fn best_match_is_none_without_a_match() {
+        let request = closest(Some(Range::at_least(Resolution::new(1920, 1080))), None);
+
+        assert_eq!(request.best_match(&[format(1280, 720, 30, FrameFormat::MJpeg)]), None);
+        assert_eq!(request.best_match(&[]), None);
+    }
+
+    #[test]
+    fn best_skips_a_disallowed_first_format() {
+        let request = FormatRequest::new(
+            FormatRequestType::HighestResolution { resolution: Range::at_least(Resolution::new(640, 480)) },
+            vec![FrameFormat::MJpeg],
+        );
+        let formats = vec![format(3840, 2160, 30, FrameFormat::Yuyv422), format(1920, 1080, 30, FrameFormat::MJpeg)];
+
+        assert_eq!(request.best(&formats), Some(&formats[1]));
+    }
+
+    #[test]
+    fn best_follows_the_request_type() {
+        let request = FormatRequest::new(
+            FormatRequestType::HighestFrameRate { frame_rate: Range::at_most(FrameRate::frame_rate(60)) },
+            vec![FrameFormat::MJpeg],
+        );
+        let formats = vec![
+            format(1920, 1080, 30, FrameFormat::MJpeg),
+            format(640, 480, 120, FrameFormat::MJpeg),
+            format(1280, 720, 60, FrameFormat::MJpeg),
+        ];
+
+        assert_eq!(request.best(&formats), Some(&formats[2]));
+    }
+
+    fn highest_frame_rate_at(resolution: Resolution, resolution_tolerance: Option<u32>) -> FormatRequest {
+        FormatRequest::new(
+            FormatRequestType::HighestFrameRateAtResolution { resolution, resolution_tolerance },
+            vec![FrameFormat::MJpeg],
+        )
+    }
+
+    #[test]
+    fn highest_frame_rate_at_resolution_sorts_matches_by_frame_rate() {
+        let formats = vec![
+            format(1920, 1080, 30, FrameFormat::MJpeg),
+            format(1280, 720, 120, FrameFormat::MJpeg),
+            format(1920, 1080, 60, FrameFormat::MJpeg),
+            format(1920, 1088, 90, FrameFormat::MJpeg),
+            format(1920, 1080, 120, FrameFormat::Yuyv422),
+        ];
+
+        let exact = highest_frame_rate_at(Resolution::new(1920, 1080), None).sort_formats(formats.clone());
+        assert_eq!(exact, vec![formats[2], formats[0]]);
+
+        let tolerant = highest_frame_rate_at(Resolution::new(1920, 1080), Some(8)).sort_formats(formats.clone());
+        assert_eq!(tolerant, vec![formats[3], formats[2], formats[0]]);
+    }
+
+    #[test]
+    fn highest_frame_rate_at_resolution_falls_back_to_the_nearest_resolution() {
+        let formats = vec![
+            format(640, 480, 120, FrameFormat::MJpeg),
+            format(1280, 720, 30, FrameFormat::MJpeg),
+            format(1280, 720, 60, FrameFormat::MJpeg),
+            format(3840, 2160, 30, FrameFormat::MJpeg),
+        ];
+
+        let sorted = highest_frame_rate_at(Resolution::new(1920, 1080), Some(8)).sort_formats(formats.clone());
+        assert_eq!(sorted, vec![formats[2], formats[1]]);
+    }
+
+    #[test]
+    fn resolution_weight_biases_the_distance() {
+        let big_and_slow = format(1920, 1080, 30, FrameFormat::MJpeg);
+        let small_and_fast = format(1280, 720, 60, FrameFormat::MJpeg);
+        let weighted = |resolution_weight| FormatRequest::new(
+            FormatRequestType::Closest {
+                resolution: Some(Range::at_least(Resolution::new(1280, 720))),
+                frame_rate: Some(Range::at_least(FrameRate::frame_rate(30))),
+                resolution_weight,
+            },
+            vec![FrameFormat::MJpeg],
+        );
+
+        // 1920x1080 is off by half of 1280x720's diagonal, 60 fps by all of 30 fps.
+        assert_eq!(weighted(None).sort_formats(vec![small_and_fast, big_and_slow]), vec![big_and_slow, small_and_fast]);
+        assert_eq!(weighted(Some(3.0)).sort_formats(vec![big_and_slow, small_and_fast]), vec![small_and_fast, big_and_slow]);
+    }
+
+    #[test]
+    fn distance_is_relative() {
+        let point = (Some(Resolution::new(1280, 720)), Some(FrameRate::frame_rate(30)));
+        let distance = |format| format_distance_to_point(&point.0, &point.1, 1.0, &format);
+
+        assert!(distance(format(1280, 720, 30, FrameFormat::MJpeg)).abs() < f32::EPSILON);
+        assert!((distance(format(1280, 720, 15, FrameFormat::MJpeg)) - 0.5).abs() < 1e-6);
+        assert!((distance(format(1920, 1080, 30, FrameFormat::MJpeg)) - 0.5).abs() < 1e-6);
+    }
 }
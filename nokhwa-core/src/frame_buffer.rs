@@ -13,10 +13,21 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
+#[cfg(feature = "image")]
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use crate::frame_format::FrameFormat;
 use small_map::{FxSmallMap, Iter};
 use crate::control::ControlValue;
+#[cfg(feature = "image")]
+use crate::decoder::Codec;
+use crate::conversion::{resize_rgb888, to_luma8, to_rgb888, to_rgb888_with, ColorMatrix, QuantRange};
+use crate::error::NokhwaError;
+use crate::types::Resolution;
 
 pub type PlatformSpecificFlag = u32;
 
@@ -26,6 +37,21 @@ pub struct Metadata {
 }
 
 impl Metadata {
+    // Keys from 0xFFFF_0000 up are reserved by nokhwa, backends must keep their own keys out of this range.
+
+    /// Reserved key of [`Metadata::dmabuf_fd`].
+    pub const DMABUF_FD: PlatformSpecificFlag = 0xFFFF_0000;
+    /// Reserved key of [`Metadata::dmabuf_length`].
+    pub const DMABUF_LENGTH: PlatformSpecificFlag = 0xFFFF_0001;
+    /// Reserved key of [`Metadata::timestamp`].
+    pub const TIMESTAMP: PlatformSpecificFlag = 0xFFFF_0002;
+    /// Reserved key of [`Metadata::sequence`].
+    pub const SEQUENCE: PlatformSpecificFlag = 0xFFFF_0003;
+    /// Reserved key of [`Metadata::color_matrix`].
+    pub const COLOR_MATRIX: PlatformSpecificFlag = 0xFFFF_0004;
+    /// Reserved key of [`Metadata::quant_range`].
+    pub const QUANT_RANGE: PlatformSpecificFlag = 0xFFFF_0005;
+
     pub fn new() -> Self {
         Self {
             flags: Default::default(),
@@ -43,12 +69,180 @@ impl Metadata {
     pub fn iter(&self) -> Iter<'_, 8, u32, ControlValue> {
         self.flags.iter()
     }
+
+    /// [`Metadata::insert`], for building metadata in one expression, e.g.
+    /// `Metadata::new().with_sequence(sequence).with_timestamp(timestamp)`.
+    #[must_use]
+    pub fn with(mut self, key: u32, value: ControlValue) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// The DMABUF file descriptor this frame was exported as, if the stream was opened with
+    /// [`crate::stream::StreamConfiguration::dmabuf`].
+    ///
+    /// The descriptor belongs to the frame: it stays open, and the driver leaves the buffer alone, for as long as the
+    /// frame (or any clone of it) is alive. `dup` it to keep the descriptor longer, the buffer's contents are only
+    /// stable while the frame is around though.
+    #[must_use]
+    pub fn dmabuf_fd(&self) -> Option<i32> {
+        match self.get(Self::DMABUF_FD) {
+            Some(ControlValue::Integer(fd)) => i32::try_from(*fd).ok(),
+            _ => None,
+        }
+    }
+
+    /// Records the DMABUF file descriptor of this frame. See [`Metadata::dmabuf_fd`].
+    pub fn set_dmabuf_fd(&mut self, fd: i32) {
+        self.insert(Self::DMABUF_FD, ControlValue::Integer(i64::from(fd)));
+    }
+
+    /// See [`Metadata::set_dmabuf_fd`].
+    #[must_use]
+    pub fn with_dmabuf_fd(mut self, fd: i32) -> Self {
+        self.set_dmabuf_fd(fd);
+        self
+    }
+
+    /// How many bytes of the DMABUF (see [`Metadata::dmabuf_fd`]) hold the frame.
+    #[must_use]
+    pub fn dmabuf_length(&self) -> Option<usize> {
+        match self.get(Self::DMABUF_LENGTH) {
+            Some(ControlValue::Integer(length)) => usize::try_from(*length).ok(),
+            _ => None,
+        }
+    }
+
+    /// Records how many bytes of the DMABUF hold the frame. See [`Metadata::dmabuf_length`].
+    pub fn set_dmabuf_length(&mut self, length: usize) {
+        self.insert(Self::DMABUF_LENGTH, ControlValue::Integer(i64::try_from(length).unwrap_or(i64::MAX)));
+    }
+
+    /// See [`Metadata::set_dmabuf_length`].
+    #[must_use]
+    pub fn with_dmabuf_length(mut self, length: usize) -> Self {
+        self.set_dmabuf_length(length);
+        self
+    }
+
+    /// When the frame was captured, as reported by the backend.
+    ///
+    /// The epoch is backend specific (on V4L2 it is usually the monotonic clock), so only compare timestamps from the same stream.
+    #[must_use]
+    pub fn timestamp(&self) -> Option<Duration> {
+        match self.get(Self::TIMESTAMP) {
+            Some(ControlValue::Integer(nanos)) => u64::try_from(*nanos).ok().map(Duration::from_nanos),
+            _ => None,
+        }
+    }
+
+    /// Records when the frame was captured. See [`Metadata::timestamp`].
+    pub fn set_timestamp(&mut self, timestamp: Duration) {
+        self.insert(Self::TIMESTAMP, ControlValue::Integer(i64::try_from(timestamp.as_nanos()).unwrap_or(i64::MAX)));
+    }
+
+    /// See [`Metadata::set_timestamp`].
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: Duration) -> Self {
+        self.set_timestamp(timestamp);
+        self
+    }
+
+    /// The frame's sequence number, counting up with every frame the driver captures.
+    ///
+    /// A gap between two frames means frames were dropped in between.
+    #[must_use]
+    pub fn sequence(&self) -> Option<u64> {
+        match self.get(Self::SEQUENCE) {
+            Some(ControlValue::Integer(sequence)) => u64::try_from(*sequence).ok(),
+            _ => None,
+        }
+    }
+
+    /// Records the frame's sequence number. See [`Metadata::sequence`].
+    pub fn set_sequence(&mut self, sequence: u64) {
+        self.insert(Self::SEQUENCE, ControlValue::Integer(i64::try_from(sequence).unwrap_or(i64::MAX)));
+    }
+
+    /// See [`Metadata::set_sequence`].
+    #[must_use]
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.set_sequence(sequence);
+        self
+    }
+
+    /// The YCbCr matrix the backend says the frame was encoded with, if it knows.
+    #[must_use]
+    pub fn color_matrix(&self) -> Option<ColorMatrix> {
+        match self.get(Self::COLOR_MATRIX) {
+            Some(ControlValue::Integer(0)) => Some(ColorMatrix::Bt601),
+            Some(ControlValue::Integer(1)) => Some(ColorMatrix::Bt709),
+            Some(ControlValue::Integer(2)) => Some(ColorMatrix::Bt2020),
+            _ => None,
+        }
+    }
+
+    /// Records the frame's YCbCr matrix. See [`Metadata::color_matrix`].
+    ///
+    /// Only the standard matrices can be recorded, a [`ColorMatrix::Custom`] one is ignored.
+    pub fn set_color_matrix(&mut self, matrix: ColorMatrix) {
+        let tag = match matrix {
+            ColorMatrix::Bt601 => 0,
+            ColorMatrix::Bt709 => 1,
+            ColorMatrix::Bt2020 => 2,
+            ColorMatrix::Custom(_) => return,
+        };
+        self.insert(Self::COLOR_MATRIX, ControlValue::Integer(tag));
+    }
+
+    /// See [`Metadata::set_color_matrix`].
+    #[must_use]
+    pub fn with_color_matrix(mut self, matrix: ColorMatrix) -> Self {
+        self.set_color_matrix(matrix);
+        self
+    }
+
+    /// The YCbCr quantization range the backend says the frame uses, if it knows.
+    #[must_use]
+    pub fn quant_range(&self) -> Option<QuantRange> {
+        match self.get(Self::QUANT_RANGE) {
+            Some(ControlValue::Integer(0)) => Some(QuantRange::Limited),
+            Some(ControlValue::Integer(1)) => Some(QuantRange::Full),
+            _ => None,
+        }
+    }
+
+    /// Records the frame's YCbCr quantization range. See [`Metadata::quant_range`].
+    pub fn set_quant_range(&mut self, range: QuantRange) {
+        let tag = match range {
+            QuantRange::Limited => 0,
+            QuantRange::Full => 1,
+        };
+        self.insert(Self::QUANT_RANGE, ControlValue::Integer(tag));
+    }
+
+    /// See [`Metadata::set_quant_range`].
+    #[must_use]
+    pub fn with_quant_range(mut self, range: QuantRange) -> Self {
+        self.set_quant_range(range);
+        self
+    }
+}
+
+/// See [`Metadata::iter`].
+impl<'a> IntoIterator for &'a Metadata {
+    type Item = (&'a u32, &'a ControlValue);
+    type IntoIter = Iter<'a, 8, u32, ControlValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl Hash for Metadata {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        for (key, value) in self.flags {
-            state.write_u32(key);
+        for (key, value) in &self.flags {
+            state.write_u32(*key);
             value.hash(state);
         }
     }
@@ -69,26 +263,105 @@ impl PartialEq for Metadata {
     }
 }
 
+/// Memory layout of a tensor produced by [`FrameBuffer::to_model_input`].
+#[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub enum TensorLayout {
+    /// Channels first: all red values, then all green values, then all blue values.
+    Nchw,
+    /// Channels last: `R, G, B` for each pixel in turn.
+    Nhwc,
+}
+
+/// A borrowed view of one plane of a [`FrameBuffer`].
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Plane<'a> {
+    data: &'a [u8],
+    stride: usize,
+}
+
+impl<'a> Plane<'a> {
+    /// The samples of this plane, `stride` bytes per row.
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// How many bytes one row of this plane takes up.
+    #[must_use]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+}
+
+/// Borrowed views of the planes of a planar [`FrameBuffer`], see [`FrameBuffer::planes`].
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Planes<'a> {
+    /// A Y plane followed by one plane of interleaved chroma samples.
+    /// For [`FrameFormat::Nv12`] those are `U, V` pairs, for [`FrameFormat::Nv21`] `V, U` pairs.
+    SemiPlanar { y: Plane<'a>, chroma: Plane<'a> },
+    /// Separate Y, U and V planes, in that order regardless of their order in the buffer.
+    Planar { y: Plane<'a>, u: Plane<'a>, v: Plane<'a> },
+}
+
+// What a frame's data lives in, see `FrameBuffer::with_backing`. It isn't part of the frame's value, so it is
+// ignored when comparing or hashing frames.
+#[derive(Clone, Default)]
+struct Backing(Option<Arc<dyn Any + Send + Sync>>);
+
+impl Debug for Backing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
+impl PartialEq for Backing {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Hash for Backing {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
 /// A buffer returned by a camera to accommodate custom decoding.
 /// Contains information of Resolution, the buffer's [`FrameFormat`], and the buffer.
 ///
 /// Note that decoding on the main thread **will** decrease your performance and lead to dropped frames.
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct FrameBuffer {
+    resolution: Resolution,
     buffer: Vec<u8>,
+    source_frame_format: FrameFormat,
     metadata: Option<Metadata>,
+    backing: Backing,
 }
 
 impl FrameBuffer {
     /// Creates a new buffer with a [`&[u8]`].
     #[must_use]
     #[inline]
-    pub fn new(buffer: Vec<u8>, metadata: Option<Metadata>) -> Self {
+    pub fn new(resolution: Resolution, buffer: Vec<u8>, source_frame_format: FrameFormat, metadata: Option<Metadata>) -> Self {
         Self {
+            resolution,
             buffer,
+            source_frame_format,
             metadata,
+            backing: Backing::default(),
         }
     }
+
+    /// Get the [`Resolution`] of this buffer.
+    #[must_use]
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Get the [`FrameFormat`] the data of this buffer is encoded in.
+    #[must_use]
+    pub fn source_frame_format(&self) -> FrameFormat {
+        self.source_frame_format
+    }
     
     /// Get the data of this buffer.
     #[must_use]
@@ -106,4 +379,441 @@ impl FrameBuffer {
         self.metadata.as_ref()
     }
 
+    /// The metadata, for backends (or pipelines) adding to it after the buffer was created.
+    pub fn metadata_mut(&mut self) -> &mut Option<Metadata> {
+        &mut self.metadata
+    }
+
+    /// Replaces the buffer's metadata with `metadata`.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Keeps `backing` alive for as long as this buffer, or any clone of it, is.
+    ///
+    /// Backends use this for frames that point into memory they must not reuse while the frame is around, e.g. the
+    /// driver buffer behind a [`Metadata::dmabuf_fd`].
+    #[must_use]
+    pub fn with_backing(mut self, backing: Arc<dyn Any + Send + Sync>) -> Self {
+        self.backing = Backing(Some(backing));
+        self
+    }
+
+    /// Copies the `width`x`height` rectangle at (`x`, `y`) out into a new buffer of the same [`FrameFormat`].
+    ///
+    /// Subsampled formats can only be cropped along their chroma grid: `x` has to be even for packed 4:2:2 formats,
+    /// and `x` and `y` for 4:2:0 and Bayer formats. Metadata is carried over.
+    /// # Errors
+    /// If the rectangle is empty, doesn't fit the frame or isn't on the chroma grid, the buffer is too small, or the
+    /// format can't be cropped (e.g. compressed formats), this will error.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Result<FrameBuffer, NokhwaError> {
+        let error = |error: String| NokhwaError::ProcessFrameError {
+            src: self.source_frame_format,
+            destination: self.source_frame_format.to_string(),
+            error,
+        };
+
+        let fits = |offset: u32, length: u32, total: u32| length > 0 && offset.checked_add(length).is_some_and(|end| end <= total);
+        if !fits(x, width, self.resolution.width()) || !fits(y, height, self.resolution.height()) {
+            return Err(error(format!("Crop {width}x{height} at ({x}, {y}) does not fit {}", self.resolution)));
+        }
+        let expected = self.source_frame_format.expected_buffer_size(self.resolution)
+            .ok_or_else(|| error("Cannot crop this format".to_string()))?;
+        if self.buffer.len() < expected {
+            return Err(error(format!("Buffer too small: expected {expected} bytes, got {}", self.buffer.len())));
+        }
+
+        let cropped_resolution = Resolution::new(width, height);
+        let (x, y, width, height) = (x as usize, y as usize, width as usize, height as usize);
+        let source_width = self.resolution.width() as usize;
+        let source_height = self.resolution.height() as usize;
+        let mut cropped = Vec::new();
+        // copies `length` bytes of each of `rows` rows starting at `row` out of a plane at `plane_start`.
+        let mut copy_rows = |plane_start: usize, stride: usize, row: usize, rows: usize, start: usize, length: usize| {
+            for row in row..row + rows {
+                let row_start = plane_start + row * stride + start;
+                cropped.extend_from_slice(&self.buffer[row_start..row_start + length]);
+            }
+        };
+
+        match self.source_frame_format {
+            FrameFormat::Yuyv422 | FrameFormat::Uyvy422 | FrameFormat::Yvyu422 => {
+                if x % 2 != 0 {
+                    return Err(error("x must be even for 4:2:2 formats".to_string()));
+                }
+                copy_rows(0, source_width.div_ceil(2) * 4, y, height, x * 2, width.div_ceil(2) * 4);
+            }
+            FrameFormat::Nv12 | FrameFormat::Nv21 | FrameFormat::I420 | FrameFormat::Yv12 => {
+                if x % 2 != 0 || y % 2 != 0 {
+                    return Err(error("x and y must be even for 4:2:0 formats".to_string()));
+                }
+                let luma_size = source_width * source_height;
+                let chroma_width = source_width.div_ceil(2);
+                let chroma_size = chroma_width * source_height.div_ceil(2);
+
+                copy_rows(0, source_width, y, height, x, width);
+                if matches!(self.source_frame_format, FrameFormat::Nv12 | FrameFormat::Nv21) {
+                    copy_rows(luma_size, chroma_width * 2, y / 2, height.div_ceil(2), x, width.div_ceil(2) * 2);
+                } else {
+                    for plane in 0..2 {
+                        copy_rows(luma_size + plane * chroma_size, chroma_width, y / 2, height.div_ceil(2), x / 2, width.div_ceil(2));
+                    }
+                }
+            }
+            FrameFormat::P010 => {
+                if x % 2 != 0 || y % 2 != 0 {
+                    return Err(error("x and y must be even for 4:2:0 formats".to_string()));
+                }
+                // like NV12, with two bytes per sample.
+                let luma_size = source_width * 2 * source_height;
+                copy_rows(0, source_width * 2, y, height, x * 2, width * 2);
+                copy_rows(luma_size, source_width.div_ceil(2) * 4, y / 2, height.div_ceil(2), x * 2, width.div_ceil(2) * 4);
+            }
+            FrameFormat::Yvu9 => return Err(error("Cannot crop this format".to_string())),
+            other => {
+                if matches!(other, FrameFormat::Bayer8 | FrameFormat::Bayer16) && (x % 2 != 0 || y % 2 != 0) {
+                    return Err(error("x and y must be even for Bayer formats".to_string()));
+                }
+                // every remaining format with a known size has a whole number of bytes per pixel
+                let bytes_per_pixel = other.bits_per_pixel().unwrap_or_default() as usize / 8;
+                copy_rows(0, source_width * bytes_per_pixel, y, height, x * bytes_per_pixel, width * bytes_per_pixel);
+            }
+        }
+
+        Ok(FrameBuffer::new(cropped_resolution, cropped, self.source_frame_format, self.metadata.clone()))
+    }
+
+    /// Splits this buffer into its planes without copying.
+    ///
+    /// Returns `None` for formats that aren't planar 4:2:0 (e.g. packed formats like [`FrameFormat::Yuyv422`]),
+    /// or if the buffer is too small for its resolution.
+    #[must_use]
+    pub fn planes(&self) -> Option<Planes<'_>> {
+        let width = self.resolution.width() as usize;
+        let height = self.resolution.height() as usize;
+        let chroma_width = width.div_ceil(2);
+        let chroma_height = height.div_ceil(2);
+        let luma_size = width * height;
+        let chroma_size = chroma_width * chroma_height;
+
+        if self.buffer.len() < luma_size + chroma_size * 2 {
+            return None;
+        }
+        let (luma, chroma) = self.buffer.split_at(luma_size);
+        let y = Plane { data: luma, stride: width };
+
+        match self.source_frame_format {
+            FrameFormat::Nv12 | FrameFormat::Nv21 => Some(Planes::SemiPlanar {
+                y,
+                chroma: Plane { data: &chroma[..chroma_size * 2], stride: chroma_width * 2 },
+            }),
+            FrameFormat::I420 | FrameFormat::Yv12 => {
+                let first = Plane { data: &chroma[..chroma_size], stride: chroma_width };
+                let second = Plane { data: &chroma[chroma_size..chroma_size * 2], stride: chroma_width };
+                let (u, v) = if self.source_frame_format == FrameFormat::I420 {
+                    (first, second)
+                } else {
+                    (second, first)
+                };
+                Some(Planes::Planar { y, u, v })
+            }
+            _ => None,
+        }
+    }
+
+    /// Decodes this buffer into packed RGB888 data using the software converters in [`crate::conversion`].
+    /// # Errors
+    /// If the [`FrameFormat`] has no software conversion or the buffer is too small, this will error.
+    pub fn decode_to_rgb(&self) -> Result<Vec<u8>, NokhwaError> {
+        to_rgb888(self.source_frame_format, self.resolution, &self.buffer)
+    }
+
+    /// [`FrameBuffer::decode_to_rgb`], but YCbCr formats are converted with `matrix` and `range`
+    /// instead of the default limited range BT.601.
+    /// # Errors
+    /// If the [`FrameFormat`] has no software conversion or the buffer is too small, this will error.
+    pub fn decode_to_rgb_with(&self, matrix: ColorMatrix, range: QuantRange) -> Result<Vec<u8>, NokhwaError> {
+        to_rgb888_with(self.source_frame_format, self.resolution, &self.buffer, matrix, range)
+    }
+
+    /// The samples of a 16-bit format (see [`FrameFormat::is_16bit`]), read from the buffer in little or big endian
+    /// byte order. V4L2 delivers `Y16 ` and `Z16 ` frames as little endian.
+    ///
+    /// Returns `None` if the format isn't 16-bit, or the buffer is too small for the resolution.
+    #[must_use]
+    pub fn luma16_samples(&self, little_endian: bool) -> Option<Vec<u16>> {
+        if !self.source_frame_format.is_16bit() {
+            return None;
+        }
+        let size = self.source_frame_format.expected_buffer_size(self.resolution)?;
+        let bytes = self.buffer.get(..size)?;
+
+        let samples = bytes.chunks_exact(2).map(|sample| {
+            let sample = [sample[0], sample[1]];
+            if little_endian { u16::from_le_bytes(sample) } else { u16::from_be_bytes(sample) }
+        });
+        Some(samples.collect())
+    }
+
+    /// Extracts an 8-bit grayscale image from this buffer, see [`crate::conversion::to_luma8`].
+    ///
+    /// This is much cheaper than [`FrameBuffer::decode_to_rgb`] for YCbCr formats, which is all
+    /// motion detection or QR scanning usually need.
+    /// # Errors
+    /// If the [`FrameFormat`] has no software conversion or the buffer is too small, this will error.
+    pub fn to_luma8(&self) -> Result<Vec<u8>, NokhwaError> {
+        to_luma8(self.source_frame_format, self.resolution, &self.buffer)
+    }
+
+    /// Decodes this buffer with `decoder` into an [`image::RgbImage`], e.g. to save it as a PNG.
+    /// # Errors
+    /// If `decoder` doesn't output [`FrameFormat::Rgb888`], decoding fails, or the decoded data doesn't fit the
+    /// buffer's resolution, this will error.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self, decoder: &mut impl Codec) -> Result<image::RgbImage, NokhwaError> {
+        if decoder.output_format() != FrameFormat::Rgb888 {
+            return Err(NokhwaError::ProcessFrameError {
+                src: self.source_frame_format,
+                destination: "RgbImage".to_string(),
+                error: format!("Decoder outputs {}, not {}", decoder.output_format(), FrameFormat::Rgb888),
+            });
+        }
+
+        let rgb = decoder.decode_frame(self)?.into_owned();
+        image::RgbImage::from_raw(self.resolution.width(), self.resolution.height(), rgb).ok_or_else(|| {
+            NokhwaError::ProcessFrameError {
+                src: self.source_frame_format,
+                destination: "RgbImage".to_string(),
+                error: format!("Decoded data does not fit {}", self.resolution),
+            }
+        })
+    }
+
+    /// Decodes this buffer with `decoder` like [`FrameBuffer::to_image`] and saves it to `path`, encoded as PNG, JPEG
+    /// or BMP depending on its extension (`.png`, `.jpg`/`.jpeg` or `.bmp`).
+    /// # Errors
+    /// If `path` has any other extension, decoding fails, or the file can't be written, this will error.
+    #[cfg(feature = "image")]
+    pub fn save_to_path(&self, path: impl AsRef<Path>, decoder: &mut impl Codec) -> Result<(), NokhwaError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|extension| extension.to_str()).map(str::to_ascii_lowercase);
+        let format = match extension.as_deref() {
+            Some("png") => image::ImageFormat::Png,
+            Some("jpg" | "jpeg") => image::ImageFormat::Jpeg,
+            Some("bmp") => image::ImageFormat::Bmp,
+            _ => {
+                return Err(NokhwaError::GeneralError(format!(
+                    "Cannot save to {}: the extension must be png, jpg, jpeg or bmp",
+                    path.display()
+                )));
+            }
+        };
+
+        self.to_image(decoder)?.save_with_format(path, format).map_err(|why| {
+            NokhwaError::GeneralError(format!("Failed to save to {}: {why}", path.display()))
+        })
+    }
+
+    /// [`FrameBuffer::to_luma8`], wrapped in an [`image::GrayImage`].
+    /// # Errors
+    /// If the luma extraction fails, this will error.
+    #[cfg(feature = "image")]
+    pub fn to_luma(&self) -> Result<image::GrayImage, NokhwaError> {
+        image::GrayImage::from_raw(self.resolution.width(), self.resolution.height(), self.to_luma8()?).ok_or_else(|| {
+            NokhwaError::ProcessFrameError {
+                src: self.source_frame_format,
+                destination: "GrayImage".to_string(),
+                error: format!("Luma data does not fit {}", self.resolution),
+            }
+        })
+    }
+
+    /// Prepares this buffer as input for a machine learning model: decodes it to RGB, resizes it
+    /// to `target`, and lays it out as a batch-of-one float tensor in `layout`.
+    ///
+    /// Values are scaled into `0.0..=1.0`. If `normalize` is `Some((mean, std))`, each value is
+    /// further mapped to `(value - mean) / std`.
+    /// # Errors
+    /// If decoding fails, `target` has a zero dimension, or `std` is zero, this will error.
+    pub fn to_model_input(&self, target: Resolution, layout: TensorLayout, normalize: Option<(f32, f32)>) -> Result<Vec<f32>, NokhwaError> {
+        if target.width() == 0 || target.height() == 0 {
+            return Err(NokhwaError::ProcessFrameError {
+                src: self.source_frame_format,
+                destination: "Tensor".to_string(),
+                error: format!("Invalid target resolution {target}"),
+            });
+        }
+        let (mean, std) = normalize.unwrap_or((0.0, 1.0));
+        if std.abs() < f32::EPSILON {
+            return Err(NokhwaError::ProcessFrameError {
+                src: self.source_frame_format,
+                destination: "Tensor".to_string(),
+                error: "Normalization standard deviation cannot be zero".to_string(),
+            });
+        }
+
+        let rgb = resize_rgb888(&self.decode_to_rgb()?, self.resolution, target);
+        let to_value = |byte: u8| (f32::from(byte) / 255.0 - mean) / std;
+
+        Ok(match layout {
+            TensorLayout::Nhwc => rgb.into_iter().map(to_value).collect(),
+            TensorLayout::Nchw => {
+                let pixels = rgb.len() / 3;
+                let mut tensor = vec![0_f32; rgb.len()];
+                for (idx, pixel) in rgb.chunks_exact(3).enumerate() {
+                    for (channel, byte) in pixel.iter().enumerate() {
+                        tensor[channel * pixels + idx] = to_value(*byte);
+                    }
+                }
+                tensor
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn save_to_path_writes_a_png() {
+        use crate::decoder::yuyv::YuyvToRgbDecoder;
+        use crate::types::{CameraFormat, FrameRate};
+
+        let resolution = Resolution::new(4, 2);
+        let mut decoder = YuyvToRgbDecoder::new();
+        decoder.initialize(CameraFormat::new(resolution, FrameFormat::Yuyv422, FrameRate::default())).unwrap();
+        let frame = FrameBuffer::new(resolution, [235, 128, 16, 128].repeat(4), FrameFormat::Yuyv422, None);
+
+        let directory = std::env::temp_dir().join(format!("nokhwa-save-to-path-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("frame.png");
+        frame.save_to_path(&path, &mut decoder).unwrap();
+        let saved = image::open(&path).unwrap().to_rgb8();
+        let unsupported = frame.save_to_path(directory.join("frame.gif"), &mut decoder);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(saved.dimensions(), (4, 2));
+        assert_eq!(saved.get_pixel(0, 0).0, [255, 255, 255]);
+        assert_eq!(saved.get_pixel(1, 0).0, [0, 0, 0]);
+        assert!(matches!(unsupported, Err(NokhwaError::GeneralError(_))));
+    }
+
+
+    #[test]
+    fn crop_copies_the_rectangle_and_keeps_the_metadata() {
+        // every pixel of a 4x3 RGB888 frame is (column, row, 255).
+        let pixels = (0..3).flat_map(|row| (0..4).flat_map(move |column| [column, row, 255])).collect::<Vec<u8>>();
+        let metadata = Metadata::new().with_sequence(7);
+        let frame = FrameBuffer::new(Resolution::new(4, 3), pixels, FrameFormat::Rgb888, Some(metadata.clone()));
+
+        let cropped = frame.crop(1, 1, 2, 2).unwrap();
+        assert_eq!(cropped.resolution(), Resolution::new(2, 2));
+        assert_eq!(cropped.buffer(), &[1, 1, 255, 2, 1, 255, 1, 2, 255, 2, 2, 255]);
+        assert_eq!(cropped.metadata(), Some(&metadata));
+        assert_eq!(frame.crop(0, 0, 4, 3).unwrap().buffer(), frame.buffer());
+    }
+
+    #[test]
+    fn crop_rejects_invalid_rectangles() {
+        let frame = FrameBuffer::new(Resolution::new(4, 2), vec![0; 24], FrameFormat::Rgb888, None);
+        for (x, y, width, height) in [(0, 0, 0, 1), (3, 0, 2, 1), (0, 1, 1, 2), (u32::MAX, 0, 2, 1)] {
+            assert!(matches!(frame.crop(x, y, width, height), Err(NokhwaError::ProcessFrameError { .. })), "{x} {y} {width} {height}");
+        }
+
+        let yuyv = FrameBuffer::new(Resolution::new(4, 2), vec![0; 16], FrameFormat::Yuyv422, None);
+        assert!(yuyv.crop(1, 0, 2, 1).is_err());
+        assert!(FrameBuffer::new(Resolution::new(4, 2), vec![0; 4], FrameFormat::Rgb888, None).crop(0, 0, 1, 1).is_err());
+        assert!(FrameBuffer::new(Resolution::new(4, 2), vec![0; 64], FrameFormat::MJpeg, None).crop(0, 0, 1, 1).is_err());
+    }
+
+
+    #[test]
+    fn planes_split_4_2_0_frames_without_copying() {
+        let resolution = Resolution::new(640, 480);
+        let size = FrameFormat::Nv12.expected_buffer_size(resolution).unwrap();
+        let buffer = vec![0; size];
+
+        let nv12 = FrameBuffer::new(resolution, buffer.clone(), FrameFormat::Nv12, None);
+        let Some(Planes::SemiPlanar { y, chroma }) = nv12.planes() else {
+            panic!("NV12 is semi-planar");
+        };
+        assert_eq!((y.data().len(), y.stride()), (307_200, 640));
+        assert_eq!((chroma.data().len(), chroma.stride()), (153_600, 640));
+        assert_eq!(chroma.data().as_ptr(), nv12.buffer()[307_200..].as_ptr());
+
+        let i420 = FrameBuffer::new(resolution, buffer, FrameFormat::I420, None);
+        let Some(Planes::Planar { y, u, v }) = i420.planes() else {
+            panic!("I420 is planar");
+        };
+        assert_eq!(y.data().len(), 307_200);
+        assert_eq!((u.data().len(), u.stride()), (76_800, 320));
+        assert_eq!(u.data().as_ptr(), i420.buffer()[307_200..].as_ptr());
+        assert_eq!(v.data().as_ptr(), i420.buffer()[384_000..].as_ptr());
+
+        assert!(FrameBuffer::new(resolution, vec![0; 1000], FrameFormat::Nv12, None).planes().is_none());
+        assert!(FrameBuffer::new(resolution, vec![0; size], FrameFormat::Luma8, None).planes().is_none());
+    }
+
+
+    #[test]
+    fn timestamps_and_sequences_read_back() {
+        let mut metadata = Metadata::new().with_timestamp(Duration::from_micros(1_500)).with_sequence(42);
+        assert_eq!(metadata.timestamp(), Some(Duration::from_micros(1_500)));
+        assert_eq!(metadata.sequence(), Some(42));
+
+        metadata.set_sequence(43);
+        assert_eq!(metadata.sequence(), Some(43));
+        // values that don't fit the integer control are saturated.
+        metadata.set_sequence(u64::MAX);
+        assert_eq!(metadata.sequence(), Some(i64::MAX.unsigned_abs()));
+
+        assert_eq!(Metadata::new().timestamp(), None);
+        let foreign = Metadata::new().with(Metadata::SEQUENCE, ControlValue::Integer(-1));
+        assert_eq!(foreign.sequence(), None);
+    }
+
+
+    #[test]
+    fn luma16_samples_read_either_byte_order() {
+        let frame = FrameBuffer::new(Resolution::new(2, 1), vec![0x34, 0x12, 0xFF, 0x00], FrameFormat::Luma16, None);
+        assert_eq!(frame.luma16_samples(true), Some(vec![0x1234, 0x00FF]));
+        assert_eq!(frame.luma16_samples(false), Some(vec![0x3412, 0xFF00]));
+
+        // extra bytes past the resolution are ignored, missing ones are not.
+        let padded = FrameBuffer::new(Resolution::new(1, 1), vec![0x01, 0x00, 0xAA], FrameFormat::Y10, None);
+        assert_eq!(padded.luma16_samples(true), Some(vec![1]));
+        assert_eq!(FrameBuffer::new(Resolution::new(2, 1), vec![0; 3], FrameFormat::Depth16, None).luma16_samples(true), None);
+        assert_eq!(FrameBuffer::new(Resolution::new(2, 1), vec![0; 4], FrameFormat::Yuyv422, None).luma16_samples(true), None);
+    }
+
+
+    #[test]
+    fn metadata_can_be_added_after_the_fact() {
+        let mut frame = FrameBuffer::new(Resolution::new(1, 1), vec![0], FrameFormat::Luma8, None);
+        assert_eq!(frame.metadata(), None);
+
+        frame.metadata_mut().get_or_insert_with(Metadata::new).set_sequence(1);
+        frame.metadata_mut().as_mut().unwrap().set_timestamp(Duration::from_millis(33));
+        let metadata = frame.metadata().unwrap();
+        assert_eq!((metadata.sequence(), metadata.timestamp()), (Some(1), Some(Duration::from_millis(33))));
+
+        // `with_metadata` replaces whatever was there.
+        let frame = frame.with_metadata(Metadata::new().with_sequence(2));
+        assert_eq!(frame.metadata().unwrap().sequence(), Some(2));
+        assert_eq!(frame.metadata().unwrap().timestamp(), None);
+    }
+
+
+    #[test]
+    fn metadata_iterates_by_reference() {
+        let metadata = Metadata::new().with_sequence(9).with(7, ControlValue::Boolean(true));
+        let mut entries = (&metadata).into_iter().map(|(key, value)| (*key, value.clone())).collect::<Vec<_>>();
+        entries.sort_by_key(|(key, _)| *key);
+        assert_eq!(entries, vec![(7, ControlValue::Boolean(true)), (Metadata::SEQUENCE, ControlValue::Integer(9))]);
+        assert_eq!(metadata.iter().count(), 2);
+    }
 }
@@ -14,6 +14,8 @@
  * limitations under the License.
  */
 
+use crate::error::NokhwaError;
+use crate::types::Resolution;
 use std::fmt::{Display, Formatter};
 
 /// Describes a frame format (i.e. how the bytes themselves are encoded). Often called `FourCC`.
@@ -44,12 +46,15 @@ pub enum FrameFormat {
     Yuyv422, // AKA YUY2
     Uyvy422, // UYUV
     Yvyu422,
-    Yv12,
 
     // 4:2:0
+    Yv12,
     Nv12,
     Nv21,
     I420,
+    /// Semi-planar 4:2:0 like [`FrameFormat::Nv12`], but every sample is 16 bits (little endian) with the
+    /// 10 significant bits at the top.
+    P010,
 
     // 16:1:1
     Yvu9,
@@ -57,12 +62,18 @@ pub enum FrameFormat {
     // Grayscale Formats
     Luma8,
     Luma16,
+    /// 10-bit luma, each sample in the low bits of a 16-bit little endian word.
+    Y10,
+    /// 12-bit luma, each sample in the low bits of a 16-bit little endian word.
+    Y12,
 
     // Depth
     Depth16,
 
     // RGB Formats
     Rgb332,
+    Rgb555,
+    Rgb565,
     Rgb888,
 
     Bgr888,
@@ -100,31 +111,205 @@ macro_rules! define_frame_format_groups {
 define_frame_format_groups! {
     ALL => [
         H263, H264, H265, Av1, Avc1, Mpeg1, Mpeg2, Mpeg4, MJpeg, XVid,
-        VP8, VP9, Yuyv422, Uyvy422, Nv12, Nv21, Yv12, Luma8, Luma16,
-        Rgb332, RgbA8888
+        VP8, VP9, Ayuv444, Yuyv422, Uyvy422, Yvyu422, Yv12, Nv12, Nv21,
+        I420, P010, Yvu9, Luma8, Luma16, Y10, Y12, Depth16, Rgb332, Rgb555,
+        Rgb565, Rgb888, Bgr888, BgrA8888, RgbA8888, ARgb8888, Bayer8, Bayer16
     ],
     COMPRESSED => [
         H263, H264, H265, Av1, Avc1, Mpeg1, Mpeg2, Mpeg4, MJpeg, XVid,
         VP8, VP9
     ],
     CHROMA => [
-        Yuyv422, Uyvy422, Nv12, Nv21, Yv12
+        Ayuv444, Yuyv422, Uyvy422, Yvyu422, Yv12, Nv12, Nv21, I420, P010, Yvu9
     ],
     LUMA => [
-        Luma8, Luma16
+        Luma8, Luma16, Y10, Y12
+    ],
+    DEPTH => [
+        Depth16
     ],
     RGB => [
-        Rgb332, RgbA8888
+        Rgb332, Rgb555, Rgb565, Rgb888, Bgr888, BgrA8888, RgbA8888, ARgb8888
+    ],
+    BAYER => [
+        Bayer8, Bayer16
     ],
     COLOR_FORMATS => [
         H265, H264, H263, Av1, Avc1, Mpeg1, Mpeg2, Mpeg4, MJpeg, XVid,
-        VP8, VP9, Yuyv422, Uyvy422, Nv12, Nv21, Yv12, Rgb332, RgbA8888
+        VP8, VP9, Ayuv444, Yuyv422, Uyvy422, Yvyu422, Yv12, Nv12, Nv21,
+        I420, P010, Yvu9, Rgb332, Rgb555, Rgb565, Rgb888, Bgr888, BgrA8888,
+        RgbA8888, ARgb8888
     ],
     GRAYSCALE => [
-        Luma8, Luma16
+        Luma8, Luma16, Y10, Y12
     ]
 }
 
+// The FourCCs of the known formats, shorter codes are padded with spaces. Backends map formats through this table
+// (see `FrameFormat::fourcc`), so it is the one place a new format's code has to be added.
+const FOURCCS: &[(FrameFormat, &[u8; 4])] = &[
+    (FrameFormat::H265, b"HEVC"),
+    (FrameFormat::H264, b"H264"),
+    (FrameFormat::Avc1, b"AVC1"),
+    (FrameFormat::H263, b"H263"),
+    (FrameFormat::Av1, b"AV1F"),
+    (FrameFormat::Mpeg1, b"MPG1"),
+    (FrameFormat::Mpeg2, b"MPG2"),
+    (FrameFormat::Mpeg4, b"MPG4"),
+    (FrameFormat::MJpeg, b"MJPG"),
+    (FrameFormat::XVid, b"XVID"),
+    (FrameFormat::VP8, b"VP80"),
+    (FrameFormat::VP9, b"VP90"),
+    (FrameFormat::Ayuv444, b"AYUV"),
+    (FrameFormat::Yuyv422, b"YUYV"),
+    (FrameFormat::Uyvy422, b"UYVY"),
+    (FrameFormat::Yvyu422, b"YVYU"),
+    (FrameFormat::Yv12, b"YV12"),
+    (FrameFormat::Nv12, b"NV12"),
+    (FrameFormat::Nv21, b"NV21"),
+    (FrameFormat::I420, b"YU12"),
+    (FrameFormat::P010, b"P010"),
+    (FrameFormat::Yvu9, b"YVU9"),
+    (FrameFormat::Luma8, b"GREY"),
+    (FrameFormat::Luma16, b"Y16 "),
+    (FrameFormat::Y10, b"Y10 "),
+    (FrameFormat::Y12, b"Y12 "),
+    (FrameFormat::Depth16, b"Z16 "),
+    (FrameFormat::Rgb332, b"RGB1"),
+    (FrameFormat::Rgb555, b"RGBO"),
+    (FrameFormat::Rgb565, b"RGBP"),
+    (FrameFormat::Rgb888, b"RGB3"),
+    (FrameFormat::Bgr888, b"BGR3"),
+    (FrameFormat::BgrA8888, b"RA24"),
+    (FrameFormat::RgbA8888, b"AB24"),
+    (FrameFormat::ARgb8888, b"BA24"),
+    (FrameFormat::Bayer8, b"BA81"),
+    (FrameFormat::Bayer16, b"BYR2"),
+];
+
+impl FrameFormat {
+    /// The format with the `FourCC` `code`, where codes shorter than 4 characters are padded with spaces
+    /// (e.g. `*b"Y16 "` is [`FrameFormat::Luma16`]).
+    ///
+    /// Unknown codes become a [`FrameFormat::Custom`] with the code in bytes 0-3 and bytes 4-7 zeroed.
+    #[must_use]
+    pub fn from_fourcc(code: [u8; 4]) -> FrameFormat {
+        match FOURCCS.iter().find(|(_, known)| **known == code) {
+            Some((format, _)) => *format,
+            None => FrameFormat::Custom([code[0], code[1], code[2], code[3], 0, 0, 0, 0]),
+        }
+    }
+
+    /// The raw `FourCC` of this format, padding included, i.e. the inverse of [`FrameFormat::from_fourcc`].
+    ///
+    /// Returns `None` for a [`FrameFormat::Custom`] with any of bytes 4-7 set, which doesn't fit a `FourCC`.
+    #[must_use]
+    pub fn fourcc(&self) -> Option<[u8; 4]> {
+        match self {
+            FrameFormat::Custom(custom) => {
+                (custom[4..] == [0; 4]).then(|| [custom[0], custom[1], custom[2], custom[3]])
+            }
+            known => FOURCCS.iter().find(|(format, _)| format == known).map(|(_, code)| **code),
+        }
+    }
+
+    /// Parses a `FourCC` such as `"MJPG"`. Known codes map to their format, anything else becomes a
+    /// [`FrameFormat::Custom`] with the code in bytes 0-3 and bytes 4-7 zeroed.
+    ///
+    /// Codes shorter than 4 characters are matched as if padded with spaces, e.g. `"Y16"` is [`FrameFormat::Luma16`].
+    /// # Errors
+    /// If `fourcc` is empty, longer than 4 bytes, or not ASCII, this will error.
+    pub fn from_fourcc_str(fourcc: &str) -> Result<FrameFormat, NokhwaError> {
+        if fourcc.is_empty() || fourcc.len() > 4 || !fourcc.is_ascii() {
+            return Err(NokhwaError::ConversionError(format!("Invalid FourCC {fourcc:?}: must be 1 to 4 ASCII characters")));
+        }
+
+        let mut padded = [b' '; 4];
+        padded[..fourcc.len()].copy_from_slice(fourcc.as_bytes());
+        if let FrameFormat::Custom(_) = FrameFormat::from_fourcc(padded) {
+            // keep an unknown code as given, without the padding.
+            let mut custom = [0_u8; 8];
+            custom[..fourcc.len()].copy_from_slice(fourcc.as_bytes());
+            return Ok(FrameFormat::Custom(custom));
+        }
+        Ok(FrameFormat::from_fourcc(padded))
+    }
+
+    /// The `FourCC` of this format, e.g. `"MJPG"`, with any trailing padding removed.
+    ///
+    /// Returns `None` for a [`FrameFormat::Custom`] that isn't an ASCII `FourCC` with bytes 4-7 zeroed.
+    #[must_use]
+    pub fn fourcc_str(&self) -> Option<String> {
+        let code = self.fourcc()?;
+        let code = std::str::from_utf8(&code).ok().filter(|code| code.is_ascii())?;
+        let code = code.trim_end_matches(['\0', ' ']);
+        (!code.is_empty()).then(|| code.to_string())
+    }
+
+    /// Whether every pixel of this format is a single 16-bit sample, i.e. [`FrameFormat::Luma16`],
+    /// [`FrameFormat::Y10`], [`FrameFormat::Y12`], [`FrameFormat::Depth16`] and [`FrameFormat::Bayer16`].
+    ///
+    /// The byte order of those samples depends on the backend, see [`crate::frame_buffer::FrameBuffer::luma16_samples`].
+    #[must_use]
+    pub fn is_16bit(&self) -> bool {
+        matches!(self, FrameFormat::Luma16 | FrameFormat::Y10 | FrameFormat::Y12 | FrameFormat::Depth16 | FrameFormat::Bayer16)
+    }
+
+    /// The average number of bits each pixel takes up, for uncompressed formats.
+    ///
+    /// Subsampled formats report their average, e.g. 12 for [`FrameFormat::Nv12`].
+    /// Compressed and [`FrameFormat::Custom`] formats return `None`.
+    #[must_use]
+    pub fn bits_per_pixel(&self) -> Option<u32> {
+        let bpp = match self {
+            FrameFormat::Yvu9 => 9,
+            FrameFormat::Luma8 | FrameFormat::Rgb332 | FrameFormat::Bayer8 => 8,
+            FrameFormat::Yv12 | FrameFormat::Nv12 | FrameFormat::Nv21 | FrameFormat::I420 => 12,
+            FrameFormat::Yuyv422
+            | FrameFormat::Uyvy422
+            | FrameFormat::Yvyu422
+            | FrameFormat::Luma16
+            | FrameFormat::Y10
+            | FrameFormat::Y12
+            | FrameFormat::Depth16
+            | FrameFormat::Rgb555
+            | FrameFormat::Rgb565
+            | FrameFormat::Bayer16 => 16,
+            FrameFormat::Rgb888 | FrameFormat::Bgr888 | FrameFormat::P010 => 24,
+            FrameFormat::Ayuv444
+            | FrameFormat::BgrA8888
+            | FrameFormat::RgbA8888
+            | FrameFormat::ARgb8888 => 32,
+            _ => return None,
+        };
+        Some(bpp)
+    }
+
+    /// The size in bytes an uncompressed buffer of this format at `resolution` should be.
+    ///
+    /// This accounts for subsampling: chroma planes of odd-sized 4:2:0 images are rounded up, as are
+    /// macropixels of odd-width 4:2:2 images.
+    /// Compressed and [`FrameFormat::Custom`] formats return `None`.
+    #[must_use]
+    pub fn expected_buffer_size(&self, resolution: Resolution) -> Option<usize> {
+        let width = resolution.width() as usize;
+        let height = resolution.height() as usize;
+
+        let size = match self {
+            FrameFormat::Yv12 | FrameFormat::Nv12 | FrameFormat::Nv21 | FrameFormat::I420 => {
+                width * height + 2 * (width.div_ceil(2) * height.div_ceil(2))
+            }
+            FrameFormat::P010 => 2 * (width * height + 2 * (width.div_ceil(2) * height.div_ceil(2))),
+            FrameFormat::Yvu9 => width * height + 2 * (width.div_ceil(4) * height.div_ceil(4)),
+            FrameFormat::Yuyv422 | FrameFormat::Uyvy422 | FrameFormat::Yvyu422 => {
+                width.div_ceil(2) * 4 * height
+            }
+            other => width * height * (other.bits_per_pixel()? as usize / 8),
+        };
+        Some(size)
+    }
+}
+
 impl Display for FrameFormat {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -158,3 +343,98 @@ macro_rules! define_back_and_fourth_frame_format {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn groups_cover_every_mapped_format() {
+        let all = FrameFormat::ALL.iter().copied().collect::<HashSet<_>>();
+        assert_eq!(all.len(), FrameFormat::ALL.len());
+        // backends map formats through `FOURCCS`, so that is every format there is.
+        assert_eq!(all, FOURCCS.iter().map(|(format, _)| *format).collect());
+
+        let groups = [
+            FrameFormat::COMPRESSED,
+            FrameFormat::CHROMA,
+            FrameFormat::LUMA,
+            FrameFormat::DEPTH,
+            FrameFormat::RGB,
+            FrameFormat::BAYER,
+        ];
+        // every format is in exactly one of them.
+        assert_eq!(groups.iter().map(|group| group.len()).sum::<usize>(), all.len());
+        assert_eq!(groups.concat().into_iter().collect::<HashSet<_>>(), all);
+
+        let color = FrameFormat::COLOR_FORMATS.iter().copied().collect::<HashSet<_>>();
+        let expected_color = [FrameFormat::COMPRESSED, FrameFormat::CHROMA, FrameFormat::RGB].concat();
+        assert_eq!(color, expected_color.into_iter().collect());
+        assert_eq!(FrameFormat::GRAYSCALE, FrameFormat::LUMA);
+    }
+
+
+    #[test]
+    fn every_fourcc_round_trips() {
+        for (format, code) in FOURCCS {
+            assert_eq!(FrameFormat::from_fourcc(**code), *format, "{code:?}");
+            assert_eq!(format.fourcc(), Some(**code), "{format}");
+        }
+        assert_eq!(FrameFormat::from_fourcc(*b"RGBP"), FrameFormat::Rgb565);
+        assert_eq!(FrameFormat::from_fourcc(*b"RGBO"), FrameFormat::Rgb555);
+        // codes are unique, or the lookup would pick one of them.
+        assert_eq!(FOURCCS.iter().map(|(_, code)| code).collect::<HashSet<_>>().len(), FOURCCS.len());
+    }
+
+
+    #[test]
+    fn fourcc_strings_parse_and_print() {
+        assert_eq!(FrameFormat::from_fourcc_str("MJPG").unwrap(), FrameFormat::MJpeg);
+        assert_eq!(FrameFormat::MJpeg.fourcc_str().as_deref(), Some("MJPG"));
+        // padded codes can be given without their padding, and are printed without it.
+        assert_eq!(FrameFormat::from_fourcc_str("Y16").unwrap(), FrameFormat::Luma16);
+        assert_eq!(FrameFormat::Luma16.fourcc_str().as_deref(), Some("Y16"));
+
+        let unknown = FrameFormat::from_fourcc_str("ABCD").unwrap();
+        assert_eq!(unknown, FrameFormat::Custom(*b"ABCD\0\0\0\0"));
+        assert_eq!(unknown.fourcc_str().as_deref(), Some("ABCD"));
+
+        for invalid in ["", "MJPEG", "ÄB"] {
+            assert!(matches!(FrameFormat::from_fourcc_str(invalid), Err(NokhwaError::ConversionError(_))), "{invalid:?}");
+        }
+        assert_eq!(FrameFormat::Custom(*b"TOOLONG!").fourcc_str(), None);
+    }
+
+
+    #[test]
+    fn high_bit_depth_formats_round_trip() {
+        for (format, code) in [(FrameFormat::P010, "P010"), (FrameFormat::Y10, "Y10"), (FrameFormat::Y12, "Y12")] {
+            assert_eq!(FrameFormat::from_fourcc_str(code).unwrap(), format);
+            assert_eq!(format.fourcc_str().as_deref(), Some(code));
+        }
+        assert!(FrameFormat::Y10.is_16bit() && FrameFormat::Y12.is_16bit());
+        // P010 is semi-planar, not one sample per pixel.
+        assert!(!FrameFormat::P010.is_16bit());
+        assert_eq!(FrameFormat::P010.expected_buffer_size(Resolution::new(640, 480)), Some(921_600));
+        assert_eq!(FrameFormat::Y10.expected_buffer_size(Resolution::new(640, 480)), Some(614_400));
+    }
+
+
+    #[test]
+    fn expected_buffer_sizes_account_for_subsampling() {
+        let vga = Resolution::new(640, 480);
+        assert_eq!(FrameFormat::Nv12.bits_per_pixel(), Some(12));
+        assert_eq!(FrameFormat::Nv12.expected_buffer_size(vga), Some(460_800));
+        assert_eq!(FrameFormat::Yuyv422.expected_buffer_size(vga), Some(614_400));
+        assert_eq!(FrameFormat::Rgb888.expected_buffer_size(vga), Some(921_600));
+
+        // odd sizes round the chroma up.
+        assert_eq!(FrameFormat::I420.expected_buffer_size(Resolution::new(3, 3)), Some(9 + 2 * 4));
+        assert_eq!(FrameFormat::Yuyv422.expected_buffer_size(Resolution::new(3, 1)), Some(8));
+
+        assert_eq!(FrameFormat::MJpeg.bits_per_pixel(), None);
+        assert_eq!(FrameFormat::MJpeg.expected_buffer_size(vga), None);
+        assert_eq!(FrameFormat::Custom(*b"ABCD\0\0\0\0").expected_buffer_size(vga), None);
+    }
+}
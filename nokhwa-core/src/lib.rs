@@ -20,6 +20,7 @@
 
 //! Core type definitions for `nokhwa`
 pub mod camera;
+pub mod conversion;
 pub mod decoder;
 pub mod error;
 pub mod format_request;
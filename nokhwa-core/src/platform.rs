@@ -1,7 +1,10 @@
-use crate::camera::{AsyncCamera, Camera};
-use crate::error::NokhwaResult;
-use crate::types::{CameraIndex, CameraInformation};
+#[cfg(feature = "async")]
+use crate::camera::AsyncCamera;
+use crate::camera::{Camera, Setting};
+use crate::error::{NokhwaError, NokhwaResult};
+use crate::types::{CameraFormat, CameraIndex, CameraInformation};
 use std::fmt::{Display, Formatter};
+use std::sync::{Mutex, PoisonError};
 
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Backends {
@@ -19,27 +22,188 @@ impl Display for Backends {
     }
 }
 
+/// Where the user stands on letting the application use cameras.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// The user hasn't been asked yet, see [`PlatformTrait::block_on_permission`].
+    NotDetermined,
+    /// Camera access is blocked by something the user can't change, e.g. parental controls or device policy.
+    Restricted,
+}
+
+impl Display for PermissionStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 pub trait PlatformTrait {
     const PLATFORM: Backends;
     type Camera: Camera;
 
     fn block_on_permission(&mut self) -> NokhwaResult<()>;
 
-    fn check_permission_given(&mut self) -> bool;
+    /// Whether [`PlatformTrait::permission_status`] is [`PermissionStatus::Granted`].
+    #[deprecated(note = "use `permission_status`, which can tell denied and not yet asked apart")]
+    fn check_permission_given(&mut self) -> bool {
+        self.permission_status() == PermissionStatus::Granted
+    }
+
+    /// The current permission state.
+    fn permission_status(&mut self) -> PermissionStatus;
 
     fn query(&mut self) -> NokhwaResult<Vec<CameraInformation>>;
 
     fn open(&mut self, index: CameraIndex) -> NokhwaResult<Self::Camera>;
 
+    /// Quickly checks whether the device at `index` is a camera that can actually capture.
+    ///
+    /// Backends should override this with something cheaper than a full open. The default
+    /// implementation opens the camera and checks that it has at least one format.
+    /// # Errors
+    /// If the device can't be probed at all, this will error. A device that isn't a usable camera is `Ok(false)`.
+    fn probe(&mut self, index: &CameraIndex) -> NokhwaResult<bool> {
+        match self.open(index.clone()) {
+            Ok(camera) => Ok(camera.enumerate_formats().is_ok_and(|formats| !formats.is_empty())),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Every camera from [`PlatformTrait::query`] together with its formats, e.g. for a device picker.
+    ///
+    /// A camera whose formats can't be read is still listed, with no formats. The default implementation opens
+    /// each camera in turn, so a camera that hangs while opening hangs the whole query; backends should override
+    /// this with something that gives up on such devices.
+    /// # Errors
+    /// If querying the cameras fails, this will error.
+    fn query_with_formats(&mut self) -> NokhwaResult<Vec<(CameraInformation, Vec<CameraFormat>)>> {
+        let cameras = self.query()?;
+        Ok(cameras.into_iter().map(|information| {
+            let formats = self.open(information.index().clone()).and_then(|camera| camera.enumerate_formats()).unwrap_or_default();
+            (information, formats)
+        }).collect())
+    }
+
+    /// [`PlatformTrait::open`], boxed as a [`Camera`] trait object.
+    /// # Errors
+    /// If the camera can't be opened, this will error.
+    fn open_dynamic(&mut self, index: CameraIndex) -> NokhwaResult<Box<dyn Camera>> where Self::Camera: 'static {
+        self.open(index).map(|cam| Box::new(cam) as Box<dyn Camera>)
+    }
+}
+
+/// An object-safe version of [`PlatformTrait`], so backends can be stored and picked at runtime.
+///
+/// This is implemented for every [`PlatformTrait`] whose camera is `'static`.
+pub trait DynPlatform {
+    #[must_use]
+    fn platform(&self) -> Backends;
+
+    /// See [`PlatformTrait::block_on_permission`].
+    /// # Errors
+    /// If permission is denied or can't be requested, this will error.
+    fn block_on_permission(&mut self) -> NokhwaResult<()>;
+
+    fn permission_status(&mut self) -> PermissionStatus;
+
+    /// See [`PlatformTrait::query`].
+    /// # Errors
+    /// If the cameras can't be listed, this will error.
+    fn query(&mut self) -> NokhwaResult<Vec<CameraInformation>>;
+
+    /// See [`PlatformTrait::query_with_formats`].
+    /// # Errors
+    /// If the cameras can't be listed, this will error.
+    fn query_with_formats(&mut self) -> NokhwaResult<Vec<(CameraInformation, Vec<CameraFormat>)>>;
+
+    /// See [`PlatformTrait::open_dynamic`].
+    /// # Errors
+    /// If the camera can't be opened, this will error.
+    fn open_dynamic(&mut self, index: CameraIndex) -> NokhwaResult<Box<dyn Camera>>;
+}
+
+impl<T> DynPlatform for T where T: PlatformTrait, T::Camera: 'static {
+    fn platform(&self) -> Backends {
+        T::PLATFORM
+    }
+
+    fn block_on_permission(&mut self) -> NokhwaResult<()> {
+        PlatformTrait::block_on_permission(self)
+    }
+
+    fn permission_status(&mut self) -> PermissionStatus {
+        PlatformTrait::permission_status(self)
+    }
+
+    fn query(&mut self) -> NokhwaResult<Vec<CameraInformation>> {
+        PlatformTrait::query(self)
+    }
+
+    fn query_with_formats(&mut self) -> NokhwaResult<Vec<(CameraInformation, Vec<CameraFormat>)>> {
+        PlatformTrait::query_with_formats(self)
+    }
+
     fn open_dynamic(&mut self, index: CameraIndex) -> NokhwaResult<Box<dyn Camera>> {
-        self.open(index).map(|cam| Box::new(cam))
+        PlatformTrait::open_dynamic(self, index)
+    }
+}
+
+/// Creates a fresh instance of a registered backend.
+pub type BackendFactory = Box<dyn FnMut() -> Box<dyn DynPlatform> + Send>;
+
+static BACKENDS: Mutex<Vec<(Backends, BackendFactory)>> = Mutex::new(Vec::new());
+
+/// Registers a backend, e.g. a [`Backends::Custom`] one from another crate, to be used by [`open_any`].
+///
+/// Backends are tried in the order they were registered. Registering the same [`Backends`] again replaces
+/// its factory but keeps its place.
+pub fn register_backend(backend: Backends, factory: BackendFactory) {
+    let mut backends = BACKENDS.lock().unwrap_or_else(PoisonError::into_inner);
+    match backends.iter_mut().find(|(registered, _)| *registered == backend) {
+        Some((_, registered_factory)) => *registered_factory = factory,
+        None => backends.push((backend, factory)),
+    }
+}
+
+/// Removes a backend registered with [`register_backend`]. Returns `false` if it was never registered.
+pub fn unregister_backend(backend: Backends) -> bool {
+    let mut backends = BACKENDS.lock().unwrap_or_else(PoisonError::into_inner);
+    let before = backends.len();
+    backends.retain(|(registered, _)| *registered != backend);
+    backends.len() != before
+}
+
+/// The backends registered with [`register_backend`], in the order they are tried.
+#[must_use]
+pub fn registered_backends() -> Vec<Backends> {
+    BACKENDS.lock().unwrap_or_else(PoisonError::into_inner).iter().map(|(backend, _)| *backend).collect()
+}
+
+/// Opens the camera at `index` with the first registered backend that manages to.
+/// # Errors
+/// If no backends are registered or none of them could open the camera, this will error with the last
+/// backend's error.
+///
+/// The registry is locked while the backends run, so factories must not call [`register_backend`].
+pub fn open_any(index: &CameraIndex) -> NokhwaResult<Box<dyn Camera>> {
+    let mut backends = BACKENDS.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut last_error = NokhwaError::OpenDeviceError(index.to_string(), "No backends registered".to_string(), None);
+
+    for (_, factory) in backends.iter_mut() {
+        match factory().open_dynamic(index.clone()) {
+            Ok(camera) => return Ok(camera),
+            Err(why) => last_error = why,
+        }
     }
+    Err(last_error)
 }
 
 #[cfg(feature = "async")]
 pub trait AsyncPlatformTrait {
     const PLATFORM: Backends;
-    type AsyncCamera: AsyncCamera;
+    type AsyncCamera: AsyncCamera + 'static;
 
     async fn await_permission(&mut self) -> NokhwaResult<()>;
 
@@ -48,6 +212,173 @@ pub trait AsyncPlatformTrait {
     async fn open_async(&mut self, index: &CameraIndex) -> NokhwaResult<Self::AsyncCamera>;
 
     async fn open_dynamic_async(&mut self, index: &CameraIndex) -> NokhwaResult<Box<dyn Camera>> {
-        self.open_async(index).await.map(|cam| Box::new(cam))
+        self.open_async(index).await.map(|cam| Box::new(cam) as Box<dyn Camera>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Capture;
+    use crate::control::{ControlDescription, ControlId, ControlValue, Controls};
+    use crate::frame_format::FrameFormat;
+    use crate::stream::{StreamConfiguration, StreamHandle};
+    use crate::types::{FrameRate, Resolution};
+    use std::collections::hash_map::{Keys, Values};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    // an in-memory camera that only knows its formats.
+    struct FakeCamera {
+        formats: Vec<CameraFormat>,
+        controls: Controls,
+    }
+
+    impl Setting for FakeCamera {
+        fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
+            Ok(self.formats.clone())
+        }
+
+        fn enumerate_resolution_and_frame_rates(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
+            let mut frame_rates = HashMap::<Resolution, Vec<FrameRate>>::new();
+            for format in self.formats.iter().filter(|format| *format.format() == frame_format) {
+                frame_rates.entry(*format.resolution()).or_default().push(*format.frame_rate());
+            }
+            Ok(frame_rates)
+        }
+
+        fn set_format(&mut self, _: CameraFormat) -> Result<(), NokhwaError> {
+            Ok(())
+        }
+
+        fn current_format(&self) -> Option<CameraFormat> {
+            self.formats.first().copied()
+        }
+
+        fn control_ids(&self) -> Keys<'_, ControlId, ControlDescription> {
+            self.controls.ids()
+        }
+
+        fn control_descriptions(&self) -> Values<'_, ControlId, ControlDescription> {
+            self.controls.descriptions()
+        }
+
+        fn control_values(&self) -> Values<'_, ControlId, ControlValue> {
+            self.controls.values()
+        }
+
+        fn control_value(&self, id: &ControlId) -> Option<&ControlValue> {
+            self.controls.value(id)
+        }
+
+        fn control_description(&self, id: &ControlId) -> Option<&ControlDescription> {
+            self.controls.description(id)
+        }
+
+        fn set_control(&mut self, property: &ControlId, value: ControlValue) -> Result<(), NokhwaError> {
+            self.controls.set_control_value(property, value)
+        }
+
+        fn refresh_controls(&mut self) -> Result<(), NokhwaError> {
+            Ok(())
+        }
+    }
+
+    impl Capture for FakeCamera {
+        fn open_stream_with(&mut self, _: StreamConfiguration) -> Result<Arc<StreamHandle>, NokhwaError> {
+            Err(NokhwaError::OpenStreamError("Fake cameras don't stream".to_string()))
+        }
+
+        fn close_stream(&mut self) -> Result<(), NokhwaError> {
+            Ok(())
+        }
+
+        fn stream_handle(&self) -> Option<Arc<StreamHandle>> {
+            None
+        }
+    }
+
+    impl Camera for FakeCamera {}
+
+    // a backend with two cameras, `0` with two formats and `1` with one.
+    struct FakePlatform;
+
+    impl FakePlatform {
+        fn formats(index: &CameraIndex) -> Option<Vec<CameraFormat>> {
+            let format = |width, height| CameraFormat::new(Resolution::new(width, height), FrameFormat::MJpeg, FrameRate::frame_rate(30));
+            match index {
+                CameraIndex::Index(0) => Some(vec![format(1280, 720), format(640, 480)]),
+                CameraIndex::Index(1) => Some(vec![format(1920, 1080)]),
+                _ => None,
+            }
+        }
+    }
+
+    impl PlatformTrait for FakePlatform {
+        const PLATFORM: Backends = Backends::Custom("fake");
+        type Camera = FakeCamera;
+
+        fn block_on_permission(&mut self) -> NokhwaResult<()> {
+            Ok(())
+        }
+
+        fn permission_status(&mut self) -> PermissionStatus {
+            PermissionStatus::Granted
+        }
+
+        fn query(&mut self) -> NokhwaResult<Vec<CameraInformation>> {
+            Ok((0..2).map(|index| {
+                CameraInformation::new(format!("Fake {index}"), "A fake camera".to_string(), String::new(), CameraIndex::Index(index))
+            }).collect())
+        }
+
+        fn open(&mut self, index: CameraIndex) -> NokhwaResult<Self::Camera> {
+            let formats = Self::formats(&index).ok_or_else(|| {
+                NokhwaError::OpenDeviceError(index.to_string(), "No such fake camera".to_string(), None)
+            })?;
+            Ok(FakeCamera { formats, controls: Controls::empty() })
+        }
+    }
+
+    #[test]
+    fn open_any_goes_through_registered_backends() {
+        let backend = Backends::Custom("fake-registry");
+        let other = Backends::Custom("fake-registry-other");
+        register_backend(backend, Box::new(|| Box::new(FakePlatform)));
+        register_backend(other, Box::new(|| Box::new(FakePlatform)));
+        // registering again keeps the place.
+        register_backend(backend, Box::new(|| Box::new(FakePlatform)));
+        let registered = registered_backends();
+        let position = |backend| registered.iter().position(|registered| *registered == backend);
+        assert!(position(backend) < position(other));
+
+        let camera = open_any(&CameraIndex::Index(1)).unwrap();
+        assert_eq!(camera.enumerate_formats().unwrap().len(), 1);
+        assert!(open_any(&CameraIndex::Index(7)).is_err());
+
+        assert!(unregister_backend(backend));
+        assert!(unregister_backend(other));
+        assert!(!unregister_backend(backend));
+    }
+
+    #[test]
+    fn dyn_platform_reports_its_backend() {
+        let mut platform: Box<dyn DynPlatform> = Box::new(FakePlatform);
+
+        assert_eq!(platform.platform(), Backends::Custom("fake"));
+        assert_eq!(platform.permission_status(), PermissionStatus::Granted);
+        assert_eq!(platform.query().unwrap()[0].human_name(), "Fake 0");
+    }
+
+    #[test]
+    fn query_with_formats_lists_every_camera_with_its_formats() {
+        let cameras = PlatformTrait::query_with_formats(&mut FakePlatform).unwrap();
+
+        assert_eq!(cameras.len(), 2);
+        for (information, formats) in &cameras {
+            assert_eq!(Some(formats.clone()), FakePlatform::formats(information.index()));
+        }
+        assert_eq!(cameras[0].1.len(), 2);
+        assert_eq!(*cameras[1].1[0].resolution(), Resolution::new(1920, 1080));
     }
 }
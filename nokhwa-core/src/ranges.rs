@@ -1,6 +1,6 @@
 use core::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
-use std::ops::{Div, Rem, Sub};
+use std::ops::{Add, Div, Rem, Sub};
 use ordered_float::OrderedFloat;
 
 /// A range type that can be validated.
@@ -14,33 +14,60 @@ pub trait ValidatableRange {
 
 /// Creates a range of values.
 ///
-/// Inclusive by default.
+/// Inclusive by default. If a `step` is set, only `minimum, minimum + step, minimum + 2 * step, ...`
+/// are valid values.
+///
+/// Either bound may be missing (`None`), in which case the range is open-ended on that side
+/// (i.e. that bound is -∞ or ∞).
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range<T> where T: RangeItem
 {
-    minimum: T,
+    minimum: Option<T>,
     lower_inclusive: bool,
-    maximum: T,
+    maximum: Option<T>,
     upper_inclusive: bool,
     step: Option<T>,
 }
 
-impl<T> Range<T> where T: Copy {
+impl<T> Range<T> where T: RangeItem {
     /// Create an upper and lower inclusive [`Range`]
     pub fn new(min: T, max: T, step: Option<T>) -> Self {
         Self {
-            minimum: min,
+            minimum: Some(min),
             lower_inclusive: true,
-            maximum: max,
+            maximum: Some(max),
             upper_inclusive: true,
             step,
         }
     }
 
+    /// Create an inclusive [`Range`] with no upper bound.
+    pub fn at_least(min: T) -> Self {
+        Self {
+            minimum: Some(min),
+            lower_inclusive: true,
+            maximum: None,
+            upper_inclusive: true,
+            step: None,
+        }
+    }
+
+    /// Create an inclusive [`Range`] with no lower bound.
+    pub fn at_most(max: T) -> Self {
+        Self {
+            minimum: None,
+            lower_inclusive: true,
+            maximum: Some(max),
+            upper_inclusive: true,
+            step: None,
+        }
+    }
+
     pub fn with_inclusive(
-        min: T,
+        min: Option<T>,
         lower_inclusive: bool,
-        max: T,
+        max: Option<T>,
         upper_inclusive: bool,
         step: Option<T>
     ) -> Self {
@@ -68,13 +95,13 @@ impl<T> Range<T> where T: Copy {
     pub fn set_step(&mut self, step: T) {
         self.step = Some(step);
     }
-    pub fn minimum(&self) -> T {
+    pub fn minimum(&self) -> Option<T> {
         self.minimum
     }
     pub fn lower_inclusive(&self) -> bool {
         self.lower_inclusive
     }
-    pub fn maximum(&self) -> T {
+    pub fn maximum(&self) -> Option<T> {
         self.maximum
     }
     pub fn upper_inclusive(&self) -> bool {
@@ -83,6 +110,120 @@ impl<T> Range<T> where T: Copy {
     pub fn step(&self) -> Option<T> {
         self.step
     }
+
+    /// The value a request over this range aims for: the maximum, or the minimum if the range has no upper bound.
+    ///
+    /// E.g. `15..=30` fps prefers 30 fps, while "at least 1280x720" prefers 1280x720.
+    pub fn preferred(&self) -> Option<T> {
+        self.maximum.or(self.minimum)
+    }
+
+    /// Whether every value of `other` is also a value of this range, respecting both inclusivity flags.
+    ///
+    /// If this range has a step, `other` has to step along this range's grid (by a multiple of the step).
+    pub fn contains(&self, other: &Range<T>) -> bool {
+        let lower = match (self.minimum, other.minimum) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(minimum), Some(other_minimum)) => {
+                other_minimum > minimum || (other_minimum == minimum && (self.lower_inclusive || !other.lower_inclusive))
+            }
+        };
+        let upper = match (self.maximum, other.maximum) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(maximum), Some(other_maximum)) => {
+                other_maximum < maximum || (other_maximum == maximum && (self.upper_inclusive || !other.upper_inclusive))
+            }
+        };
+        if !(lower && upper) {
+            return false
+        }
+
+        match self.step {
+            Some(step) if step != T::ZERO => {
+                let on_grid = other.minimum.is_some_and(|other_minimum| self.validate(&other_minimum));
+                let same_grid = other.step.is_some_and(|other_step| other_step != T::ZERO && other_step % step == T::ZERO);
+                on_grid && same_grid
+            }
+            _ => true,
+        }
+    }
+
+    /// The values both this range and `other` share, or `None` if there are none.
+    ///
+    /// The step is only kept if both ranges have the same step on the same grid, otherwise it is dropped.
+    pub fn intersection(&self, other: &Range<T>) -> Option<Range<T>> {
+        // the higher minimum wins, on a tie it is only included if both include it.
+        let (minimum, lower_inclusive) = match (self.minimum, other.minimum) {
+            (None, None) => (None, true),
+            (Some(minimum), None) => (Some(minimum), self.lower_inclusive),
+            (None, Some(minimum)) => (Some(minimum), other.lower_inclusive),
+            (Some(a), Some(b)) => match a.cmp(&b) {
+                std::cmp::Ordering::Greater => (Some(a), self.lower_inclusive),
+                std::cmp::Ordering::Less => (Some(b), other.lower_inclusive),
+                std::cmp::Ordering::Equal => (Some(a), self.lower_inclusive && other.lower_inclusive),
+            },
+        };
+        let (maximum, upper_inclusive) = match (self.maximum, other.maximum) {
+            (None, None) => (None, true),
+            (Some(maximum), None) => (Some(maximum), self.upper_inclusive),
+            (None, Some(maximum)) => (Some(maximum), other.upper_inclusive),
+            (Some(a), Some(b)) => match a.cmp(&b) {
+                std::cmp::Ordering::Less => (Some(a), self.upper_inclusive),
+                std::cmp::Ordering::Greater => (Some(b), other.upper_inclusive),
+                std::cmp::Ordering::Equal => (Some(a), self.upper_inclusive && other.upper_inclusive),
+            },
+        };
+
+        let empty = matches!((minimum, maximum), (Some(minimum), Some(maximum))
+            if minimum > maximum || (minimum == maximum && !(lower_inclusive && upper_inclusive)));
+        if empty {
+            return None
+        }
+
+        let step = match (self.step, other.step) {
+            (Some(a), Some(b)) if a == b && a != T::ZERO => {
+                let (a_anchor, b_anchor) = (self.minimum.unwrap_or(T::ZERO), other.minimum.unwrap_or(T::ZERO));
+                // subtract the smaller anchor, so unsigned types can't underflow.
+                let offset = if a_anchor >= b_anchor { a_anchor - b_anchor } else { b_anchor - a_anchor };
+                (offset % a == T::ZERO).then_some(a)
+            }
+            _ => None,
+        };
+
+        Some(Range::with_inclusive(minimum, lower_inclusive, maximum, upper_inclusive, step))
+    }
+
+    /// Every legal value of this range, from the minimum up: `minimum, minimum + step, ...`,
+    /// respecting both inclusivity flags.
+    ///
+    /// This is empty if the range has no step, a step that is not positive, or is missing either bound.
+    pub fn iter_steps(&self) -> impl Iterator<Item = T>
+    where
+        T: Add<Output = T>,
+    {
+        let bounds = match (self.minimum, self.maximum, self.step) {
+            (Some(minimum), Some(maximum), Some(step)) if step > T::ZERO && minimum <= maximum => {
+                Some((minimum, maximum, step))
+            }
+            _ => None,
+        };
+        let lower_inclusive = self.lower_inclusive;
+        let upper_inclusive = self.upper_inclusive;
+
+        bounds.into_iter().flat_map(move |(minimum, maximum, step)| {
+            // the `maximum - value >= step` checks keep us from ever stepping (and overflowing) past the maximum.
+            let first = if lower_inclusive {
+                Some(minimum)
+            } else {
+                (maximum - minimum >= step).then(|| minimum + step)
+            };
+
+            std::iter::successors(first, move |value| (maximum - *value >= step).then(|| *value + step))
+                .take_while(move |value| if upper_inclusive { *value <= maximum } else { *value < maximum })
+        })
+    }
 }
 
 impl<T> ValidatableRange for Range<T>
@@ -101,15 +242,24 @@ where
             false => T::lt,
         };
 
-        if !(l_comparison_fn(&self.minimum, value) && u_comparison_fn(&self.maximum, value)) {
+        // a missing bound is infinite, so everything is on the right side of it.
+        let above_minimum = self.minimum.is_none_or(|minimum| l_comparison_fn(value, &minimum));
+        let below_maximum = self.maximum.is_none_or(|maximum| u_comparison_fn(value, &maximum));
+        if !(above_minimum && below_maximum) {
             return false
         }
 
         // check step
-
+        // the grid is anchored at the minimum (`minimum, minimum + step, ...`), matching V4L2.
+        // since `value >= minimum` here, the offset is never negative, so signed ranges with
+        // a negative minimum (e.g. `[-180, 180]` step 5) work as expected.
+        // without a minimum the grid is anchored at zero instead.
         if let Some(step) = self.step {
-            let step_chk_value = *value - self.minimum;
-            return step_chk_value % step == 0;
+            if step == T::ZERO {
+                return true
+            }
+            let step_chk_value = *value - self.minimum.unwrap_or(T::ZERO);
+            return step_chk_value % step == T::ZERO;
         }
 
         return true
@@ -118,13 +268,13 @@ where
 
 impl<T> Default for Range<T>
 where
-    T: Default,
+    T: RangeItem + Default,
 {
     fn default() -> Self {
         Range {
-            minimum: T::default(),
+            minimum: Some(T::default()),
             lower_inclusive: true,
-            maximum: T::default(),
+            maximum: Some(T::default()),
             upper_inclusive: true,
             step: None,
         }
@@ -133,17 +283,20 @@ where
 
 impl<T> Display for Range<T>
 where
-    T: Debug,
+    T: RangeItem,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let lower_inclusive_char = bool_to_inclusive_char(self.lower_inclusive, false);
-        let upper_inclusive_char = bool_to_inclusive_char(self.upper_inclusive, true);
+        // an infinite bound is never included.
+        let lower = match &self.minimum {
+            Some(minimum) => format!("{}{minimum:?}", bool_to_inclusive_char(self.lower_inclusive, false)),
+            None => "(-∞".to_string(),
+        };
+        let upper = match &self.maximum {
+            Some(maximum) => format!("{maximum:?}{}", bool_to_inclusive_char(self.upper_inclusive, true)),
+            None => "∞)".to_string(),
+        };
 
-        write!(
-            f,
-            "Range: {lower_inclusive_char}{:?}, {:?}{upper_inclusive_char}",
-            self.minimum, self.maximum
-        )
+        write!(f, "Range: {lower}, {upper}")
     }
 }
 
@@ -166,18 +319,6 @@ fn bool_to_inclusive_char(inclusive: bool, upper: bool) -> char {
     }
 }
 
-fn default_to_string<T>(default: &Option<T>) -> String
-where
-    T: Debug,
-{
-    match default {
-        Some(v) => {
-            format!("{v:?}")
-        }
-        None => String::from("None"),
-    }
-}
-
 pub trait RangeItem: Copy + Clone + Debug + Div<Output = Self> + Sub<Output = Self> + Rem<Output = Self> + Hash + Ord + PartialOrd + Eq + PartialEq {
     const ZERO: Self;
 }
@@ -198,4 +339,126 @@ impl RangeItem for OrderedFloat<f32> {
 
 impl RangeItem for OrderedFloat<f64> {
     const ZERO: Self = OrderedFloat(0_f64);
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_minimum_anchors_the_step_grid() {
+        let hue = Range::new(-180, 180, Some(5));
+
+        assert!(hue.validate(&-175));
+        assert!(!hue.validate(&-177));
+        // the default of most signed controls.
+        assert!(hue.validate(&0));
+        assert!(hue.validate(&-180));
+        assert!(hue.validate(&180));
+        assert!(!hue.validate(&185));
+    }
+
+    #[test]
+    fn off_grid_minimum_shifts_the_grid() {
+        let range = Range::new(-7, 13, Some(5));
+
+        assert!(range.validate(&-2));
+        assert!(range.validate(&13));
+        assert!(!range.validate(&0));
+    }
+
+    #[test]
+    fn iter_steps_respects_the_upper_bound() {
+        let zoom = Range::new(100, 800, Some(100));
+        assert_eq!(zoom.iter_steps().collect::<Vec<_>>(), vec![100, 200, 300, 400, 500, 600, 700, 800]);
+
+        let exclusive = Range::with_inclusive(Some(100), true, Some(800), false, Some(100));
+        assert_eq!(exclusive.iter_steps().last(), Some(700));
+
+        let lower_exclusive = Range::with_inclusive(Some(100), false, Some(300), true, Some(100));
+        assert_eq!(lower_exclusive.iter_steps().collect::<Vec<_>>(), vec![200, 300]);
+    }
+
+    #[test]
+    fn iter_steps_stops_before_an_uneven_maximum() {
+        let range = Range::new(0, 10, Some(4));
+        assert_eq!(range.iter_steps().collect::<Vec<_>>(), vec![0, 4, 8]);
+    }
+
+    #[test]
+    fn iter_steps_never_overflows() {
+        let range = Range::new(250_u8, 255, Some(3));
+        assert_eq!(range.iter_steps().collect::<Vec<_>>(), vec![250, 253]);
+    }
+
+    #[test]
+    fn iter_steps_is_empty_without_a_step() {
+        assert_eq!(Range::new(0, 10, None).iter_steps().count(), 0);
+        assert_eq!(Range::new(0, 10, Some(0)).iter_steps().count(), 0);
+        assert_eq!(Range::at_least(0).iter_steps().count(), 0);
+    }
+
+
+    #[test]
+    fn contains_respects_bounds_and_the_step_grid() {
+        let zoom = Range::new(100, 800, Some(100));
+
+        // nested.
+        assert!(zoom.contains(&Range::new(200, 400, Some(200))));
+        assert!(zoom.contains(&zoom));
+        // overlapping, then disjoint.
+        assert!(!zoom.contains(&Range::new(700, 900, Some(100))));
+        assert!(!zoom.contains(&Range::new(900, 1000, Some(100))));
+        // nested, but off the grid or without a step.
+        assert!(!zoom.contains(&Range::new(150, 350, Some(100))));
+        assert!(!zoom.contains(&Range::new(200, 400, None)));
+
+        let exclusive = Range::with_inclusive(Some(0), false, Some(10), false, None);
+        assert!(!exclusive.contains(&Range::new(0, 5, None)));
+        assert!(exclusive.contains(&Range::with_inclusive(Some(0), false, Some(5), true, None)));
+        assert!(Range::at_least(0).contains(&Range::at_least(5)));
+        assert!(!Range::new(0, 100, None).contains(&Range::at_least(5)));
+    }
+
+    #[test]
+    fn intersection_of_disjoint_overlapping_and_nested_ranges() {
+        assert_eq!(Range::new(0, 10, None).intersection(&Range::new(20, 30, None)), None);
+        assert_eq!(Range::new(0, 10, None).intersection(&Range::new(5, 30, None)), Some(Range::new(5, 10, None)));
+        assert_eq!(Range::new(0, 30, Some(5)).intersection(&Range::new(10, 20, Some(5))), Some(Range::new(10, 20, Some(5))));
+
+        // ranges touching at a bound only share it if both include it.
+        let below = Range::with_inclusive(Some(0), true, Some(10), false, None);
+        assert_eq!(below.intersection(&Range::new(10, 20, None)), None);
+        assert_eq!(Range::new(0, 10, None).intersection(&Range::new(10, 20, None)), Some(Range::new(10, 10, None)));
+
+        // different grids lose the step, open-ended bounds take the other one.
+        assert_eq!(Range::new(0, 30, Some(5)).intersection(&Range::new(1, 20, Some(5))).unwrap().step(), None);
+        assert_eq!(Range::at_least(5).intersection(&Range::at_most(8)), Some(Range::new(5, 8, None)));
+    }
+
+
+    #[test]
+    fn open_ended_bounds_validate_everything_on_their_side() {
+        let at_least = Range::at_least(1280);
+        assert!(at_least.validate(&1280));
+        assert!(at_least.validate(&u32::MAX));
+        assert!(!at_least.validate(&1279));
+
+        let at_most = Range::at_most(-5);
+        assert!(at_most.validate(&i32::MIN));
+        assert!(!at_most.validate(&-4));
+
+        // without a minimum the step grid is anchored at zero.
+        let stepped = Range::with_inclusive(None, true, Some(100), true, Some(10));
+        assert!(stepped.validate(&-30));
+        assert!(!stepped.validate(&-35));
+    }
+
+    #[test]
+    fn display_shows_open_ended_bounds_as_infinite() {
+        assert_eq!(Range::new(0, 10, Some(2)).to_string(), "Range: [0, 10]");
+        assert_eq!(Range::with_inclusive(Some(0), false, Some(10), false, None).to_string(), "Range: (0, 10)");
+        assert_eq!(Range::at_least(5).to_string(), "Range: [5, ∞)");
+        assert_eq!(Range::at_most(5).to_string(), "Range: (-∞, 5]");
+        assert_eq!(Range::<i32>::with_inclusive(None, true, None, true, None).to_string(), "Range: (-∞, ∞)");
+    }
+}
@@ -1,10 +1,22 @@
-use std::cell::Cell;
-use std::time::Duration;
-use flume::{Receiver, Sender, TryRecvError};
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use flume::{bounded, unbounded, Receiver, RecvTimeoutError, Selector, SendError, Sender, TryRecvError, TrySendError};
 use typed_builder::TypedBuilder;
+use crate::conversion::{can_convert, convert};
 use crate::error::NokhwaError;
 use crate::frame_buffer::FrameBuffer;
-use crate::types::CameraFormat;
+use crate::frame_format::FrameFormat;
+use crate::types::{CameraFormat, FrameRate};
+#[cfg(feature = "async")]
+use std::{future::{poll_fn, Future}, pin::Pin, task::{Context, Poll}};
+#[cfg(feature = "async")]
+use flume::r#async::RecvStream;
+#[cfg(feature = "async")]
+use futures_core::Stream;
+#[cfg(feature = "async")]
+use futures_timer::Delay;
 
 /// What receiving behaviour the stream should observe.
 ///
@@ -28,8 +40,16 @@ pub enum StreamReceiverBehaviour {
 /// This means that streams will be blocked until the stream handle is emptied.
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub enum StreamBounds {
+    /// Hold at most this many events. Once the stream handle falls that far behind, backends either wait for
+    /// it or drop new frames (V4L2 drops them), so memory use stays bounded.
     Bounded(u32),
     Unbounded,
+    /// Only ever hold the newest frame: if the consumer hasn't taken the previous frame yet, it is dropped in
+    /// favour of the new one, so the stream never blocks and never serves stale frames.
+    ///
+    /// Only [`Event::NewFrame`]s are dropped, every other event (e.g. [`Event::FormatChange`] or
+    /// [`Event::Terminating`]) is always delivered.
+    LatestOnly,
 }
 
 impl Default for StreamBounds {
@@ -45,6 +65,111 @@ pub enum ControlFlowOnOther {
     Break,
 }
 
+/// What a backend does once a stream's device stops delivering frames, e.g. because a USB camera briefly dropped off
+/// the bus. By default, the stream terminates.
+///
+/// Before each attempt to reopen the stream the backend sends an [`Event::Other`] of `"reconnecting"`, so consumers
+/// that want to ride the reconnect out should set [`StreamConfiguration::on_other`] to [`ControlFlowOnOther::Continue`].
+/// [`Event::Terminating`] is only sent once every retry failed.
+///
+/// On V4L2 a reconnect opens the device anew, and the camera only switches over to the new device the next time it is
+/// changed (e.g. by [`crate::camera::Setting::set_control`] or [`crate::camera::Setting::refresh_controls`]), until
+/// then queries still go to the lost one. Controls aren't reapplied to the new device.
+#[derive(Clone, Debug, Default, PartialOrd, PartialEq)]
+pub enum ReconnectPolicy {
+    /// Terminate the stream.
+    #[default]
+    Never,
+    /// Reopen the stream right away, up to this many times in a row.
+    Retries(u32),
+    /// Reopen the stream up to `max_retries` times in a row, waiting `base_delay` before the first attempt and twice
+    /// as long before each one after it.
+    Backoff {
+        max_retries: u32,
+        base_delay: Duration,
+    },
+}
+
+impl ReconnectPolicy {
+    /// How many reconnect attempts in a row are allowed. A frame arriving starts the count over.
+    #[must_use]
+    pub fn max_retries(&self) -> u32 {
+        match self {
+            ReconnectPolicy::Never => 0,
+            ReconnectPolicy::Retries(max_retries) | ReconnectPolicy::Backoff { max_retries, .. } => *max_retries,
+        }
+    }
+
+    /// How long to wait before reconnect attempt number `attempt`, counting from zero.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match self {
+            ReconnectPolicy::Never | ReconnectPolicy::Retries(_) => Duration::ZERO,
+            ReconnectPolicy::Backoff { base_delay, .. } => base_delay.saturating_mul(2_u32.saturating_pow(attempt)),
+        }
+    }
+
+    /// Runs a backend's capture loop on `stream`, reopening it as often as this policy allows whenever it is lost.
+    ///
+    /// `capture` delivers the stream's frames until it is stopped or lost. Before each reconnect attempt an
+    /// `Event::Other("reconnecting")` is delivered and the policy's delay is waited out on `stop`, so a stop request
+    /// still ends the stream right away. Failed attempts are delivered as [`Event::Error`]s.
+    ///
+    /// Returns whether the stream was stopped (or its consumer went away), rather than given up on.
+    pub fn drive<S>(
+        &self,
+        mut stream: S,
+        stop: &Receiver<()>,
+        mut deliver: impl FnMut(Event) -> bool,
+        mut capture: impl FnMut(S) -> CaptureOutcome,
+        mut reopen: impl FnMut() -> Result<S, NokhwaError>,
+    ) -> bool {
+        let mut attempts = 0;
+        loop {
+            match capture(stream) {
+                CaptureOutcome::Stopped => return true,
+                CaptureOutcome::Lost { delivered } => {
+                    if delivered {
+                        attempts = 0;
+                    }
+                }
+            }
+
+            stream = loop {
+                if attempts >= self.max_retries() {
+                    return false;
+                }
+                if !deliver(Event::Other("reconnecting".to_string())) {
+                    return true;
+                }
+                match stop.recv_timeout(self.delay(attempts)) {
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => return true,
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+                attempts += 1;
+                match reopen() {
+                    Ok(stream) => break stream,
+                    Err(why) => {
+                        if !deliver(Event::Error(why)) {
+                            return true;
+                        }
+                    }
+                }
+            };
+        }
+    }
+}
+
+/// How a capture loop run by [`ReconnectPolicy::drive`] ended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CaptureOutcome {
+    /// The stream was stopped, or its consumer went away.
+    Stopped,
+    /// The device stopped delivering frames. `delivered` is whether any frame came through first, which starts the
+    /// count of reconnect attempts over.
+    Lost { delivered: bool },
+}
+
 /// Configuration for a [`StreamHandle`].
 #[derive(Clone, Debug, Default, PartialOrd, PartialEq, TypedBuilder)]
 pub struct StreamConfiguration {
@@ -54,6 +179,28 @@ pub struct StreamConfiguration {
     pub bound: StreamBounds,
     #[builder(default)]
     pub on_other: ControlFlowOnOther,
+    /// Export frames as DMABUFs instead of copying them, on backends that support it.
+    ///
+    /// Frames then carry an empty buffer, and their [`crate::frame_buffer::Metadata::dmabuf_fd`] is their own
+    /// descriptor of the driver's buffer instead. The driver only gets that buffer back (to overwrite it) once every
+    /// frame holding it is dropped, so a consumer holding on to all of a stream's buffers stalls it.
+    #[builder(default)]
+    pub dmabuf: bool,
+    /// Don't reapply the camera's controls after the format is (re)applied when opening the stream.
+    ///
+    /// Some drivers reset controls to their defaults on a format change, so by default the controls are
+    /// snapshotted beforehand and restored afterward. Set this for drivers where that is unnecessary.
+    #[builder(default)]
+    pub skip_control_restore: bool,
+    /// Deliver at most this many frames per second, dropping any frame that arrives sooner than `1 / rate` after
+    /// the last delivered one. Dropped frames are simply skipped, they are not errors.
+    ///
+    /// This happens in the [`StreamHandle`], so it works with any backend, but the camera still captures at its own rate.
+    #[builder(default, setter(strip_option))]
+    pub max_consume_rate: Option<FrameRate>,
+    /// What to do when the device stops delivering frames, on backends that support reconnecting.
+    #[builder(default)]
+    pub reconnect: ReconnectPolicy,
 }
 
 /// Possible events to receive from an active stream.
@@ -77,10 +224,171 @@ pub enum Event {
     Terminating,
     /// The stream is closed.
     Closed,
+    /// The backend failed to capture a frame. The stream may recover, backends decide how many errors in a row they
+    /// tolerate before giving up with [`Event::Terminating`].
+    ///
+    /// Returned as an `Err` by the frame-reading methods of [`StreamHandle`].
+    Error(NokhwaError),
     /// Some other message sent by the driver. This can be ignored, although logging this is preferable.
     Other(String)
 }
 
+/// Creates the channel a backend sends a stream's [`Event`]s through, sized according to `bound`.
+#[must_use]
+pub fn event_channel(bound: &StreamBounds) -> (EventSender, Receiver<Event>) {
+    let (sender, receiver) = match bound {
+        StreamBounds::Bounded(bound) => bounded(*bound as usize),
+        StreamBounds::Unbounded | StreamBounds::LatestOnly => unbounded(),
+    };
+    let stale = (*bound == StreamBounds::LatestOnly).then(|| receiver.clone());
+    (EventSender { sender, stale, stats: StreamStatsMonitor::default() }, receiver)
+}
+
+/// A snapshot of how a [`StreamHandle`] has been doing, see [`StreamHandle::stats`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// Frames handed to the consumer.
+    pub frames_delivered: u64,
+    /// Frames thrown away before reaching the consumer, by [`StreamBounds::LatestOnly`], a full
+    /// [`StreamBounds::Bounded`] channel or [`StreamConfiguration::max_consume_rate`].
+    pub frames_dropped: u64,
+    /// [`Event::Error`]s received.
+    pub errors: u64,
+    /// When the last frame was handed to the consumer.
+    pub last_frame_at: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct StreamCounters {
+    frames_delivered: AtomicU64,
+    frames_dropped: AtomicU64,
+    errors: AtomicU64,
+    last_frame_at: Mutex<Option<Instant>>,
+}
+
+/// A shareable view of a stream's [`StreamStats`], e.g. for a monitoring thread. It stays readable after the stream
+/// is gone, without keeping the [`StreamHandle`] alive.
+#[derive(Clone, Debug, Default)]
+pub struct StreamStatsMonitor {
+    counters: Arc<StreamCounters>,
+}
+
+impl StreamStatsMonitor {
+    #[must_use]
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            frames_delivered: self.counters.frames_delivered.load(Ordering::Relaxed),
+            frames_dropped: self.counters.frames_dropped.load(Ordering::Relaxed),
+            errors: self.counters.errors.load(Ordering::Relaxed),
+            last_frame_at: self.last_frame_at(),
+        }
+    }
+
+    fn last_frame_at(&self) -> Option<Instant> {
+        *self.counters.last_frame_at.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record_delivered(&self, at: Instant) {
+        self.counters.frames_delivered.fetch_add(1, Ordering::Relaxed);
+        *self.counters.last_frame_at.lock().unwrap_or_else(PoisonError::into_inner) = Some(at);
+    }
+
+    fn record_dropped(&self, count: u64) {
+        self.counters.frames_dropped.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn record_error(&self) {
+        self.counters.errors.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The sending half of a stream's event channel, see [`event_channel`].
+#[derive(Clone, Debug)]
+pub struct EventSender {
+    sender: Sender<Event>,
+    // only kept for `StreamBounds::LatestOnly`, to take stale frames back out of the channel.
+    stale: Option<Receiver<Event>>,
+    stats: StreamStatsMonitor,
+}
+
+impl EventSender {
+    /// Sends `event`, dropping any frame still waiting in a [`StreamBounds::LatestOnly`] channel first.
+    ///
+    /// # Errors
+    /// If the receiving [`StreamHandle`] is gone, this will error.
+    // hands the event back like flume does, boxing it would cost every send an allocation.
+    #[allow(clippy::result_large_err)]
+    pub fn send(&self, event: Event) -> Result<(), SendError<Event>> {
+        if !self.drop_stale(&event) {
+            return Err(SendError(event));
+        }
+        self.sender.send(event)
+    }
+
+    /// Like [`EventSender::send`], but never blocks. With [`StreamBounds::Bounded`] this fails with
+    /// [`TrySendError::Full`] if the consumer has fallen behind.
+    ///
+    /// # Errors
+    /// If the channel is full or the receiving [`StreamHandle`] is gone, this will error.
+    // see `EventSender::send`.
+    #[allow(clippy::result_large_err)]
+    pub fn try_send(&self, event: Event) -> Result<(), TrySendError<Event>> {
+        if !self.drop_stale(&event) {
+            return Err(TrySendError::Disconnected(event));
+        }
+        let result = self.sender.try_send(event);
+        if let Err(TrySendError::Full(Event::NewFrame(_))) = result {
+            self.stats.record_dropped(1);
+        }
+        result
+    }
+
+    /// Like [`EventSender::send`], but gives up once `stop` receives a message or disconnects, so a backend waiting
+    /// on a consumer that fell behind can still be shut down.
+    ///
+    /// Returns whether `event` was sent, i.e. `false` if the receiving [`StreamHandle`] is gone or a stop was requested.
+    #[must_use]
+    pub fn send_or_stop(&self, event: Event, stop: &Receiver<()>) -> bool {
+        if !self.drop_stale(&event) {
+            return false;
+        }
+        Selector::new()
+            .send(&self.sender, event, |sent| sent.is_ok())
+            .recv(stop, |_| false)
+            .wait()
+    }
+
+    /// The stats of the stream this sender feeds, pass it to [`StreamHandle::with_stats_monitor`] so the handle
+    /// counts the frames dropped on this side of the channel too.
+    #[must_use]
+    pub fn stats_monitor(&self) -> StreamStatsMonitor {
+        self.stats.clone()
+    }
+
+    // takes pending frames out of a `LatestOnly` channel before `event` is sent. Returns `false` if the consumer is gone.
+    fn drop_stale(&self, event: &Event) -> bool {
+        let Some(stale) = &self.stale else {
+            return true;
+        };
+        // our own receiver keeps the channel connected, so check for the consumer's.
+        if self.sender.receiver_count() <= 1 {
+            return false;
+        }
+
+        if matches!(event, Event::NewFrame(_)) {
+            let (dropped, kept) = stale.try_iter().partition::<Vec<Event>, _>(|pending| matches!(pending, Event::NewFrame(_)));
+            self.stats.record_dropped(dropped.len() as u64);
+            for pending in kept {
+                // the channel is unbounded, so this only fails once the consumer is gone.
+                if self.sender.send(pending).is_err() {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
 /// Represents a handle to a currently open stream.
 /// 
 /// Streams are only valid as long as the camera is live. Any Stream that is living past a camera
@@ -98,7 +406,8 @@ pub struct StreamHandle {
     frame: Receiver<Event>,
     control: Sender<()>,
     configuration: StreamConfiguration,
-    format: Cell<CameraFormat>,
+    format: Mutex<CameraFormat>,
+    stats: StreamStatsMonitor,
 }
 
 impl StreamHandle {
@@ -108,18 +417,87 @@ impl StreamHandle {
             frame: recv,
             control,
             configuration,
-            format: Cell::new(format),
+            format: Mutex::new(format),
+            stats: StreamStatsMonitor::default(),
         }
     }
+
+    /// Counts this handle's stats into `monitor`, usually the [`EventSender::stats_monitor`] of the sender feeding it.
+    #[must_use]
+    pub fn with_stats_monitor(mut self, monitor: StreamStatsMonitor) -> Self {
+        self.stats = monitor;
+        self
+    }
     
     pub fn configuration(&self) -> &StreamConfiguration {
         &self.configuration
     }
     
     pub fn format(&self) -> CameraFormat {
-        self.format.get()
+        *self.format.lock().unwrap_or_else(PoisonError::into_inner)
     }
-    
+
+    /// How many frames this stream has delivered and dropped so far, and how many errors it has seen.
+    pub fn stats(&self) -> StreamStats {
+        self.stats.stats()
+    }
+
+    /// A handle to [`StreamHandle::stats`] that can be read from another thread.
+    pub fn stats_monitor(&self) -> StreamStatsMonitor {
+        self.stats.clone()
+    }
+
+    // applies `StreamConfiguration::max_consume_rate`: whether a frame arriving now should be delivered.
+    fn accept_frame(&self) -> bool {
+        let interval = self.configuration.max_consume_rate.and_then(|rate| {
+            let fps = rate.approximate_float()?;
+            Duration::try_from_secs_f32(1.0 / fps).ok()
+        });
+
+        let now = Instant::now();
+        let last = self.stats.last_frame_at();
+        if interval.zip(last).is_some_and(|(interval, last)| now.duration_since(last) < interval) {
+            self.stats.record_dropped(1);
+            return false;
+        }
+        self.stats.record_delivered(now);
+        true
+    }
+
+    // bookkeeping for every event taken out of the channel.
+    fn observe(&self, event: &Event) {
+        match event {
+            Event::FormatChange(fmt) => *self.format.lock().unwrap_or_else(PoisonError::into_inner) = *fmt,
+            Event::Error(_) => self.stats.record_error(),
+            _ => {}
+        }
+    }
+
+    // what a frame consumer does with `event`, shared by every way of taking frames out of the stream.
+    // frames go through `accept_frame`, and ending the stream also shuts the capture down.
+    fn classify(&self, event: Event) -> Step {
+        match event {
+            Event::NewFrame(frame) => {
+                if self.accept_frame() {
+                    Step::Frame(frame)
+                } else {
+                    Step::Skip
+                }
+            }
+            Event::FormatChange(_) => Step::Skip,
+            Event::NotReady => Step::NotReady,
+            Event::Terminating | Event::Closed => {
+                let _ = self.control.try_send(());
+                Step::End
+            }
+            Event::Error(why) => Step::Stop(why),
+            Event::Other(why) => match self.configuration.on_other {
+                ControlFlowOnOther::Continue => Step::Skip,
+                ControlFlowOnOther::Break => Step::Stop(NokhwaError::ReadFrameError(why)),
+            },
+        }
+    }
+
     pub fn next_event(&self) -> Result<Event, NokhwaError> {
         let event = match self.configuration.receiver {
             StreamReceiverBehaviour::Blocking => {
@@ -138,68 +516,597 @@ impl StreamHandle {
             }
         };
         
-        if let Event::FormatChange(fmt) = event {
-            self.format.set(fmt);
-        }
-        
+        self.observe(&event);
         return Ok(event)
     }
 
     pub fn next_frame(&self) -> Result<FrameBuffer, NokhwaError> {
         loop {
             let event = self.next_event()?;
-            match event {
-                Event::NewFrame(f) => return Ok(f),
-                Event::FormatChange(_) | Event::NotReady => continue,
-                Event::Terminating | Event::Closed => {
-                    let _ = self.control.try_send(());
-                    return Err(NokhwaError::ReadFrameError("Stream Closed.".to_string()))
-                }
-                Event::Other(why) => {
-                    match self.configuration.on_other {
-                        ControlFlowOnOther::Continue => continue,
-                        ControlFlowOnOther::Break => return Err(NokhwaError::ReadFrameError(why))
+            match self.classify(event) {
+                Step::Frame(f) => return Ok(f),
+                Step::Skip | Step::NotReady => {}
+                Step::End => return Err(NokhwaError::StreamClosed),
+                Step::Stop(why) => return Err(why),
+            }
+        }
+    }
+
+    /// Takes the next frame if one is already waiting, whatever the [`StreamReceiverBehaviour`] is.
+    ///
+    /// Returns `Ok(None)` if no frame is ready. [`Event::FormatChange`]s in front of the frame are skipped, and
+    /// [`Event::Other`] is handled according to [`StreamConfiguration::on_other`].
+    /// # Errors
+    /// If the stream is terminating or closed ([`NokhwaError::StreamClosed`]), or an [`Event::Error`] arrives, this will error.
+    pub fn try_next_frame(&self) -> Result<Option<FrameBuffer>, NokhwaError> {
+        loop {
+            let event = match self.frame.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Disconnected) => Event::Closed,
+            };
+            self.observe(&event);
+            match self.classify(event) {
+                Step::Frame(f) => return Ok(Some(f)),
+                Step::Skip | Step::NotReady => {}
+                Step::End => return Err(NokhwaError::StreamClosed),
+                Step::Stop(why) => return Err(why),
+            }
+        }
+    }
+
+    /// An iterator over this stream's frames, ending once the stream terminates or closes.
+    ///
+    /// With [`StreamReceiverBehaviour::Try`] or [`StreamReceiverBehaviour::Timeout`], a frame that isn't ready yet is
+    /// yielded as an `Err` instead of spinning until one is, the iterator can be polled again afterward.
+    /// [`Event::Other`] is handled according to [`StreamConfiguration::on_other`].
+    pub fn frames(&self) -> FrameIter<'_> {
+        FrameIter {
+            stream: self,
+            finished: false,
+        }
+    }
+
+    /// Calls `f` with every new frame until it returns [`ControlFlow::Break`] or the stream ends.
+    ///
+    /// [`Event::Terminating`] and [`Event::Closed`] end the loop normally, [`Event::Other`] is handled according to
+    /// [`StreamConfiguration::on_other`].
+    ///
+    /// # Errors
+    /// If an [`Event::Error`] arrives, or an [`Event::Other`] arrives and [`StreamConfiguration::on_other`] is
+    /// [`ControlFlowOnOther::Break`], this will error.
+    pub fn for_each_frame<F: FnMut(FrameBuffer) -> ControlFlow<()>>(&self, mut f: F) -> Result<(), NokhwaError> {
+        loop {
+            let event = self.next_event()?;
+            match self.classify(event) {
+                Step::Frame(frame) => {
+                    if f(frame).is_break() {
+                        return Ok(())
                     }
                 }
+                Step::Skip | Step::NotReady => {}
+                Step::End => return Ok(()),
+                Step::Stop(why) => return Err(why),
             }
         }
     }
 
+    /// Creates a new handle that receives this stream's events with every frame converted into `output`.
+    ///
+    /// The conversion runs on its own thread, so the consumer only ever sees `output` frames. Dropping
+    /// the returned handle also shuts down this stream.
+    ///
+    /// That thread reads from this handle's channel, so once converted, only read from the returned handle: any
+    /// event taken out of this one (or another handle to the same stream, e.g. the camera's
+    /// [`crate::camera::Capture::stream_handle`]) is missing from the converted stream.
+    ///
+    /// # Errors
+    /// If this stream's current format cannot be converted into `output`, this will error.
+    pub fn converted(&self, output: FrameFormat) -> Result<StreamHandle, NokhwaError> {
+        let source = *self.format().format();
+        if !can_convert(source, output) {
+            return Err(NokhwaError::OpenStreamError(format!("No conversion from {source} to {output}")));
+        }
+
+        let (sender, receiver) = event_channel(&self.configuration.bound);
+        let stats = sender.stats_monitor();
+        let (control, ctrl_recv) = bounded::<()>(1);
+        let inner = self.frame.clone();
+        let inner_control = self.control.clone();
+
+        std::thread::spawn(move || {
+            loop {
+                let event = Selector::new()
+                    .recv(&ctrl_recv, |_| None)
+                    .recv(&inner, |event| Some(event.unwrap_or(Event::Closed)))
+                    .wait();
+
+                let Some(event) = event else {
+                    let _ = inner_control.try_send(());
+                    return;
+                };
+
+                let event = match event {
+                    Event::NewFrame(frame) => {
+                        match convert(frame.source_frame_format(), frame.resolution(), frame.buffer(), output) {
+                            Ok(buffer) => Event::NewFrame(FrameBuffer::new(frame.resolution(), buffer, output, frame.metadata().cloned())),
+                            Err(why) => Event::Other(why.to_string()),
+                        }
+                    }
+                    Event::FormatChange(mut format) => {
+                        if !can_convert(*format.format(), output) {
+                            let _ = sender.send(Event::Other(format!("No conversion from {} to {output}", format.format())));
+                            let _ = sender.send(Event::Terminating);
+                            let _ = inner_control.try_send(());
+                            return;
+                        }
+                        format.set_format(output);
+                        Event::FormatChange(format)
+                    }
+                    other => other,
+                };
+
+                let closed = event == Event::Closed;
+                if sender.send(event).is_err() || closed {
+                    let _ = inner_control.try_send(());
+                    return;
+                }
+            }
+        });
+
+        let mut format = self.format();
+        format.set_format(output);
+        Ok(StreamHandle::new(receiver, control, self.configuration.clone(), format).with_stats_monitor(stats))
+    }
+
     #[cfg(feature = "async")]
     pub async fn poll_event(&self) -> Result<Event, NokhwaError> {
-        Ok(self.frame.recv_async().await.map_or_else(|_| { Event::Closed }, |e| { if let Event::FormatChange(fmt) = e {
-            self.format.set(fmt);
-        }
-        e
-        }))
+        let event = self.frame.recv_async().await.unwrap_or_else(|_| Event::Closed);
+        self.observe(&event);
+        Ok(event)
     }
     
+    /// [`StreamHandle::poll_event`], but gives up after `timeout`, returning [`Event::NotReady`].
+    ///
+    /// The timer doesn't depend on any particular async runtime.
+    /// # Errors
+    /// Like [`StreamHandle::poll_event`], this currently doesn't error, a closed stream is returned as [`Event::Closed`].
+    #[cfg(feature = "async")]
+    pub async fn poll_event_timeout(&self, timeout: Duration) -> Result<Event, NokhwaError> {
+        let mut recv = self.frame.recv_async();
+        let mut delay = Delay::new(timeout);
+        let received = poll_fn(|cx| {
+            if let Poll::Ready(received) = Pin::new(&mut recv).poll(cx) {
+                return Poll::Ready(Some(received));
+            }
+            Pin::new(&mut delay).poll(cx).map(|()| None)
+        }).await;
+
+        let event = match received {
+            Some(Ok(event)) => event,
+            Some(Err(_)) => Event::Closed,
+            None => Event::NotReady,
+        };
+        self.observe(&event);
+        Ok(event)
+    }
+
     // TODO: a smarter implementation? maybe?
     #[cfg(feature = "async")]
     pub async fn poll_next_frame(&self) -> Result<FrameBuffer, NokhwaError> {
         loop {
             let event = self.poll_event().await?;
-            match event {
-                Event::NewFrame(f) => return Ok(f),
-                Event::FormatChange(_) | Event::NotReady => continue,
-                Event::Terminating | Event::Closed => {
-                    let _ = self.control.try_send(());
-                    return Err(NokhwaError::ReadFrameError("Stream Closed.".to_string()))
-                }
-                Event::Other(why) => {
-                    match self.configuration.on_other {
-                        ControlFlowOnOther::Continue => continue,
-                        ControlFlowOnOther::Break => return Err(NokhwaError::ReadFrameError(why))
-                    }
-                }
+            match self.classify(event) {
+                Step::Frame(f) => return Ok(f),
+                Step::Skip | Step::NotReady => {}
+                Step::End => return Err(NokhwaError::StreamClosed),
+                Step::Stop(why) => return Err(why),
             }
         }
     }
+
+    /// Turns this handle into a [`Stream`] of frames, ending once the stream terminates or closes.
+    ///
+    /// Events are handled like [`StreamHandle::poll_next_frame`] does, and like it this **does not** respect the
+    /// [`StreamReceiverBehaviour`] setting.
+    #[cfg(feature = "async")]
+    pub fn into_stream(self: Arc<Self>) -> FrameStream {
+        FrameStream {
+            events: self.frame.clone().into_stream(),
+            stream: self,
+            finished: false,
+        }
+    }
+}
+
+/// An async [`Stream`] over the frames of a [`StreamHandle`], see [`StreamHandle::into_stream`].
+#[cfg(feature = "async")]
+pub struct FrameStream {
+    events: RecvStream<'static, Event>,
+    stream: Arc<StreamHandle>,
+    finished: bool,
 }
 
+#[cfg(feature = "async")]
+impl Stream for FrameStream {
+    type Item = Result<FrameBuffer, NokhwaError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        while !self.finished {
+            let event = match Pin::new(&mut self.events).poll_next(cx) {
+                Poll::Ready(Some(event)) => event,
+                Poll::Ready(None) => Event::Closed,
+                Poll::Pending => return Poll::Pending,
+            };
+            self.stream.observe(&event);
+            match self.stream.classify(event) {
+                Step::Frame(f) => return Poll::Ready(Some(Ok(f))),
+                Step::Skip | Step::NotReady => {}
+                Step::End => self.finished = true,
+                Step::Stop(why) => return Poll::Ready(Some(Err(why))),
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+// see `StreamHandle::classify`. Only ever lives on the stack for a moment, so the frame isn't boxed.
+#[allow(clippy::large_enum_variant)]
+enum Step {
+    Frame(FrameBuffer),
+    // a dropped frame, a format change or an `Event::Other` that is continued past.
+    Skip,
+    NotReady,
+    // the stream is terminating or closed.
+    End,
+    Stop(NokhwaError),
+}
+
+/// An iterator over the frames of a [`StreamHandle`], see [`StreamHandle::frames`].
+#[derive(Debug)]
+pub struct FrameIter<'stream> {
+    stream: &'stream StreamHandle,
+    finished: bool,
+}
+
+impl Iterator for FrameIter<'_> {
+    type Item = Result<FrameBuffer, NokhwaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let event = match self.stream.next_event() {
+                Ok(event) => event,
+                Err(why) => return Some(Err(why)),
+            };
+            match self.stream.classify(event) {
+                Step::Frame(f) => return Some(Ok(f)),
+                Step::Skip => {}
+                // don't spin on `Try`/`Timeout`, hand control back to the caller instead.
+                Step::NotReady => return Some(Err(NokhwaError::ReadFrameError("Frame not ready.".to_string()))),
+                Step::End => self.finished = true,
+                Step::Stop(why) => return Some(Err(why)),
+            }
+        }
+        None
+    }
+}
+
+impl std::iter::FusedIterator for FrameIter<'_> {}
+
 impl Drop for StreamHandle {
     fn drop(&mut self) {
         let _ = self.control.try_send(());
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Resolution;
+
+    fn frame(sequence: u8) -> Event {
+        Event::NewFrame(FrameBuffer::new(Resolution::new(1, 1), vec![sequence], FrameFormat::Luma8, None))
+    }
+
+    #[test]
+    fn latest_only_keeps_one_frame() {
+        let (sender, receiver) = event_channel(&StreamBounds::LatestOnly);
+        for sequence in 0..5 {
+            sender.send(frame(sequence)).unwrap();
+        }
+
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![frame(4)]);
+        assert_eq!(sender.stats_monitor().stats().frames_dropped, 4);
+    }
+
+    #[test]
+    fn latest_only_never_drops_other_events() {
+        let (sender, receiver) = event_channel(&StreamBounds::LatestOnly);
+        let format = CameraFormat::default();
+        sender.send(frame(0)).unwrap();
+        sender.send(Event::FormatChange(format)).unwrap();
+        sender.try_send(frame(1)).unwrap();
+        sender.send(Event::Terminating).unwrap();
+
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![Event::FormatChange(format), frame(1), Event::Terminating]);
+    }
+
+    #[test]
+    fn latest_only_notices_the_consumer_is_gone() {
+        let (sender, receiver) = event_channel(&StreamBounds::LatestOnly);
+        drop(receiver);
+
+        assert!(sender.send(frame(0)).is_err());
+    }
+
+    #[test]
+    fn send_or_stop_gives_up_on_a_stop() {
+        let (sender, receiver) = event_channel(&StreamBounds::Bounded(1));
+        let (stop_send, stop) = bounded(1);
+        assert!(sender.send_or_stop(frame(0), &stop));
+
+        // the channel is full, so only the stop can end the wait.
+        stop_send.send(()).unwrap();
+        assert!(!sender.send_or_stop(Event::Terminating, &stop));
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![frame(0)]);
+    }
+
+    #[test]
+    fn send_or_stop_waits_for_room() {
+        let (sender, receiver) = event_channel(&StreamBounds::Bounded(1));
+        let (_stop_send, stop) = bounded::<()>(1);
+        sender.send(frame(0)).unwrap();
+
+        let consumer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            receiver.iter().take(2).collect::<Vec<_>>()
+        });
+        assert!(sender.send_or_stop(Event::Terminating, &stop));
+        assert_eq!(consumer.join().unwrap(), vec![frame(0), Event::Terminating]);
+    }
+
+    // a handle with `events` already waiting, the sender keeps the stream from closing.
+    fn handle(configuration: StreamConfiguration, events: Vec<Event>) -> (StreamHandle, Sender<Event>, Receiver<()>) {
+        let (sender, receiver) = unbounded();
+        for event in events {
+            sender.send(event).unwrap();
+        }
+        let (control, stop) = bounded(1);
+        (StreamHandle::new(receiver, control, configuration, CameraFormat::default()), sender, stop)
+    }
+
+    #[test]
+    fn frame_readers_skip_format_changes_and_other_events() {
+        let format = CameraFormat::default();
+        let events = vec![Event::FormatChange(format), Event::Other("reconnecting".to_string()), frame(0), Event::Terminating];
+        let configuration = StreamConfiguration::builder().on_other(ControlFlowOnOther::Continue).build();
+        let (stream, _sender, stop) = handle(configuration, events);
+
+        assert_eq!(Event::NewFrame(stream.next_frame().unwrap()), frame(0));
+        assert!(matches!(stream.next_frame(), Err(NokhwaError::StreamClosed)));
+        assert_eq!(stop.try_recv(), Ok(()));
+    }
+
+    #[test]
+    fn frame_iter_yields_not_ready_and_ends_on_terminating() {
+        let configuration = StreamConfiguration::builder().receiver(StreamReceiverBehaviour::Try).build();
+        let (stream, sender, _stop) = handle(configuration, vec![frame(0)]);
+        let mut frames = stream.frames();
+
+        assert!(matches!(frames.next(), Some(Ok(_))));
+        assert!(matches!(frames.next(), Some(Err(NokhwaError::ReadFrameError(_)))));
+        assert_eq!(stream.try_next_frame().unwrap(), None);
+
+        sender.send(Event::Terminating).unwrap();
+        assert!(frames.next().is_none());
+        assert!(frames.next().is_none());
+    }
+
+    #[test]
+    fn for_each_frame_ends_normally_on_close_and_breaks_on_other() {
+        let (stream, _sender, _stop) = handle(StreamConfiguration::default(), vec![frame(0), frame(1), Event::Closed]);
+        let mut seen = 0;
+        stream.for_each_frame(|_| {
+            seen += 1;
+            ControlFlow::Continue(())
+        }).unwrap();
+        assert_eq!(seen, 2);
+
+        let configuration = StreamConfiguration::builder().on_other(ControlFlowOnOther::Break).build();
+        let (stream, _sender, _stop) = handle(configuration, vec![Event::Other("lost".to_string()), frame(0)]);
+        assert!(matches!(stream.for_each_frame(|_| ControlFlow::Continue(())), Err(NokhwaError::ReadFrameError(_))));
+    }
+
+    #[test]
+    fn reconnect_policy_retries_and_backs_off() {
+        assert_eq!(ReconnectPolicy::Never.max_retries(), 0);
+        assert_eq!(ReconnectPolicy::Retries(3).max_retries(), 3);
+        assert_eq!(ReconnectPolicy::Retries(3).delay(2), Duration::ZERO);
+
+        let backoff = ReconnectPolicy::Backoff { max_retries: 4, base_delay: Duration::from_millis(100) };
+        assert_eq!(backoff.max_retries(), 4);
+        assert_eq!([0, 1, 2].map(|attempt| backoff.delay(attempt)), [100, 200, 400].map(Duration::from_millis));
+        // never overflows, however long the outage.
+        assert!(backoff.delay(u32::MAX) >= backoff.delay(31));
+    }
+
+    // drives a mock backend whose streams each fail with a capture error and are lost, until the one opened by
+    // reconnect attempt number `losses` delivers a frame and is stopped.
+    fn drive_mock(policy: &ReconnectPolicy, losses: u8) -> (bool, Vec<Event>) {
+        let (sender, receiver) = unbounded();
+        let (_control, stop) = bounded(1);
+        let deliver = |event| sender.send(event).is_ok();
+        let mut opened = 0;
+        let stopped = policy.drive(0, &stop, deliver, |generation| {
+            if generation < losses {
+                deliver(Event::Error(NokhwaError::ReadFrameError("unplugged".to_string())));
+                CaptureOutcome::Lost { delivered: false }
+            } else {
+                deliver(frame(generation));
+                CaptureOutcome::Stopped
+            }
+        }, || {
+            opened += 1;
+            Ok(opened)
+        });
+        (stopped, receiver.try_iter().collect())
+    }
+
+    #[test]
+    fn drive_recovers_from_as_many_losses_as_allowed() {
+        let lost = || Event::Error(NokhwaError::ReadFrameError("unplugged".to_string()));
+        let reconnecting = || Event::Other("reconnecting".to_string());
+
+        let (stopped, events) = drive_mock(&ReconnectPolicy::Retries(3), 3);
+        assert!(stopped);
+        assert_eq!(events, vec![lost(), reconnecting(), lost(), reconnecting(), lost(), reconnecting(), frame(3)]);
+
+        let (stopped, events) = drive_mock(&ReconnectPolicy::Retries(2), 3);
+        assert!(!stopped);
+        assert_eq!(events, vec![lost(), reconnecting(), lost(), reconnecting(), lost()]);
+
+        assert_eq!(drive_mock(&ReconnectPolicy::Never, 1), (false, vec![lost()]));
+    }
+
+    #[test]
+    fn drive_starts_over_after_a_frame_and_reports_failed_reopens() {
+        let (_control, stop) = bounded(1);
+        let mut events = vec![];
+        let mut opened = 0;
+        let mut failed = false;
+        // every stream delivers a frame before it is lost, and every other reopen fails.
+        let stopped = ReconnectPolicy::Retries(2).drive(0, &stop, |event| {
+            events.push(event);
+            true
+        }, |generation| if generation < 3 {
+            CaptureOutcome::Lost { delivered: true }
+        } else {
+            CaptureOutcome::Stopped
+        }, || {
+            failed = !failed;
+            if failed {
+                return Err(NokhwaError::OpenStreamError("busy".to_string()));
+            }
+            opened += 1;
+            Ok(opened)
+        });
+
+        assert!(stopped);
+        assert_eq!(events.iter().filter(|event| matches!(event, Event::Error(NokhwaError::OpenStreamError(_)))).count(), 3);
+        assert_eq!(events.len(), 9);
+    }
+
+    #[test]
+    fn drive_stops_during_the_backoff() {
+        let (control, stop) = bounded(1);
+        control.send(()).unwrap();
+        let policy = ReconnectPolicy::Backoff { max_retries: 1, base_delay: Duration::from_secs(30) };
+        let started = Instant::now();
+        let stopped = policy.drive((), &stop, |_| true, |()| CaptureOutcome::Lost { delivered: false }, || unreachable!());
+
+        assert!(stopped);
+        assert!(started.elapsed() < Duration::from_secs(30));
+    }
+
+
+    #[test]
+    fn next_frame_returns_errors_and_carries_on() {
+        let events = vec![Event::Error(NokhwaError::ReadFrameError("dropped out".to_string())), frame(0)];
+        let (stream, _sender, stop) = handle(StreamConfiguration::default(), events);
+
+        assert!(matches!(stream.next_frame(), Err(NokhwaError::ReadFrameError(why)) if why == "dropped out"));
+        assert_eq!(Event::NewFrame(stream.next_frame().unwrap()), frame(0));
+        // an error doesn't end the stream.
+        assert!(stop.is_empty());
+    }
+
+
+    #[test]
+    fn max_consume_rate_drops_frames_arriving_too_soon() {
+        let configuration = StreamConfiguration::builder()
+            .receiver(StreamReceiverBehaviour::Try)
+            .max_consume_rate(FrameRate::from_fps(10))
+            .build();
+        let (stream, sender, _stop) = handle(configuration, vec![frame(0), frame(1), frame(2)]);
+
+        assert_eq!(Event::NewFrame(stream.try_next_frame().unwrap().unwrap()), frame(0));
+        // the next two arrive well within 100ms of the first.
+        assert_eq!(stream.try_next_frame().unwrap(), None);
+        assert_eq!(stream.stats().frames_dropped, 2);
+
+        std::thread::sleep(Duration::from_millis(120));
+        sender.send(frame(3)).unwrap();
+        assert_eq!(Event::NewFrame(stream.try_next_frame().unwrap().unwrap()), frame(3));
+        assert_eq!(stream.stats().frames_delivered, 2);
+    }
+
+
+    #[test]
+    fn stats_count_delivered_frames_and_errors() {
+        let events = vec![frame(0), Event::Error(NokhwaError::ReadFrameError("glitch".to_string())), frame(1), Event::Other("hi".to_string()), frame(2)];
+        let configuration = StreamConfiguration::builder().on_other(ControlFlowOnOther::Continue).build();
+        let (stream, _sender, _stop) = handle(configuration, events);
+        let monitor = stream.stats_monitor();
+        assert_eq!(monitor.stats(), StreamStats::default());
+
+        assert!(stream.next_frame().is_ok());
+        assert!(stream.next_frame().is_err());
+        assert!(stream.next_frame().is_ok());
+        assert!(stream.next_frame().is_ok());
+
+        let stats = stream.stats();
+        assert_eq!((stats.frames_delivered, stats.frames_dropped, stats.errors), (3, 0, 1));
+        assert!(stats.last_frame_at.is_some());
+        // the monitor outlives the stream.
+        drop(stream);
+        assert_eq!(monitor.stats(), stats);
+    }
+
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn frame_stream_yields_frames_until_terminating() {
+        use futures::StreamExt;
+
+        let (stream, sender, stop) = handle(StreamConfiguration::default(), vec![frame(0), Event::FormatChange(CameraFormat::default())]);
+        let producer = std::thread::spawn(move || {
+            for sequence in 1..3 {
+                std::thread::sleep(Duration::from_millis(5));
+                sender.send(frame(sequence)).unwrap();
+            }
+            sender.send(Event::Terminating).unwrap();
+        });
+
+        let mut frames = Arc::new(stream).into_stream();
+        let collected = futures::executor::block_on(async {
+            let mut collected = vec![];
+            while let Some(frame) = frames.next().await {
+                collected.push(Event::NewFrame(frame.unwrap()));
+            }
+            collected
+        });
+        producer.join().unwrap();
+
+        assert_eq!(collected, vec![frame(0), frame(1), frame(2)]);
+        assert!(futures::executor::block_on(frames.next()).is_none());
+        assert_eq!(stop.try_recv(), Ok(()));
+    }
+
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn poll_event_timeout_gives_not_ready_then_the_event() {
+        let (stream, sender, _stop) = handle(StreamConfiguration::default(), vec![]);
+        let event = futures::executor::block_on(stream.poll_event_timeout(Duration::from_millis(10))).unwrap();
+        assert_eq!(event, Event::NotReady);
+
+        sender.send(frame(0)).unwrap();
+        let event = futures::executor::block_on(stream.poll_event_timeout(Duration::from_secs(5))).unwrap();
+        assert_eq!(event, frame(0));
+
+        drop(sender);
+        let event = futures::executor::block_on(stream.poll_event_timeout(Duration::from_secs(5))).unwrap();
+        assert_eq!(event, Event::Closed);
+    }
+}
@@ -5,12 +5,14 @@ use serde::{Deserialize, Serialize};
 use std::{
     borrow::Borrow,
     cmp::Ordering,
+    convert::Infallible,
     fmt::{Debug, Display, Formatter},
-    hash::{Hash},
+    hash::{Hash, Hasher},
     ops::{Sub},
 };
 use std::num::NonZeroI32;
-use std::ops::{Div, Rem};
+use std::str::FromStr;
+use std::ops::{Add, Div, Rem};
 use num_rational::{Ratio, Rational32};
 use crate::ranges::{RangeItem};
 use num_traits::FromPrimitive;
@@ -69,6 +71,20 @@ impl Display for CameraIndex {
     }
 }
 
+impl FromStr for CameraIndex {
+    type Err = Infallible;
+
+    /// Numbers (without leading zeroes, so that [`Display`] gives back the same string) become a
+    /// [`CameraIndex::Index`], anything else (e.g. `/dev/video2`) a [`CameraIndex::String`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let canonical_number = s.bytes().all(|b| b.is_ascii_digit()) && (s == "0" || !s.starts_with('0'));
+        Ok(match s.parse::<u32>() {
+            Ok(index) if canonical_number => CameraIndex::Index(index),
+            _ => CameraIndex::String(s.to_string()),
+        })
+    }
+}
+
 impl Default for CameraIndex {
     fn default() -> Self {
         CameraIndex::Index(0)
@@ -93,7 +109,12 @@ impl TryFrom<CameraIndex> for usize {
 
 /// Describes a Resolution.
 /// This struct consists of a Width and a Height value (x,y). <br>
-/// Note: the [`Ord`] implementation of this struct is flipped from highest to lowest.
+/// Note: the [`Ord`] implementation of this struct is lexicographic - it compares width first, then
+/// height. This is also what a [`Range<Resolution>`](crate::ranges::Range) validates against, so
+/// `[640x480, 1920x1080]` accepts `1280x2000` since its width is in range.
+///
+/// The arithmetic operators ([`Sub`], [`Div`], [`Rem`]) work per-axis and never panic: subtraction
+/// saturates at zero, and dividing (or taking the remainder) by a zero axis leaves that axis unconstrained.
 /// # JS-WASM
 /// This is exported as `JSResolution`
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
@@ -104,6 +125,15 @@ pub struct Resolution {
 }
 
 impl Resolution {
+    /// 640x480
+    pub const VGA: Resolution = Resolution::new(640, 480);
+    /// 1280x720
+    pub const HD720: Resolution = Resolution::new(1280, 720);
+    /// 1920x1080
+    pub const FHD1080: Resolution = Resolution::new(1920, 1080);
+    /// 3840x2160
+    pub const UHD4K: Resolution = Resolution::new(3840, 2160);
+
     /// Create a new resolution from 2 image size coordinates.
     #[must_use]
     // TODO: make this height and width.
@@ -146,6 +176,35 @@ impl Resolution {
     pub fn aspect_ratio(&self) -> f64 {
         f64::from(self.width_x) / f64::from(self.height_y)
     }
+
+    /// The aspect ratio as the smallest whole `(width, height)` pair, e.g. `(16, 9)` for 1920x1080.
+    ///
+    /// A zero-sized resolution is returned as-is.
+    #[must_use]
+    pub fn aspect_ratio_reduced(&self) -> (u32, u32) {
+        let (mut a, mut b) = (self.width_x, self.height_y);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        match a {
+            0 => (self.width_x, self.height_y),
+            gcd => (self.width_x / gcd, self.height_y / gcd),
+        }
+    }
+
+    /// How many pixels this resolution has.
+    #[must_use]
+    pub fn area(&self) -> u64 {
+        u64::from(self.width_x) * u64::from(self.height_y)
+    }
+
+    /// Scales both axes by `factor`, rounding to the nearest pixel. Negative factors give zero.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn scale(&self, factor: f32) -> Resolution {
+        let scale = |axis: u32| (f64::from(axis) * f64::from(factor)).round().clamp(0.0, f64::from(u32::MAX)) as u32;
+        Resolution::new(scale(self.width_x), scale(self.height_y))
+    }
 }
 
 impl Display for Resolution {
@@ -170,15 +229,23 @@ impl Ord for Resolution {
     }
 }
 
-impl Distance<u32> for Resolution {
-    fn distance_from(&self, other: &Self) -> u32 {
-        let x1 = self.x();
-        let x2 = other.x();
-
-        let y1 = self.y();
-        let y2 = other.y();
+impl Resolution {
+    /// The squared euclidean distance to `other` in pixels, `dw² + dh²`.
+    ///
+    /// This is exact, so unlike [`Resolution::distance_from`] it can be compared for equality.
+    #[must_use]
+    pub fn squared_distance(&self, other: &Self) -> u64 {
+        let width = u64::from(self.width().abs_diff(other.width()));
+        let height = u64::from(self.height().abs_diff(other.height()));
+        width * width + height * height
+    }
+}
 
-        (x2 - x1).pow(2) + (y2 - y1).pow(2)
+impl Distance<f64> for Resolution {
+    /// The euclidean distance to `other` in pixels, `sqrt(dw² + dh²)`.
+    #[allow(clippy::cast_precision_loss)]
+    fn distance_from(&self, other: &Self) -> f64 {
+        (self.squared_distance(other) as f64).sqrt()
     }
 }
 
@@ -186,18 +253,28 @@ impl Div for Resolution {
     type Output = Resolution;
 
     fn div(self, rhs: Self) -> Self::Output {
-        let x_div = self.x().div(rhs.x());
-        let y_div = self.y().div(rhs.y());
+        let x_div = self.x().checked_div(rhs.x()).unwrap_or(self.x());
+        let y_div = self.y().checked_div(rhs.y()).unwrap_or(self.y());
         Resolution::new(x_div, y_div)
     }
 }
 
+impl Add for Resolution {
+    type Output = Resolution;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let x_add = self.x().saturating_add(rhs.x());
+        let y_add = self.y().saturating_add(rhs.y());
+        Resolution::new(x_add, y_add)
+    }
+}
+
 impl Sub for Resolution {
     type Output = Resolution;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let x_sub = self.x().sub(rhs.x());
-        let y_sub = self.y().sub(rhs.y());
+        let x_sub = self.x().saturating_sub(rhs.x());
+        let y_sub = self.y().saturating_sub(rhs.y());
         Resolution::new(x_sub, y_sub)
     }
 }
@@ -206,8 +283,9 @@ impl Rem for Resolution {
     type Output = Resolution;
 
     fn rem(self, rhs: Self) -> Self::Output {
-        let x_rem = self.x().rem(rhs.x());
-        let y_rem = self.y().rem(rhs.y());
+        // a zero step on an axis means any value on that axis is on the grid.
+        let x_rem = self.x().checked_rem(rhs.x()).unwrap_or(0);
+        let y_rem = self.y().checked_rem(rhs.y()).unwrap_or(0);
         Resolution::new(x_rem, y_rem)
     }
 }
@@ -221,14 +299,47 @@ impl RangeItem for Resolution {
 /// Note that while constructing negative is allowed, the absolute value
 /// will be passed to the driver.
 ///
-/// # Panics
-/// If denominator is 0, any attempt to use the [`FrameRate`] will **panic.**
-#[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
-#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+/// Comparison, equality and hashing are all by *value*, so `60/2` and `30/1` are the same frame rate.
+///
+/// With the `serialize` feature, this is (de)serialized as `{ "numerator": i32, "denominator": i32 }`,
+/// and a zero denominator is rejected when deserializing.
+///
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize), serde(try_from = "FrameRateParts", into = "FrameRateParts"))]
 pub struct FrameRate {
     rational: Rational32,
 }
 
+// the serialized form of a `FrameRate`.
+#[cfg(feature = "serialize")]
+#[derive(Copy, Clone, Serialize, Deserialize)]
+struct FrameRateParts {
+    numerator: i32,
+    denominator: i32,
+}
+
+#[cfg(feature = "serialize")]
+impl TryFrom<FrameRateParts> for FrameRate {
+    type Error = String;
+
+    fn try_from(parts: FrameRateParts) -> Result<Self, Self::Error> {
+        let denominator = NonZeroI32::new(parts.denominator).ok_or_else(|| {
+            format!("frame rate {}/0 has a zero denominator", parts.numerator)
+        })?;
+        Ok(FrameRate::new(parts.numerator, denominator))
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl From<FrameRate> for FrameRateParts {
+    fn from(frame_rate: FrameRate) -> Self {
+        FrameRateParts {
+            numerator: *frame_rate.numerator(),
+            denominator: *frame_rate.denominator(),
+        }
+    }
+}
+
 impl FrameRate {
     pub const fn new(numerator: i32, denominator: NonZeroI32) -> Self {
         Self {
@@ -242,6 +353,49 @@ impl FrameRate {
         }
     }
 
+    /// A whole number frame rate (`fps/1`).
+    ///
+    /// Values past `i32::MAX` are clamped to it.
+    #[must_use]
+    pub fn from_fps(fps: u32) -> Self {
+        Self::frame_rate(i32::try_from(fps).unwrap_or(i32::MAX))
+    }
+
+    /// The frame rate closest to `fps`.
+    ///
+    /// NTSC-style rates are recognized and given their exact value, e.g. `29.97` becomes `30000/1001`.
+    /// Returns `None` for negative, non-finite or out of range values.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_f32(fps: f32) -> Option<Self> {
+        if !fps.is_finite() || fps < 0.0 {
+            return None;
+        }
+
+        let whole = fps.round();
+        if (fps - whole).abs() < 0.001 {
+            return i32::from_f32(whole).map(Self::frame_rate);
+        }
+
+        // the NTSC rates are the whole rates slowed down by 1000/1001
+        let ntsc = (fps * 1.001).round();
+        if (f64::from(ntsc) * 1000.0 / 1001.0 - f64::from(fps)).abs() < 0.005 {
+            let numerator = i32::from_f32(ntsc)?.checked_mul(1000)?;
+            return Some(Self::new(numerator, NonZeroI32::new(1001)?));
+        }
+
+        Ratio::<i32>::approximate_float(fps).map(Self::from)
+    }
+
+    /// This frame rate with numerator and denominator divided by their greatest common divisor (and a positive denominator),
+    /// so that e.g. `60/2` becomes `30/1`.
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        // reduced in wider integers like `Ord`, as flipping the sign of e.g. `i32::MIN/-2` overflows an `i32`.
+        let (numerator, denominator) = self.signed_parts();
+        Self::from_wide_parts(i128::from(numerator), i128::from(denominator))
+    }
+
     pub fn numerator(&self) -> &i32 {
         self.rational.numer()
     }
@@ -260,6 +414,72 @@ impl FrameRate {
         
         Some(numerator_float / denominator_float)
     }
+
+    /// [`FrameRate::approximate_float`], where a frame rate that doesn't fit an `f32` is `f32::INFINITY`.
+    #[must_use]
+    pub fn as_f32(&self) -> f32 {
+        self.approximate_float().unwrap_or(f32::INFINITY)
+    }
+
+    // the reduced `numerator / denominator` (with `denominator > 0`), approximated if it doesn't fit an `i32` and
+    // saturated if not even that does.
+    #[allow(clippy::cast_precision_loss)]
+    fn from_wide_parts(numerator: i128, denominator: i128) -> Self {
+        let reduced = Ratio::<i128>::new(numerator, denominator);
+        if let (Ok(numerator), Ok(denominator)) = (i32::try_from(*reduced.numer()), i32::try_from(*reduced.denom())) {
+            return Rational32::new_raw(numerator, denominator).into();
+        }
+
+        let value = *reduced.numer() as f64 / *reduced.denom() as f64;
+        Ratio::<i32>::approximate_float(value).map_or_else(|| {
+            Self::frame_rate(if value < 0.0 { i32::MIN } else { i32::MAX })
+        }, Self::from)
+    }
+
+    // widened, with the sign moved onto the numerator so the denominator is always positive.
+    fn signed_parts(self) -> (i64, i64) {
+        let numerator = i64::from(*self.numerator());
+        let denominator = i64::from(*self.denominator());
+        if denominator < 0 {
+            (-numerator, -denominator)
+        } else {
+            (numerator, denominator)
+        }
+    }
+}
+
+impl PartialEq for FrameRate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FrameRate {}
+
+impl PartialOrd for FrameRate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FrameRate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // a/b <=> c/d is a*d <=> c*b for positive denominators. This can't overflow an i64.
+        let (self_numerator, self_denominator) = self.signed_parts();
+        let (other_numerator, other_denominator) = other.signed_parts();
+        (self_numerator * other_denominator).cmp(&(other_numerator * self_denominator))
+    }
+}
+
+impl Hash for FrameRate {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // equal values must hash the same, so hash the fully reduced form. Like `normalized`, this reduces the
+        // widened parts, which always fit.
+        let (numerator, denominator) = self.signed_parts();
+        let reduced = Ratio::<i64>::new(numerator, denominator);
+        reduced.numer().hash(state);
+        reduced.denom().hash(state);
+    }
 }
 
 impl Default for FrameRate {
@@ -274,19 +494,56 @@ impl Display for FrameRate {
     }
 }
 
+/// The exact, reduced sum, computed over the common denominator in `i128` like [`FrameRate`]'s [`Sub`].
+impl Add for FrameRate {
+    type Output = FrameRate;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        // a/b + c/d = (a*d + c*b) / b*d. The products fit an i64, but their sum may not.
+        let (self_numerator, self_denominator) = self.signed_parts();
+        let (rhs_numerator, rhs_denominator) = rhs.signed_parts();
+        let numerator = i128::from(self_numerator * rhs_denominator) + i128::from(rhs_numerator * self_denominator);
+        FrameRate::from_wide_parts(numerator, i128::from(self_denominator * rhs_denominator))
+    }
+}
+
+impl Add for &FrameRate {
+    type Output = FrameRate;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+/// Dividing by a zero frame rate leaves the frame rate as-is, like [`Resolution`]'s [`Div`], instead of panicking.
 impl Div for FrameRate {
     type Output = FrameRate;
 
     fn div(self, rhs: Self) -> Self::Output {
-        self.rational.div(rhs.rational).into()
+        // (a/b) / (c/d) = a*d / b*c, with the sign of c moved onto the numerator.
+        let (self_numerator, self_denominator) = self.signed_parts();
+        let (rhs_numerator, rhs_denominator) = rhs.signed_parts();
+        if rhs_numerator == 0 {
+            return self;
+        }
+        let numerator = i128::from(self_numerator * rhs_denominator) * i128::from(rhs_numerator.signum());
+        FrameRate::from_wide_parts(numerator, i128::from(self_denominator * rhs_numerator.abs()))
     }
 }
 
+/// The exact, reduced difference, e.g. `30/1 - 30000/1001` is `30/1001`.
+///
+/// This is computed over the common denominator in `i128`, so it can't overflow. Should the result not fit a
+/// [`FrameRate`], it is approximated (and saturated at `i32::MAX` or `i32::MIN` frames per second if need be).
 impl Sub for FrameRate {
     type Output = FrameRate;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        self.rational.sub(rhs.rational).into()
+        // a/b - c/d = (a*d - c*b) / b*d. The products fit an i64, but their difference may not.
+        let (self_numerator, self_denominator) = self.signed_parts();
+        let (rhs_numerator, rhs_denominator) = rhs.signed_parts();
+        let numerator = i128::from(self_numerator * rhs_denominator) - i128::from(rhs_numerator * self_denominator);
+        FrameRate::from_wide_parts(numerator, i128::from(self_denominator * rhs_denominator))
     }
 }
 
@@ -294,15 +551,24 @@ impl Sub for &FrameRate {
     type Output = FrameRate;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        (self.rational - rhs.rational).into()
+        *self - *rhs
     }
 }
 
+/// The remainder of a zero frame rate is zero, like [`Resolution`]'s [`Rem`], so a zero step puts every frame rate
+/// on the grid instead of panicking.
 impl Rem for FrameRate {
     type Output = FrameRate;
 
     fn rem(self, rhs: Self) -> Self::Output {
-        self.rational.rem(rhs.rational).into()
+        // (a/b) % (c/d) = (a*d % c*b) / b*d
+        let (self_numerator, self_denominator) = self.signed_parts();
+        let (rhs_numerator, rhs_denominator) = rhs.signed_parts();
+        if rhs_numerator == 0 {
+            return FrameRate::ZERO;
+        }
+        let numerator = i128::from(self_numerator * rhs_denominator) % i128::from(rhs_numerator * self_denominator);
+        FrameRate::from_wide_parts(numerator, i128::from(self_denominator * rhs_denominator))
     }
 }
 
@@ -320,7 +586,10 @@ impl From<Rational32> for FrameRate {
 
 /// This is a convenience struct that holds all information about the format of a webcam stream.
 /// It consists of a [`Resolution`], [`FrameFormat`], and a [`FrameRate`].
-#[derive(Copy, Clone, Debug, Hash, PartialEq, PartialOrd, Eq, Ord)]
+///
+/// Formats are ordered by pixel count first and frame rate second, so sorting a `Vec<CameraFormat>`
+/// puts the smallest, slowest format first.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct CameraFormat {
     resolution: Resolution,
@@ -339,6 +608,15 @@ impl CameraFormat {
         }
     }
 
+    /// A builder for a [`CameraFormat`], starting from [`CameraFormat::default`] (640x480 MJPEG @ 30 fps),
+    /// e.g. `CameraFormat::builder().width(1920).height(1080).format(FrameFormat::MJpeg).fps(30).build()`.
+    #[must_use]
+    pub fn builder() -> CameraFormatBuilder {
+        CameraFormatBuilder {
+            format: CameraFormat::default(),
+        }
+    }
+
     /// [`CameraFormat::new()`], but raw.
     #[must_use]
     pub const fn new_from(res_x: u32, res_y: u32, format: FrameFormat, fps: FrameRate) -> Self {
@@ -396,6 +674,99 @@ impl CameraFormat {
     pub fn set_format(&mut self, format: FrameFormat) {
         self.format = format;
     }
+
+    /// How many megapixels (millions of pixels) a frame of this format has.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn megapixels(&self) -> f32 {
+        self.resolution.area() as f32 / 1_000_000.0
+    }
+
+    /// The width of this format divided by its height.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn aspect_ratio(&self) -> f32 {
+        self.resolution.aspect_ratio() as f32
+    }
+
+    /// The bandwidth this format needs in bits per second, e.g. over USB.
+    ///
+    /// This is only known for uncompressed formats (see [`FrameFormat::expected_buffer_size`]),
+    /// compressed formats return `None`.
+    #[must_use]
+    pub fn estimated_bitrate(&self) -> Option<u64> {
+        let bits_per_frame = self.format.expected_buffer_size(self.resolution)? as u128 * 8;
+        let (numerator, denominator) = self.frame_rate.signed_parts();
+        if denominator == 0 {
+            return None;
+        }
+
+        let bitrate = bits_per_frame * u128::from(numerator.unsigned_abs()) / u128::from(denominator.unsigned_abs());
+        Some(u64::try_from(bitrate).unwrap_or(u64::MAX))
+    }
+
+    /// Whether this format can be streamed over a USB 2.0 (480 Mbit/s) link.
+    ///
+    /// USB 2.0 webcams stream isochronously, which after protocol overhead caps out at 3 1024 byte
+    /// packets per 125µs microframe (~196 Mbit/s). Compressed formats are assumed to fit.
+    #[must_use]
+    pub fn fits_usb2(&self) -> bool {
+        self.estimated_bitrate()
+            .is_none_or(|bitrate| bitrate <= USB2_ISOCHRONOUS_BITRATE)
+    }
+}
+
+// 3 packets * 1024 bytes * 8 bits * 8000 microframes per second.
+const USB2_ISOCHRONOUS_BITRATE: u64 = 3 * 1024 * 8 * 8000;
+
+/// Builds a [`CameraFormat`] field by field, see [`CameraFormat::builder`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CameraFormatBuilder {
+    format: CameraFormat,
+}
+
+impl CameraFormatBuilder {
+    #[must_use]
+    pub fn width(mut self, width: u32) -> Self {
+        self.format.resolution.width_x = width;
+        self
+    }
+
+    #[must_use]
+    pub fn height(mut self, height: u32) -> Self {
+        self.format.resolution.height_y = height;
+        self
+    }
+
+    #[must_use]
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.format.resolution = resolution;
+        self
+    }
+
+    #[must_use]
+    pub fn format(mut self, format: FrameFormat) -> Self {
+        self.format.format = format;
+        self
+    }
+
+    /// Sets a whole number frame rate, see [`FrameRate::from_fps`].
+    #[must_use]
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.format.frame_rate = FrameRate::from_fps(fps);
+        self
+    }
+
+    #[must_use]
+    pub fn frame_rate(mut self, frame_rate: FrameRate) -> Self {
+        self.format.frame_rate = frame_rate;
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> CameraFormat {
+        self.format
+    }
 }
 
 impl Default for CameraFormat {
@@ -408,12 +779,30 @@ impl Default for CameraFormat {
     }
 }
 
+impl PartialOrd for CameraFormat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CameraFormat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.resolution.area().cmp(&other.resolution.area())
+            .then_with(|| self.frame_rate.cmp(&other.frame_rate))
+            // only to stay consistent with `Eq`
+            .then_with(|| self.resolution.cmp(&other.resolution))
+            .then_with(|| self.format.cmp(&other.format))
+    }
+}
+
+/// E.g. `1920x1080 MJPG @ 30/1 fps`. The format is shown by its `FourCC`, or by name if it has none.
 impl Display for CameraFormat {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let format = self.format.fourcc_str().unwrap_or_else(|| self.format.to_string());
         write!(
             f,
-            "{}@{}FPS, {} Format",
-            self.resolution, self.frame_rate, self.format
+            "{} {} @ {}/{} fps",
+            self.resolution, format, self.frame_rate.numerator(), self.frame_rate.denominator()
         )
     }
 }
@@ -509,6 +898,27 @@ impl CameraInformation {
         self.index = index;
     }
 
+    /// An identity for the physical camera that survives reboots and driver updates, e.g. to remember a
+    /// preferred camera across sessions.
+    ///
+    /// This is the bus path out of `misc` (the token before the `v<major>.<minor>.<patch>` version, as written
+    /// by the V4L2 backend), or the index if `misc` has none.
+    #[must_use]
+    pub fn stable_id(&self) -> String {
+        let tokens = self.misc.split_whitespace().collect::<Vec<&str>>();
+        let is_version = |token: &str| {
+            token.strip_prefix('v').is_some_and(|version| {
+                let parts = version.split('.').collect::<Vec<&str>>();
+                parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+            })
+        };
+
+        match tokens.iter().position(|token| is_version(token)) {
+            Some(version) if version > 0 => tokens[..version].join(" "),
+            _ => self.index.to_string(),
+        }
+    }
+
     // /// Gets the device info's index as an `u32`.
     // /// # Errors
     // /// If the index is not parsable as a `u32`, this will error.
@@ -529,6 +939,39 @@ impl CameraInformation {
     // }
 }
 
+/// A [`CameraInformation`] that compares and hashes by its [`CameraInformation::stable_id`] alone, so it can be
+/// used as a map key that still matches after the rest of the information (e.g. the driver version) changed.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct StableCameraInformation(pub CameraInformation);
+
+impl StableCameraInformation {
+    #[must_use]
+    pub fn stable_id(&self) -> String {
+        self.0.stable_id()
+    }
+}
+
+impl From<CameraInformation> for StableCameraInformation {
+    fn from(information: CameraInformation) -> Self {
+        Self(information)
+    }
+}
+
+impl PartialEq for StableCameraInformation {
+    fn eq(&self, other: &Self) -> bool {
+        self.stable_id() == other.stable_id()
+    }
+}
+
+impl Eq for StableCameraInformation {}
+
+impl Hash for StableCameraInformation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.stable_id().hash(state);
+    }
+}
+
 impl Display for CameraInformation {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -596,3 +1039,182 @@ impl Display for CameraInformation {
 //         write!(f, "{self:?}")
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranges::{Range, ValidatableRange};
+    use std::collections::hash_map::DefaultHasher;
+
+    fn rate(numerator: i32, denominator: i32) -> FrameRate {
+        FrameRate::new(numerator, NonZeroI32::new(denominator).unwrap())
+    }
+
+    fn hash_of(frame_rate: FrameRate) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        frame_rate.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn frame_rates_compare_by_value() {
+        assert_eq!(rate(30, 1), rate(60, 2));
+        assert_eq!(rate(30, 1).cmp(&rate(60, 2)), Ordering::Equal);
+        assert_eq!(hash_of(rate(30, 1)), hash_of(rate(60, 2)));
+        assert!(rate(30000, 1001) < rate(30, 1));
+        assert!(rate(-30, -1) == rate(30, 1));
+    }
+
+    #[test]
+    fn extreme_frame_rates_normalize_and_hash_without_overflowing() {
+        // flipping the sign of `i32::MIN` overflows an `i32`.
+        assert_eq!(hash_of(rate(i32::MIN, -2)), hash_of(rate(1 << 30, 1)));
+        assert_eq!(rate(i32::MIN, -2).normalized().numerator(), &(1 << 30));
+        assert_eq!(rate(i32::MIN, -1).normalized(), FrameRate::frame_rate(i32::MAX));
+        assert_eq!(rate(60, -2).normalized().denominator(), &1);
+    }
+
+    #[test]
+    fn frame_rate_ranges_validate_and_step() {
+        let range = Range::new(FrameRate::frame_rate(15), FrameRate::frame_rate(30), Some(FrameRate::frame_rate(5)));
+
+        assert!(range.validate(&rate(60, 2)));
+        assert!(range.validate(&rate(40, 2)));
+        assert!(!range.validate(&rate(30000, 1001)));
+        assert_eq!(
+            range.iter_steps().collect::<Vec<_>>(),
+            [15, 20, 25, 30].map(FrameRate::frame_rate).to_vec()
+        );
+    }
+
+    #[test]
+    fn frame_rate_arithmetic() {
+        assert_eq!(rate(1, 2) + rate(1, 3), rate(5, 6));
+        assert_eq!(rate(30, 1) / rate(1, 2), rate(60, 1));
+        assert_eq!(rate(30, 1) / rate(-1, 2), rate(-60, 1));
+        assert_eq!(rate(25, 2) % rate(5, 1), rate(5, 2));
+    }
+
+    #[test]
+    fn frame_rate_division_by_zero_does_not_panic() {
+        assert_eq!(rate(30, 1) / FrameRate::ZERO, rate(30, 1));
+        assert_eq!(rate(30, 1) % FrameRate::ZERO, FrameRate::ZERO);
+    }
+
+    #[test]
+    fn frame_rate_subtraction_is_exact() {
+        let difference = rate(30, 1) - rate(30000, 1001);
+        assert_eq!(difference, rate(30, 1001));
+        assert_eq!((*difference.numerator(), *difference.denominator()), (30, 1001));
+        assert_eq!(rate(i32::MIN, 1) - rate(i32::MAX, 1), FrameRate::frame_rate(i32::MIN));
+    }
+
+    #[test]
+    fn as_f32_approximates() {
+        assert!((rate(30000, 1001).as_f32() - 29.97).abs() < 0.01);
+        assert!((rate(-30, 1).as_f32() + 30.0).abs() < f32::EPSILON);
+    }
+
+
+    #[test]
+    fn aspect_ratios_reduce_to_whole_numbers() {
+        assert_eq!(Resolution::new(1920, 1080).aspect_ratio_reduced(), (16, 9));
+        assert_eq!(Resolution::new(640, 480).aspect_ratio_reduced(), (4, 3));
+        assert_eq!(Resolution::new(1366, 768).aspect_ratio_reduced(), (683, 384));
+        assert_eq!(Resolution::new(0, 0).aspect_ratio_reduced(), (0, 0));
+        assert_eq!(Resolution::new(0, 720).aspect_ratio_reduced(), (0, 1));
+    }
+
+
+    #[test]
+    fn frame_rates_from_floats() {
+        let ntsc = FrameRate::from_f32(29.97).unwrap();
+        assert_eq!((*ntsc.numerator(), *ntsc.denominator()), (30000, 1001));
+        let ntsc_film = FrameRate::from_f32(23.976).unwrap();
+        assert_eq!((*ntsc_film.numerator(), *ntsc_film.denominator()), (24000, 1001));
+
+        let whole = FrameRate::from_f32(30.0).unwrap();
+        assert_eq!((*whole.numerator(), *whole.denominator()), (30, 1));
+        // anything else is approximated, and reduced.
+        let half = FrameRate::from_f32(7.5).unwrap();
+        assert_eq!((*half.numerator(), *half.denominator()), (15, 2));
+
+        assert_eq!(FrameRate::from_f32(-1.0), None);
+        assert_eq!(FrameRate::from_f32(f32::NAN), None);
+        assert_eq!(FrameRate::from_f32(f32::INFINITY), None);
+    }
+
+
+    #[test]
+    fn camera_indices_round_trip_through_strings() {
+        for index in [CameraIndex::Index(0), CameraIndex::Index(12), CameraIndex::String("/dev/video2".to_string())] {
+            assert_eq!(index.to_string().parse::<CameraIndex>(), Ok(index));
+        }
+        // a leading zero wouldn't survive `Display`, so it stays a string.
+        assert_eq!("007".parse::<CameraIndex>(), Ok(CameraIndex::String("007".to_string())));
+        assert_eq!("-1".parse::<CameraIndex>(), Ok(CameraIndex::String("-1".to_string())));
+    }
+
+
+    #[test]
+    fn camera_formats_order_by_area_then_frame_rate() {
+        let format = |width, height, fps| CameraFormat::new_from(width, height, FrameFormat::MJpeg, FrameRate::frame_rate(fps));
+        let mut formats = vec![format(1920, 1080, 30), format(640, 480, 60), format(1280, 720, 60), format(1280, 720, 30)];
+        formats.sort();
+
+        assert_eq!(formats, vec![format(640, 480, 60), format(1280, 720, 30), format(1280, 720, 60), format(1920, 1080, 30)]);
+        // the same area in a different shape is still told apart.
+        assert_eq!(format(800, 600, 30).cmp(&format(600, 800, 30)), Ordering::Greater);
+        assert_eq!(format(640, 480, 30).max(format(480, 640, 60)), format(480, 640, 60));
+    }
+
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn camera_formats_round_trip_through_json() {
+        let formats = vec![
+            CameraFormat::new_from(1920, 1080, FrameFormat::MJpeg, rate(30000, 1001)),
+            CameraFormat::new_from(640, 480, FrameFormat::Custom(*b"ABCD\0\0\0\0"), FrameRate::frame_rate(60)),
+        ];
+        let json = serde_json::to_string(&formats).unwrap();
+        assert!(json.contains(r#""frame_rate":{"numerator":30000,"denominator":1001}"#), "{json}");
+
+        let parsed = serde_json::from_str::<Vec<CameraFormat>>(&json).unwrap();
+        assert_eq!(parsed, formats);
+        assert_eq!(*parsed[0].frame_rate().denominator(), 1001);
+
+        let zero = serde_json::from_str::<FrameRate>(r#"{"numerator":30,"denominator":0}"#).unwrap_err();
+        assert!(zero.to_string().contains("zero denominator"), "{zero}");
+    }
+
+
+    #[test]
+    fn stable_ids_ignore_the_driver_version() {
+        let information = |misc: &str, index| {
+            CameraInformation::new("Webcam".to_string(), "uvcvideo".to_string(), misc.to_string(), CameraIndex::Index(index))
+        };
+        let before = information("usb-0000:00:14.0-1 v6.1.0 Flags: 2225078273", 0);
+        let after = information("usb-0000:00:14.0-1 v6.8.12 Flags: 2225078273", 2);
+
+        assert_eq!(before.stable_id(), "usb-0000:00:14.0-1");
+        assert_eq!(before.stable_id(), after.stable_id());
+        assert_ne!(before, after);
+        assert_eq!(StableCameraInformation::from(before), StableCameraInformation::from(after));
+
+        // without a bus path, the index is all there is.
+        assert_eq!(information("v6.1.0 Flags: 0", 3).stable_id(), "3");
+        assert_eq!(information("", 4).stable_id(), "4");
+    }
+
+
+    #[test]
+    fn resolution_distances_are_euclidean() {
+        let vga = Resolution::new(640, 480);
+        assert!(vga.distance_from(&vga).abs() < f64::EPSILON);
+        assert!((vga.distance_from(&Resolution::new(643, 484)) - 5.0).abs() < f64::EPSILON);
+        assert!((Resolution::new(1920, 1080).distance_from(&Resolution::new(1280, 720)) - 734.30).abs() < 0.01);
+        // and symmetric, even when one side is smaller on both axes.
+        assert!((Resolution::new(0, 0).distance_from(&vga) - vga.distance_from(&Resolution::new(0, 0))).abs() < f64::EPSILON);
+        assert_eq!(Resolution::new(0, 0).squared_distance(&vga), 640_000);
+    }
+}
@@ -72,7 +72,7 @@ impl AVFoundationCaptureDevice {
         // device.lock()?;
         let formats = device.supported_formats()?;
         let camera_fmt = req_fmt.fulfill(&formats).ok_or_else(|| {
-            NokhwaError::OpenDeviceError("Cannot fulfill request".to_string(), req_fmt.to_string())
+            NokhwaError::OpenDeviceError("Cannot fulfill request".to_string(), req_fmt.to_string(), None)
         })?;
         device.set_all(camera_fmt)?;
 
@@ -221,6 +221,7 @@ impl CaptureTrait for AVFoundationCaptureDevice {
         Err(NokhwaError::GetPropertyError {
             property: control.to_string(),
             error: "Not Found".to_string(),
+            source: None,
         })
     }
 
@@ -306,6 +307,7 @@ impl CaptureTrait for AVFoundationCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "AVCaptureSession".to_string(),
                     error: "Doesnt Exist".to_string(),
+                    source: None,
                 })
             }
         };
@@ -316,6 +318,7 @@ impl CaptureTrait for AVFoundationCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "AVCaptureVideoDataOutput".to_string(),
                     error: "Doesnt Exist".to_string(),
+                    source: None,
                 })
             }
         };
@@ -326,6 +329,7 @@ impl CaptureTrait for AVFoundationCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "AVCaptureDeviceInput".to_string(),
                     error: "Doesnt Exist".to_string(),
+                    source: None,
                 })
             }
         };
@@ -109,12 +109,12 @@ impl BrowserCaptureDevice {
 
         let (group_id, device_id) = match index {
             CameraIndex::Index(i) => {
-                return Err(NokhwaError::OpenDeviceError(i.to_string(), "Invalid Index".to_string()))
+                return Err(NokhwaError::OpenDeviceError(i.to_string(), "Invalid Index".to_string(), None))
             },
             CameraIndex::String(s) => {
                 match s.split_once(" ") {
                     Some((g, d)) => (g.to_string(), d.to_string()),
-                    None => return Err(NokhwaError::OpenDeviceError(s.to_string(), "Invalid Index".to_string())) ,
+                    None => return Err(NokhwaError::OpenDeviceError(s.to_string(), "Invalid Index".to_string(), None)) ,
                 }
             },
         };
@@ -133,7 +133,7 @@ impl BrowserCaptureDevice {
             Some(v) => {
                 CameraInformation::new(&v.label(), v.kind(), &v.device_id(), index)
             }
-            None => return Err(NokhwaError::OpenDeviceError(index.to_string(), "failed to find MediaDeviceInfo".to_string())),
+            None => return Err(NokhwaError::OpenDeviceError(index.to_string(), "failed to find MediaDeviceInfo".to_string(), None)),
         };
 
         let mut constraint = MediaStreamConstraints::new();
@@ -110,6 +110,7 @@ impl GStreamerCaptureDevice {
                     return Err(NokhwaError::OpenDeviceError(
                         index.to_string(),
                         "No device".to_string(),
+                        None,
                     ))
                 }
             };
@@ -205,6 +206,7 @@ impl GStreamerCaptureDevice {
                                             property: "Capibilities by Resolution: Width"
                                                 .to_string(),
                                             error: why.to_string(),
+                                            source: None,
                                         })
                                     }
                                 };
@@ -215,6 +217,7 @@ impl GStreamerCaptureDevice {
                                             property: "Capibilities by Resolution: Height"
                                                 .to_string(),
                                             error: why.to_string(),
+                                            source: None,
                                         })
                                     }
                                 };
@@ -232,6 +235,7 @@ impl GStreamerCaptureDevice {
                                                     "Failed to make framerates into string: {}",
                                                     why
                                                 ),
+                                                source: None,
                                             });
                                         }
                                     },
@@ -240,6 +244,7 @@ impl GStreamerCaptureDevice {
                                             property: "Framerates".to_string(),
                                             error: "Failed to get framerates: doesnt exist!"
                                                 .to_string(),
+                                            source: None,
                                         })
                                     }
                                 };
@@ -248,7 +253,7 @@ impl GStreamerCaptureDevice {
                                     let fraction_string: Vec<&str> =
                                         m.as_str().split('/').collect();
                                     if fraction_string.len() != 2 {
-                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: format!("Fraction framerate had more than one demoninator: {:?}", fraction_string) });
+                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: format!("Fraction framerate had more than one demoninator: {:?}", fraction_string), source: None });
                                     }
 
                                     if let Some(v) = fraction_string.get(1) {
@@ -256,7 +261,7 @@ impl GStreamerCaptureDevice {
                                             continue; // swallow error
                                         }
                                     } else {
-                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate denominator? Shouldn't happen, please report!".to_string() });
+                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate denominator? Shouldn't happen, please report!".to_string(), source: None });
                                     }
 
                                     if let Some(numerator) = fraction_string.get(0) {
@@ -269,11 +274,12 @@ impl GStreamerCaptureDevice {
                                                         "Failed to parse numerator: {}",
                                                         why
                                                     ),
+                                                    source: None,
                                                 });
                                             }
                                         }
                                     } else {
-                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate numerator? Shouldn't happen, please report!".to_string() });
+                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate numerator? Shouldn't happen, please report!".to_string(), source: None });
                                     }
                                 }
                                 resolution_map
@@ -293,6 +299,7 @@ impl GStreamerCaptureDevice {
                                             property: "Capibilities by Resolution: Width"
                                                 .to_string(),
                                             error: why.to_string(),
+                                            source: None,
                                         })
                                     }
                                 };
@@ -303,6 +310,7 @@ impl GStreamerCaptureDevice {
                                             property: "Capibilities by Resolution: Height"
                                                 .to_string(),
                                             error: why.to_string(),
+                                            source: None,
                                         })
                                     }
                                 };
@@ -320,6 +328,7 @@ impl GStreamerCaptureDevice {
                                                     "Failed to make framerates into string: {}",
                                                     why
                                                 ),
+                                                source: None,
                                             });
                                         }
                                     },
@@ -328,6 +337,7 @@ impl GStreamerCaptureDevice {
                                             property: "Framerates".to_string(),
                                             error: "Failed to get framerates: doesnt exist!"
                                                 .to_string(),
+                                            source: None,
                                         })
                                     }
                                 };
@@ -336,7 +346,7 @@ impl GStreamerCaptureDevice {
                                     let fraction_string: Vec<&str> =
                                         m.as_str().split('/').collect();
                                     if fraction_string.len() != 2 {
-                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: format!("Fraction framerate had more than one demoninator: {:?}", fraction_string) });
+                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: format!("Fraction framerate had more than one demoninator: {:?}", fraction_string), source: None });
                                     }
 
                                     if let Some(v) = fraction_string.get(1) {
@@ -344,7 +354,7 @@ impl GStreamerCaptureDevice {
                                             continue; // swallow error
                                         }
                                     } else {
-                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate denominator? Shouldn't happen, please report!".to_string() });
+                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate denominator? Shouldn't happen, please report!".to_string(), source: None });
                                     }
 
                                     if let Some(numerator) = fraction_string.get(0) {
@@ -357,11 +367,12 @@ impl GStreamerCaptureDevice {
                                                         "Failed to parse numerator: {}",
                                                         why
                                                     ),
+                                                    source: None,
                                                 });
                                             }
                                         }
                                     } else {
-                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate numerator? Shouldn't happen, please report!".to_string() });
+                                        return Err(NokhwaError::GetPropertyError { property: "Framerates".to_string(), error: "No framerate numerator? Shouldn't happen, please report!".to_string(), source: None });
                                     }
                                 }
                                 resolution_map
@@ -380,6 +391,7 @@ impl GStreamerCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "Device Caps".to_string(),
                     error: "No device caps!".to_string(),
+                    source: None,
                 })
             }
         }
@@ -405,6 +417,7 @@ impl GStreamerCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "Device Caps".to_string(),
                     error: "No device caps!".to_string(),
+                    source: None,
                 })
             }
         }
@@ -631,6 +644,7 @@ fn generate_pipeline(fmt: CameraFormat, index: usize) -> Result<PipelineGenRet,
                         webcam_pipeline(format!("{}", index).as_str(), fmt),
                         why
                     ),
+                    None,
                 ))
             }
         };
@@ -646,6 +660,7 @@ fn generate_pipeline(fmt: CameraFormat, index: usize) -> Result<PipelineGenRet,
             return Err(NokhwaError::OpenDeviceError(
                 index.to_string(),
                 "Failed to get sink element!".to_string(),
+                None,
             ))
         }
     };
@@ -656,6 +671,7 @@ fn generate_pipeline(fmt: CameraFormat, index: usize) -> Result<PipelineGenRet,
             return Err(NokhwaError::OpenDeviceError(
                 index.to_string(),
                 "Failed to get sink element as appsink".to_string(),
+                None,
             ))
         }
     };
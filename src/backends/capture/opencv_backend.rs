@@ -112,7 +112,7 @@ impl OpenCvCaptureDevice {
             CameraIndex::String(ip) => VideoCapture::from_file(ip.as_str(), api_pref),
         }
         .map_err(|why| {
-            NokhwaError::OpenDeviceError(format!("Failed to open {index}"), why.to_string())
+            NokhwaError::OpenDeviceError(format!("Failed to open {index}"), why.to_string(), None)
         })?;
 
         let camera_format =
@@ -251,6 +251,7 @@ impl OpenCvCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "Width".to_string(),
                     error: why.to_string(),
+                    source: None,
                 })
             }
         };
@@ -261,6 +262,7 @@ impl OpenCvCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "Height".to_string(),
                     error: why.to_string(),
+                    source: None,
                 })
             }
         };
@@ -279,6 +281,7 @@ impl OpenCvCaptureDevice {
             Err(why) => Err(NokhwaError::GetPropertyError {
                 property: "Framerate".to_string(),
                 error: why.to_string(),
+                source: None,
             }),
         }
     }
@@ -302,6 +305,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                     property: "Resolution Width".to_string(),
                     value: self.camera_format.to_string(),
                     error: why.to_string(),
+                    source: None,
                 })?,
         );
         let height = self
@@ -311,6 +315,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                 property: "Resolution Height".to_string(),
                 value: self.camera_format.to_string(),
                 error: why.to_string(),
+                source: None,
             })? as u32;
         let fps = self
             .video_capture
@@ -319,6 +324,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                 property: "FPS".to_string(),
                 value: self.camera_format.to_string(),
                 error: why.to_string(),
+                source: None,
             })? as u32;
 
         let ffmt = self.frame_format();
@@ -339,6 +345,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                 return Err(NokhwaError::GetPropertyError {
                     property: "Is Stream Open".to_string(),
                     error: why.to_string(),
+                    source: None,
                 })
             }
         };
@@ -355,6 +362,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                 return Err(NokhwaError::OpenDeviceError(
                     self.camera_location.to_string(),
                     why.to_string(),
+                    None,
                 ));
             }
         }
@@ -411,6 +419,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: id.to_string(),
                 error: why.to_string(),
+                source: None,
             })?;
         Ok(CameraControl::new(
             control,
@@ -445,6 +454,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                     property: "Camera Control".to_string(),
                     value: val.to_string(),
                     error: "unsupported value".to_string(),
+                    source: None,
                 })
             }
         };
@@ -459,12 +469,14 @@ impl CaptureTrait for OpenCvCaptureDevice {
                 property: "Camera Control".to_string(),
                 value: control_val.to_string(),
                 error: why.to_string(),
+                source: None,
             })?
         {
             return Err(NokhwaError::SetPropertyError {
                 property: "Camera Control".to_string(),
                 value: control_val.to_string(),
                 error: "false".to_string(),
+                source: None,
             });
         }
 
@@ -474,6 +486,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
                 property: "Camera Control".to_string(),
                 value: control_val.to_string(),
                 error: "failed to set value: rejected".to_string(),
+                source: None,
             });
         }
 
@@ -496,12 +509,14 @@ impl CaptureTrait for OpenCvCaptureDevice {
                     Err(why) => Err(NokhwaError::OpenDeviceError(
                         idx.to_string(),
                         format!("Failed to open device: {why}"),
+                        None,
                     )),
                 }
             }
             CameraIndex::String(_) => Err(NokhwaError::OpenDeviceError(
                 "Cannot open".to_string(),
                 "String index not supported (try NetworkCamera instead)".to_string(),
+                None,
             )),
         }?;
 
@@ -517,6 +532,7 @@ impl CaptureTrait for OpenCvCaptureDevice {
             Err(why) => Err(NokhwaError::GetPropertyError {
                 property: "Is Stream Open After Open Stream".to_string(),
                 error: why.to_string(),
+                source: None,
             }),
         }
     }
@@ -573,12 +589,14 @@ fn set_properties(vc: &mut VideoCapture, camera_format: CameraFormat) -> Result<
             property: "Resolution Width".to_string(),
             value: camera_format.to_string(),
             error: why.to_string(),
+            source: None,
         })?
     {
         return Err(NokhwaError::SetPropertyError {
             property: "Resolution Width".to_string(),
             value: camera_format.to_string(),
             error: "false".to_string(),
+            source: None,
         });
     }
     if !vc
@@ -587,12 +605,14 @@ fn set_properties(vc: &mut VideoCapture, camera_format: CameraFormat) -> Result<
             property: "Resolution Height".to_string(),
             value: camera_format.to_string(),
             error: why.to_string(),
+            source: None,
         })?
     {
         return Err(NokhwaError::SetPropertyError {
             property: "Resolution Height".to_string(),
             value: camera_format.to_string(),
             error: "false".to_string(),
+            source: None,
         });
     }
     if !vc
@@ -601,12 +621,14 @@ fn set_properties(vc: &mut VideoCapture, camera_format: CameraFormat) -> Result<
             property: "FPS".to_string(),
             value: camera_format.to_string(),
             error: why.to_string(),
+            source: None,
         })?
     {
         return Err(NokhwaError::SetPropertyError {
             property: "FPS".to_string(),
             value: camera_format.to_string(),
             error: "false".to_string(),
+            source: None,
         });
     }
     Ok(())
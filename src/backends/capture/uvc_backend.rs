@@ -90,6 +90,7 @@ impl<'a> UVCCaptureDevice<'a> {
                 return Err(NokhwaError::OpenDeviceError(
                     index.to_string(),
                     why.to_string(),
+                    None,
                 ))
             }
         };
@@ -101,6 +102,7 @@ impl<'a> UVCCaptureDevice<'a> {
                     return Err(NokhwaError::OpenDeviceError(
                         index.to_string(),
                         why.to_string(),
+                        None,
                     ))
                 }
             };
@@ -111,6 +113,7 @@ impl<'a> UVCCaptureDevice<'a> {
                     return Err(NokhwaError::OpenDeviceError(
                         index.to_string(),
                         "Not Found".to_string(),
+                        None,
                     ))
                 }
             };
@@ -121,6 +124,7 @@ impl<'a> UVCCaptureDevice<'a> {
                     return Err(NokhwaError::OpenDeviceError(
                         index.to_string(),
                         why.to_string(),
+                        None,
                     ))
                 }
             };
@@ -236,6 +240,7 @@ impl<'a> CaptureBackendTrait for UVCCaptureDevice<'a> {
                         property: "CameraFormat".to_string(),
                         value: new_fmt.to_string(),
                         error: why.to_string(),
+                        source: None,
                     })
                 }
             };
@@ -355,17 +360,20 @@ impl<'a> CaptureBackendTrait for UVCCaptureDevice<'a> {
                         Err(why) => Err(NokhwaError::GetPropertyError {
                             property: control.to_string(),
                             error: why.to_string(),
+                            source: None,
                         }),
                     }
                 }
                 Err(why) => Err(NokhwaError::GetPropertyError {
                     property: control.to_string(),
                     error: why.to_string(),
+                    source: None,
                 }),
             },
             _ => Err(NokhwaError::GetPropertyError {
                 property: control.to_string(),
                 error: "Not Supported".to_string(),
+                source: None,
             }),
         }
     }
@@ -30,8 +30,8 @@ pub struct V4L2CaptureDevice {
 
 impl Open for V4L2CaptureDevice {
     fn open(index: CameraIndex) -> NokhwaResult<Self> {
-        let device = DeviceInner::new(index.as_index()? as usize).map_err(|why| NokhwaError::OpenDeviceError(index.to_string(), why.to_string()))?;
-        let caps = device.inner().query_caps().map_err(|why| NokhwaError::OpenDeviceError(index.to_string(), why.to_string()))?;
+        let device = DeviceInner::new(index.as_index()? as usize).map_err(|why| NokhwaError::OpenDeviceError(index.to_string(), why.to_string(), None))?;
+        let caps = device.inner().query_caps().map_err(|why| NokhwaError::OpenDeviceError(index.to_string(), why.to_string(), None))?;
         let camera_info = CameraInformation::new(caps.card, caps.bus, caps.driver, index);
         Ok(Self {
             device_inner: Arc::new(device),
@@ -45,7 +45,7 @@ impl Open for V4L2CaptureDevice {
 
 impl Setting for V4L2CaptureDevice {
     fn enumerate_formats(&self) -> Result<Vec<CameraFormat>, NokhwaError> {
-        let formats_fourcc = self.device_inner.inner().enum_formats().map_err(|why| NokhwaError::GetPropertyError { property: "enum_formats".to_string(), error: why.to_string() })?.into_iter().map(|desc| desc.fourcc).collect::<Vec<FourCC>>();
+        let formats_fourcc = self.device_inner.inner().enum_formats().map_err(|why| NokhwaError::GetPropertyError { property: "enum_formats".to_string(), error: why.to_string(), source: None })?.into_iter().map(|desc| desc.fourcc).collect::<Vec<FourCC>>();
         let mut camera_formats = vec![];
 
         for fourcc in formats_fourcc {
@@ -64,7 +64,7 @@ impl Setting for V4L2CaptureDevice {
     fn enumerate_resolution_and_frame_rates(&self, frame_format: FrameFormat) -> Result<HashMap<Resolution, Vec<FrameRate>>, NokhwaError> {
         let fourcc = match FrameFormatIntermediate::from_frame_format(frame_format) {
             Some(v) => v,
-            None => return Err(NokhwaError::GetPropertyError { property: "enumerate_resolution_and_frame_rates".to_string(), error: "Unsupported FourCC".to_string() }),
+            None => return Err(NokhwaError::GetPropertyError { property: "enumerate_resolution_and_frame_rates".to_string(), error: "Unsupported FourCC".to_string(), source: None }),
         };
         let mut resolutions_and_frame_rates = HashMap::new();
         for resolution in self.device_inner.resolutions(fourcc.0.into())? {
@@ -78,7 +78,7 @@ impl Setting for V4L2CaptureDevice {
     fn set_format(&self, camera_format: CameraFormat) -> Result<(), NokhwaError> {
         let fourcc = match FrameFormatIntermediate::from_frame_format(camera_format.format()) {
             Some(v) => v,
-            None => return Err(NokhwaError::GetPropertyError { property: "set_format".to_string(), error: "Unsupported FourCC".to_string() }),
+            None => return Err(NokhwaError::GetPropertyError { property: "set_format".to_string(), error: "Unsupported FourCC".to_string(), source: None }),
         };
 
         let format = Format::new(camera_format.width(), camera_format.height(), FourCC::new(&fourcc.0));
@@ -90,6 +90,7 @@ impl Setting for V4L2CaptureDevice {
                 property: "set_format".to_string(),
                 value: camera_format.to_string(),
                 error: why.to_string(),
+                source: None,
             })
         })?;
 
@@ -98,13 +99,14 @@ impl Setting for V4L2CaptureDevice {
                 property: "set_params".to_string(),
                 value: camera_format.to_string(),
                 error: why.to_string(),
+                source: None,
             })
         })?;
     }
 
     fn properties(&self) -> &CameraProperties {
         let ctrls = self.device_inner.inner().query_controls().map_err(|why| {
-            Err(NokhwaError::GetPropertyError { property: "query_controls".to_string(), error: why.to_string() })
+            Err(NokhwaError::GetPropertyError { property: "query_controls".to_string(), error: why.to_string(), source: None })
         })?.into_iter().map(|desc| {
             match v4l2_sys_mit::
         });
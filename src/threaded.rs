@@ -80,6 +80,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "CameraInfo".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .info()
             .clone();
@@ -135,6 +136,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "CameraInfo".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .info()
             .clone();
@@ -200,6 +202,7 @@ impl CallbackCamera {
                 property: "CameraFormat".to_string(),
                 value: "CameraFormat".to_string(),
                 error: "Requested Format Not Consistant".to_string(),
+                source: None,
             });
         }
         Ok(())
@@ -253,6 +256,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "Resolution".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .resolution())
     }
@@ -273,6 +277,7 @@ impl CallbackCamera {
                 property: "Resolution".to_string(),
                 value: new_res.to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .set_resolution(new_res)
     }
@@ -285,6 +290,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "Framerate".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .frame_rate())
     }
@@ -300,6 +306,7 @@ impl CallbackCamera {
                 property: "Framerate".to_string(),
                 value: new_fps.to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .set_frame_rate(new_fps)
     }
@@ -312,6 +319,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "Frameformat".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .frame_format())
     }
@@ -327,6 +335,7 @@ impl CallbackCamera {
                 property: "Framerate".to_string(),
                 value: fourcc.to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .set_frame_format(fourcc)
     }
@@ -340,6 +349,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "Supported Camera Controls".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .supported_camera_controls()
     }
@@ -414,6 +424,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "Camera Control".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .camera_control(control)
     }
@@ -435,6 +446,7 @@ impl CallbackCamera {
                 property: "Camera Control".to_string(),
                 value: format!("{}: {}", id, control),
                 error: why.to_string(),
+                source: None,
             })?
             .set_camera_control(id, control)
     }
@@ -450,6 +462,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "thread handle".to_string(),
                 error: why.to_string(),
+                source: None,
             })?;
         if handle_lock.is_none() {
             self.camera
@@ -458,6 +471,7 @@ impl CallbackCamera {
                     property: "camera".to_string(),
                     value: "callback".to_string(),
                     error: why.to_string(),
+                    source: None,
                 })?
                 .open_stream()?;
             let die_bool_clone = self.die_bool.clone();
@@ -487,6 +501,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "frame_callback".to_string(),
                 error: why.to_string(),
+                source: None,
             })? = Box::new(callback);
         Ok(())
     }
@@ -524,6 +539,7 @@ impl CallbackCamera {
             .map_err(|why| NokhwaError::GetPropertyError {
                 property: "is stream open".to_string(),
                 error: why.to_string(),
+                source: None,
             })?
             .is_stream_open())
     }